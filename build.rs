@@ -0,0 +1,21 @@
+//! Generates netifaces.h, the header of the C ABI, in OUT_DIR when the `capi` feature is
+//! on. A build script may not write to the sources: the committed include/netifaces.h is
+//! checked against it by a test of src/capi.rs.
+
+fn main() {
+    #[cfg(feature = "capi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml")).unwrap();
+
+        cbindgen::Builder::new()
+            .with_src(format!("{crate_dir}/src/capi.rs"))
+            .with_config(config)
+            .generate()
+            .expect("Unable to generate the C header")
+            .write_to_file(format!("{}/netifaces.h", std::env::var("OUT_DIR").unwrap()));
+
+        println!("cargo:rerun-if-changed=src/capi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+    }
+}
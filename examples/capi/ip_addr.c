@@ -0,0 +1,74 @@
+/*
+ * Rudimentary replica of the `ip addr` tool, implemented with the C ABI of netifaces.
+ *
+ * Build and run it from the root of the repository with:
+ *
+ *     cargo build --release --no-default-features --features capi
+ *     cc -Iinclude examples/capi/ip_addr.c -Ltarget/release -lnetifaces -o target/ip_addr
+ *     LD_LIBRARY_PATH=target/release target/ip_addr
+ */
+
+#include <stdio.h>
+
+#include "netifaces.h"
+
+static void print_interface(const netifaces_interface *interface) {
+    printf("%u: %s: %s", netifaces_interface_index(interface), netifaces_interface_name(interface),
+           netifaces_interface_is_up(interface) ? "UP" : "DOWN");
+    if (netifaces_interface_mtu(interface) != 0) {
+        printf(" mtu %u", netifaces_interface_mtu(interface));
+    }
+    printf("\n");
+
+    const uint8_t *hardware_address;
+    size_t length = netifaces_interface_hardware_address(interface, &hardware_address);
+    if (length != 0) {
+        printf("    link ");
+        for (size_t i = 0; i < length; i++) {
+            printf(i + 1 < length ? "%02x:" : "%02x\n", hardware_address[i]);
+        }
+    }
+
+    netifaces_address_iter *addresses = netifaces_interface_addresses(interface);
+    const netifaces_address *address;
+    while ((address = netifaces_address_iter_next(addresses)) != NULL) {
+        printf("    %s %s", netifaces_address_version(address) == 4 ? "inet" : "inet6",
+               netifaces_address_string(address));
+        if (netifaces_address_prefix_len(address) >= 0) {
+            printf("/%d", netifaces_address_prefix_len(address));
+        }
+        if (netifaces_address_peer(address) != NULL) {
+            printf(" peer %s", netifaces_address_peer(address));
+        }
+        if (netifaces_address_broadcast(address) != NULL) {
+            printf(" brd %s", netifaces_address_broadcast(address));
+        }
+        printf("\n");
+    }
+    netifaces_address_iter_free(addresses);
+}
+
+int main(void) {
+    if (netifaces_abi_version() != NETIFACES_ABI_VERSION) {
+        fprintf(stderr, "libnetifaces implements version %u of the ABI, not %u\n", netifaces_abi_version(),
+                NETIFACES_ABI_VERSION);
+        return 1;
+    }
+
+    netifaces_snapshot *snapshot;
+    netifaces_status status = netifaces_snapshot_new(&snapshot);
+    if (status != NETIFACES_OK) {
+        fprintf(stderr, "%s (status %d, errno %d)\n", netifaces_last_error(), status, netifaces_last_errno());
+        return 1;
+    }
+
+    netifaces_interface_iter *interfaces = netifaces_snapshot_interfaces(snapshot);
+    const netifaces_interface *interface;
+    while ((interface = netifaces_interface_iter_next(interfaces)) != NULL) {
+        print_interface(interface);
+    }
+    netifaces_interface_iter_free(interfaces);
+
+    netifaces_snapshot_free(snapshot);
+    return 0;
+}
@@ -0,0 +1,42 @@
+//! Rudimentary replica of the `ip addr` tool, implemented with the Rust API of netifaces.
+//!
+//! Run it with `cargo run --no-default-features --example ip_addr`.
+
+fn main() -> Result<(), netifaces::NetifacesError> {
+    for interface in netifaces::interfaces()? {
+        let state = if interface.up { "UP" } else { "DOWN" };
+        print!("{}: {}: {state}", interface.index, interface.name);
+        if let Some(mtu) = interface.mtu {
+            print!(" mtu {mtu}");
+        }
+        println!();
+
+        if let Some(link_address) = &interface.link_address {
+            print!("    link {}", link_address.address);
+            if let Some(broadcast) = &link_address.broadcast {
+                print!(" brd {broadcast}");
+            }
+            println!();
+        }
+        for address in &interface.addresses {
+            let family = if address.ip.is_ipv4() {
+                "inet"
+            } else {
+                "inet6"
+            };
+            match address.prefix_len {
+                Some(prefix_len) => print!("    {family} {}/{prefix_len}", address.ip),
+                None => print!("    {family} {}", address.ip),
+            }
+            if let Some(peer) = address.peer {
+                print!(" peer {peer}");
+            }
+            if let Some(broadcast) = address.broadcast {
+                print!(" brd {broadcast}");
+            }
+            println!();
+        }
+    }
+
+    Ok(())
+}
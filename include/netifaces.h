@@ -0,0 +1,156 @@
+/*
+ * netifaces: the network interfaces of the system and their addresses.
+ *
+ * Link against libnetifaces, built with `cargo build --release --no-default-features --features capi`.
+ *
+ * A snapshot owns every interface, address, string and byte array it hands out: they stay valid until
+ * netifaces_snapshot_free. Iterators have to be released before their snapshot. Given NULL where an object
+ * is expected, the functions answer NULL, 0, -1 or false (the *_free ones ignore it), and
+ * netifaces_snapshot_new returns NETIFACES_INVALID_ARGUMENT.
+ *
+ * The functions are thread-safe, but a snapshot and its iterators must not be used by two threads at once.
+ */
+
+#ifndef NETIFACES_H
+#define NETIFACES_H
+
+/* Generated by cbindgen from src/capi.rs with the `capi` feature: do not edit */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// The version of the ABI, only raised by incompatible changes.
+#define NETIFACES_ABI_VERSION 1
+
+// The outcome of a call, which mirrors the exceptions of the Python module.
+typedef enum netifaces_status {
+  NETIFACES_OK = 0,
+  // No interface has that name or index
+  NETIFACES_INTERFACE_NOT_FOUND = 1,
+  // More than a single interface is displayed with that name
+  NETIFACES_AMBIGUOUS_INTERFACE = 2,
+  // The system refused the request
+  NETIFACES_PERMISSION_DENIED = 3,
+  // The operation is not available on this platform
+  NETIFACES_UNSUPPORTED = 4,
+  // The backend cannot be used, e.g. the kernel was built without netlink
+  NETIFACES_BACKEND_UNAVAILABLE = 5,
+  // Any other failure
+  NETIFACES_ERROR = 6,
+  // A required pointer was NULL
+  NETIFACES_INVALID_ARGUMENT = 7,
+} netifaces_status;
+
+// An IP address of an interface.
+typedef struct netifaces_address netifaces_address;
+
+// Goes over the addresses of an interface.
+typedef struct netifaces_address_iter netifaces_address_iter;
+
+// An interface of a snapshot.
+typedef struct netifaces_interface netifaces_interface;
+
+// Goes over the interfaces of a snapshot, by index.
+typedef struct netifaces_interface_iter netifaces_interface_iter;
+
+// Every interface of the system, with their addresses, as of one point in time.
+typedef struct netifaces_snapshot netifaces_snapshot;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// The version of the ABI the library implements, NETIFACES_ABI_VERSION.
+uint32_t netifaces_abi_version(void);
+
+// The message of the last call of this thread that failed, or NULL. It stays valid
+// until the next failure on the thread.
+const char *netifaces_last_error(void);
+
+// The errno of the system call behind the last failure of this thread, or 0.
+int netifaces_last_errno(void);
+
+// Take a snapshot of the interfaces of the system. On success, `*snapshot` has to be
+// released with netifaces_snapshot_free.
+enum netifaces_status netifaces_snapshot_new(struct netifaces_snapshot **snapshot);
+
+// Release a snapshot, along with its interfaces and addresses. NULL is ignored.
+void netifaces_snapshot_free(struct netifaces_snapshot *snapshot);
+
+// The number of interfaces of a snapshot; 0 for NULL.
+size_t netifaces_snapshot_len(const struct netifaces_snapshot *snapshot);
+
+// The interface of a snapshot with the given name, or NULL.
+const struct netifaces_interface *netifaces_snapshot_find(const struct netifaces_snapshot *snapshot, const char *name);
+
+// Iterate over the interfaces of a snapshot, by index. The iterator has to be released
+// with netifaces_interface_iter_free, before the snapshot. NULL for a NULL snapshot.
+struct netifaces_interface_iter *netifaces_snapshot_interfaces(const struct netifaces_snapshot *snapshot);
+
+// The next interface, or NULL once they have all been seen.
+const struct netifaces_interface *netifaces_interface_iter_next(struct netifaces_interface_iter *iter);
+
+// Release an iterator over interfaces. NULL is ignored.
+void netifaces_interface_iter_free(struct netifaces_interface_iter *iter);
+
+// The name of the interface.
+const char *netifaces_interface_name(const struct netifaces_interface *interface);
+
+// The index of the interface.
+uint32_t netifaces_interface_index(const struct netifaces_interface *interface);
+
+// The IFF_* flags of the interface; always 0 on Windows.
+uint32_t netifaces_interface_flags(const struct netifaces_interface *interface);
+
+// Whether the interface is up and can pass traffic.
+bool netifaces_interface_is_up(const struct netifaces_interface *interface);
+
+// The MTU of the interface, or 0 if it is unknown.
+uint32_t netifaces_interface_mtu(const struct netifaces_interface *interface);
+
+// The length of the hardware address of the interface, which `*address` is pointed
+// to; 0 if it has none.
+size_t netifaces_interface_hardware_address(const struct netifaces_interface *interface, const uint8_t **address);
+
+// Iterate over the IP addresses of an interface. The iterator has to be released with
+// netifaces_address_iter_free, before the snapshot. NULL for a NULL interface.
+struct netifaces_address_iter *netifaces_interface_addresses(const struct netifaces_interface *interface);
+
+// The next address, or NULL once they have all been seen.
+const struct netifaces_address *netifaces_address_iter_next(struct netifaces_address_iter *iter);
+
+// Release an iterator over addresses. NULL is ignored.
+void netifaces_address_iter_free(struct netifaces_address_iter *iter);
+
+// The IP version of the address, 4 or 6; 0 for NULL.
+uint8_t netifaces_address_version(const struct netifaces_address *address);
+
+// The length of the address in network order (4 or 16 bytes), which `*octets` is
+// pointed to.
+size_t netifaces_address_octets(const struct netifaces_address *address, const uint8_t **octets);
+
+// The address, in its text form, without the zone of link-local IPv6 addresses.
+const char *netifaces_address_string(const struct netifaces_address *address);
+
+// The zone of a link-local IPv6 address, which is the index of its interface; 0 for
+// the other addresses.
+uint32_t netifaces_address_scope_id(const struct netifaces_address *address);
+
+// The length of the netmask of the address, or -1 if it is unknown.
+int netifaces_address_prefix_len(const struct netifaces_address *address);
+
+// The broadcast address, in its text form, or NULL.
+const char *netifaces_address_broadcast(const struct netifaces_address *address);
+
+// The other end of a point-to-point link, in its text form, or NULL.
+const char *netifaces_address_peer(const struct netifaces_address *address);
+
+// The IFA_F_* flags of the address; always 0 outside of Linux.
+uint32_t netifaces_address_flags(const struct netifaces_address *address);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* NETIFACES_H */
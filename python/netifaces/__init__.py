@@ -61,12 +61,59 @@ from .defs import (
     AF_X25,
     AF_XDP,
     Addresses,
+    AddressOwner,
     DefaultGatewayEntry,
     GatewaysTable,
     InterfaceName,
+    InterfaceRef,
+    InterfaceSnapshot,
+    InterfaceStats,
     InterfaceType,
+    NeighbourEntry,
+    NextHopEntry,
+    PackedAddresses,
+    ProcRouteEntry,
+    RouteEntry,
 )
-from .netifaces import _ifaddresses, _interface_is_up, _interfaces, _interfaces_by_index
+from .backends import available_backends, get_backend, set_backend
+from .cache import CachedDict, CachedList, cache_generation, disable_cache, enable_cache
+from .errors import (
+    AmbiguousInterfaceError,
+    BackendUnavailableError,
+    InterfaceNotFoundError,
+    NetifacesError,
+    PermissionDeniedError,
+    UnsupportedOperationError,
+)
+from .netifaces import Address, Interface, NextHop, Route
+from .netifaces import (
+    _all_interfaces,
+    _cached_ifaddresses,
+    _cached_interfaces,
+    _cached_interfaces_by_index,
+    _cached_routes,
+    _default_routes,
+    _get_interface,
+    _if_indextoname,
+    _if_nametoindex,
+    _ifaddresses,
+    _interface_for_address,
+    _interface_is_up,
+    _interface_routes,
+    _interfaces,
+    _interfaces_by_index,
+    _interfaces_in_network,
+    _neighbours,
+    _packed_addresses,
+    _primary_interface,
+    _proc_routes,
+    _route_get,
+    _routes,
+    _wait_for_interface,
+    _wait_for_network_online,
+)
+from .routing import routes_from_entries, routes_from_proc, routes_parse_ip_tool
+from .session import NetlinkSession
 
 __all__ = [
     "InterfaceType",
@@ -120,6 +167,44 @@ __all__ = [
     "AF_MCTP",
     "AF_MAX",
     "AF_LINK",
+    "WaitCondition",
+    "wait_for_interface",
+    "wait_for_network_online",
+    "snapshot",
+    "NetlinkSession",
+    "routes",
+    "neighbours",
+    "enable_cache",
+    "disable_cache",
+    "cache_generation",
+    "CachedList",
+    "CachedDict",
+    "interface_routes",
+    "if_nametoindex",
+    "if_indextoname",
+    "interface_for_address",
+    "interfaces_in_network",
+    "route_get",
+    "default_routes",
+    "primary_interface",
+    "proc_routes",
+    "Interface",
+    "Address",
+    "Route",
+    "NextHop",
+    "all_interfaces",
+    "get_interface",
+    "all_routes",
+    "packed_addresses",
+    "available_backends",
+    "get_backend",
+    "set_backend",
+    "NetifacesError",
+    "InterfaceNotFoundError",
+    "AmbiguousInterfaceError",
+    "PermissionDeniedError",
+    "UnsupportedOperationError",
+    "BackendUnavailableError",
 ]
 
 
@@ -134,13 +219,45 @@ class InterfaceDisplay(enum.Enum):
     human-readable name of the network adapter, such as 'Realtek PCIe GbE
     Family Controller'.  This enum is used to select between the two.
 
-    On POSIX platforms, there is really only one format used for interface names:
-    the device name, e.g. "lo" or "eno1".  This format is always returned
-    regardless of the InterfaceDisplay value passed.
+    On Linux, the device name, e.g. "lo" or "eno1", is the machine-readable
+    name.  The human-readable name is the description set with
+    "ip link set <name> alias <description>", or the device name if there is
+    none.  Interfaces can also have alternative names, added with
+    "ip link property add dev <name> altname <altname>".
+
+    On other POSIX platforms, there is really only one format used for interface
+    names: the device name.  This format is always returned regardless of the
+    InterfaceDisplay value passed.
+
+    Two more formats are available on Linux and Windows:
+
+    - AltName: the first alternative name on Linux, or the device name if there
+      is none; the friendly name, such as 'Ethernet', on Windows
+    - Index: the index of the interface, as a string
+
+    Functions taking an interface name, such as ifaddresses(), accept any of the
+    names an interface can be displayed with.
     """
 
     HumanReadable = 0
     MachineReadable = 1
+    AltName = 2
+    Index = 3
+
+
+class WaitCondition(enum.Enum):
+    """
+    Enumeration of the conditions wait_for_interface() can wait for.
+
+    - Present: the interface exists
+    - Up: the interface is up and can pass traffic, as reported by interface_is_up()
+    - HasAddress: the interface has a global address which is done with duplicate
+      address detection (i.e. not tentative), and can be used as a source address
+    """
+
+    Present = 0
+    Up = 1
+    HasAddress = 2
 
 
 logger = logging.getLogger(__name__)
@@ -150,23 +267,93 @@ _platform = sys.platform
 _NIX_ROUTE_FILE = Path("/proc/net/route")
 
 
+def _interface_filter(
+    up: bool,
+    exclude_loopback: bool,
+    physical: bool,
+    has_address: Optional[InterfaceType],
+    kind: Optional[str],
+    name: Optional[str],
+    name_regex: Optional[str],
+    master: Optional[str],
+) -> Dict[str, object]:
+    return {
+        "up": up,
+        "exclude_loopback": exclude_loopback,
+        "physical": physical,
+        "has_address": None if has_address is None else int(has_address),
+        "kind": kind,
+        "name": name,
+        "name_regex": name_regex,
+        "master": master,
+    }
+
+
 def interfaces(
     display: InterfaceDisplay = InterfaceDisplay.HumanReadable,
+    *,
+    up: bool = False,
+    exclude_loopback: bool = False,
+    physical: bool = False,
+    has_address: Optional[InterfaceType] = None,
+    kind: Optional[str] = None,
+    name: Optional[str] = None,
+    name_regex: Optional[str] = None,
+    master: Optional[str] = None,
 ) -> List[InterfaceName]:
     """
-    List the network interfaces that are available
+    List the network interfaces that are available, sorted by index
+
+    Every interface is listed, including the ones without any address. IPv4 label
+    aliases such as "eth0:1" are not interfaces: they are not listed, and their
+    addresses are reported by `ifaddresses()` along with their interface's.
+
+    The keyword arguments filter the interfaces; only the interfaces passing all of
+    them are listed. The filtering is done while enumerating the interfaces.
 
     :param display: Hint for how to display the interface names.
                     See the `InterfaceDisplay` enum for the values. By default,
                     human-readable.
+    :param up: only the interfaces that are up, as reported by `interface_is_up()`
+    :param exclude_loopback: leave out the loopback interfaces
+    :param physical: only the interfaces backed by a hardware device (Linux only)
+    :param has_address: only the interfaces with an address in this family,
+                        e.g. AF_INET
+    :param kind: only the interfaces of this link kind, as shown by
+                 "ip -details link", e.g. "bridge" or "veth" (Linux only)
+    :param name: only the interfaces whose name matches this shell-style pattern,
+                 e.g. "eth*". On Linux, the device name is matched whatever the
+                 display.
+    :param name_regex: only the interfaces whose name matches this regular
+                       expression, anywhere in the name unless anchored
+    :param master: only the interfaces enslaved to this one, e.g. the ports of a
+                   bridge (Linux only)
     :return the list of network interfaces that are available
     """
 
-    return cast(List[InterfaceName], _interfaces(display.value))
+    interface_filter = _interface_filter(
+        up, exclude_loopback, physical, has_address, kind, name, name_regex, master
+    )
+
+    cached = _cached_interfaces(display.value, interface_filter)
+    if cached is not None:
+        generation, names = cached
+        return CachedList(names, generation)
+
+    return cast(List[InterfaceName], _interfaces(display.value, interface_filter))
 
 
 def interfaces_by_index(
     display: InterfaceDisplay = InterfaceDisplay.HumanReadable,
+    *,
+    up: bool = False,
+    exclude_loopback: bool = False,
+    physical: bool = False,
+    has_address: Optional[InterfaceType] = None,
+    kind: Optional[str] = None,
+    name: Optional[str] = None,
+    name_regex: Optional[str] = None,
+    master: Optional[str] = None,
 ) -> Dict[int, InterfaceName]:
     """
     List the network interfaces by their index
@@ -174,26 +361,147 @@ def interfaces_by_index(
     :param display: Hint for how to display the interface names.
                     See the `InterfaceDisplay` enum for the values. By default,
                     human-readable.
+    :param up, exclude_loopback, physical, has_address, kind, name, name_regex, master:
+        filters on the interfaces, see `interfaces()`
 
     :return the list of network interfaces on the machine, with the
-        interface's Index mapped to its Name
+        interface's Index mapped to its Name. The same interfaces as `interfaces()`,
+        in the same order.
     """
 
-    return cast(Dict[int, str], _interfaces_by_index(display.value))
+    interface_filter = _interface_filter(
+        up, exclude_loopback, physical, has_address, kind, name, name_regex, master
+    )
+
+    cached = _cached_interfaces_by_index(display.value, interface_filter)
+    if cached is not None:
+        generation, names = cached
+        return CachedDict(names, generation)
 
+    return cast(Dict[int, str], _interfaces_by_index(display.value, interface_filter))
 
-def ifaddresses(if_name: str) -> Addresses:
+
+def if_nametoindex(if_name: str) -> int:
     """
-    List the network addresses for the given interface
+    Look up the index of an interface by its name
+
+    On Linux, the alternative names of the interfaces (see "ip link property") are
+    looked up as well.
 
     :param if_name: the interface name
+    :return: the index of the interface
+    """
+
+    return cast(int, _if_nametoindex(if_name))
+
+
+def if_indextoname(
+    if_index: int,
+    display: InterfaceDisplay = InterfaceDisplay.HumanReadable,
+) -> InterfaceName:
+    """
+    Look up the name of an interface by its index
+
+    :param if_index: the interface index
+    :param display: Hint for how to display the interface name.
+                    See the `InterfaceDisplay` enum for the values. By default,
+                    human-readable.
+    :return: the name of the interface
+    """
+
+    return cast(InterfaceName, _if_indextoname(if_index, display.value))
+
+
+def ifaddresses(if_name: InterfaceRef) -> Addresses:
+    """
+    List the network addresses for the given interface
+
+    The addresses of the IPv4 label aliases of the interface (e.g. "eth0:1") are included.
+    An alias can also be passed as `if_name`, to only get the addresses bearing its label.
+
+    :param if_name: the interface name, or its index
     :return a map of network addresses indexed by network address type.
     The values are the addresses, indexed by their roles
     """
 
+    cached = _cached_ifaddresses(if_name)
+    if cached is not None:
+        generation, addresses = cached
+        return cast(Addresses, CachedDict(addresses, generation))
+
     return cast(Addresses, _ifaddresses(if_name))
 
 
+def packed_addresses(if_name: InterfaceRef) -> PackedAddresses:
+    """
+    List the network addresses for the given interface in binary form
+
+    This is `ifaddresses()` without the conversions to and from strings: the
+    addresses are bytes in network order, and the netmasks are prefix lengths.
+    Only the families with a binary form are included (IP and hardware addresses).
+
+    :param if_name: the interface name, or its index
+    :return a map of the binary addresses indexed by network address type
+    """
+
+    return cast(PackedAddresses, _packed_addresses(if_name))
+
+
+def snapshot(
+    display: InterfaceDisplay = InterfaceDisplay.HumanReadable,
+) -> List[InterfaceSnapshot]:
+    """
+    Get every interface of the system along with its index, flags and addresses,
+    in a single pass.
+
+    This is much cheaper than calling `ifaddresses()` for each of the `interfaces()`:
+    the system is only queried once, and the result is one consistent view of it.
+
+    :param display: Hint for how to display the interface names.
+                    See the `InterfaceDisplay` enum for the values. By default,
+                    human-readable.
+    :return: the interfaces of the system, sorted by index
+    """
+
+    return [cast(InterfaceSnapshot, interface.as_dict()) for interface in all_interfaces(display)]
+
+
+def all_interfaces(
+    display: InterfaceDisplay = InterfaceDisplay.HumanReadable,
+) -> List[Interface]:
+    """
+    Get every interface of the system as an `Interface` object, in a single pass.
+
+    An `Interface` has the name, index, IFF_* flags, MTU and MAC address of the
+    interface, and its addresses as `Address` objects: their `ip`, `netmask`,
+    `broadcast` and `peer` are `ipaddress` objects (strings for hardware addresses),
+    and `ip_interface` is the address along with its network.
+
+    :param display: Hint for how to display the interface names.
+                    See the `InterfaceDisplay` enum for the values. By default,
+                    human-readable.
+    :return: the interfaces of the system, sorted by index
+    """
+
+    return cast(List[Interface], _all_interfaces(display.value))
+
+
+def get_interface(
+    iface: InterfaceRef,
+    display: InterfaceDisplay = InterfaceDisplay.HumanReadable,
+) -> Interface:
+    """
+    Get an interface as an `Interface` object; see `all_interfaces()`.
+
+    :param iface: the interface name, as displayed or as known to the system, or its index
+    :param display: Hint for how to display the interface name.
+                    See the `InterfaceDisplay` enum for the values. By default,
+                    human-readable.
+    """
+
+    return cast(Interface, _get_interface(iface, display.value))
+
+
 def _ip_tool_path() -> Optional[str]:
     is_linux = _platform == "linux" or _platform == "linux32"
     if not is_linux:
@@ -216,31 +524,54 @@ def gateways(old_api: bool = False) -> GatewaysTable:
     :return a routing table
     """
 
+    cached = _cached_routes()
+    if cached is not None:
+        generation, entries = cached
+        return CachedDict(routes_from_entries(entries, old_api=old_api), generation)
+
+    try:
+        return routes_from_entries(routes(), old_api=old_api)
+    except NetifacesError as e:
+        logging.debug("Cannot list the routes: %s", e)
+
     ip_tool_path = _ip_tool_path()
 
     if ip_tool_path:
-        from .routes import routes_parse_ip_tool
-
         logging.debug("Using ip tool")
         return routes_parse_ip_tool(ip_tool_path, old_api=old_api)
     elif _NIX_ROUTE_FILE.exists():
-        from .routes import routes_parse_file
-
         logging.debug("Using route file")
-        return routes_parse_file(_NIX_ROUTE_FILE.read_text(), old_api=old_api)
+        return routes_from_proc(proc_routes(), old_api=old_api)
     else:
-        raise NotImplementedError("No implementation for `gateways()` yet")
+        raise UnsupportedOperationError("No implementation for `gateways()` yet")
 
 
 def default_gateway(old_api: bool = False) -> DefaultGatewayEntry:
     """
     Get the default gateway for each interface type
 
+    The gateway of a family is the one of its `primary_interface()`: of the default
+    routes, the one with the lowest metric whose interface is up. A family whose default
+    traffic goes through a link without a gateway has none. With a backend that cannot
+    list the routes, the default gateways are found from the ip tool or /proc/net/route,
+    where available.
+
     :return: the default gateway indexed by each interface type
     """
 
     default_table: DefaultGatewayEntry = {}
 
+    try:
+        for if_type in [InterfaceType.AF_INET, InterfaceType.AF_INET6]:
+            route = primary_interface(if_type)
+            if route is not None and route["gateway"] is not None and route["interface"] is not None:
+                key = if_type.value if old_api else if_type
+                default_table[key] = (route["gateway"], route["interface"])
+
+        return default_table
+    except UnsupportedOperationError as e:
+        logging.debug("Cannot list the default routes: %s", e)
+
     for if_type, list_of_tuples in gateways(old_api=old_api).items():
         for gateway_ip, if_name, *rest in list_of_tuples:
             if len(rest) > 0 and rest[0]:
@@ -249,7 +580,177 @@ def default_gateway(old_api: bool = False) -> DefaultGatewayEntry:
     return default_table
 
 
-def interface_is_up(if_name: str) -> bool:
+def routes(family: InterfaceType = InterfaceType.AF_UNSPEC) -> List[RouteEntry]:
+    """
+    List the routes of every routing table.
+
+    Each route has its "nexthops": multipath routes have one per path, with its
+    gateway, interface, weight and RTNH_F_* flags; the routes using nexthop objects
+    ("ip route add ... nhid N") have the ones of that object, or of the members of
+    that group. Only the netlink and procfs backends, on Linux, list the routes; the
+    procfs one cannot tell their tables, protocols nor sources.
+
+    :param family: AF_INET or AF_INET6 to only get the routes of that family.
+                   By default, both.
+    """
+
+    return [cast(RouteEntry, route.as_dict()) for route in all_routes(family)]
+
+
+def all_routes(family: InterfaceType = InterfaceType.AF_UNSPEC) -> List[Route]:
+    """
+    List the routes of every routing table as `Route` objects; see `routes()`.
+
+    The `destination` of a `Route` is an `ipaddress` network (0.0.0.0/0 or ::/0 for
+    the default routes), its `gateway` and `source` are `ipaddress` addresses, and
+    its `nexthops` are `NextHop` objects. Only the netlink and procfs backends, on
+    Linux, list the routes.
+
+    :param family: AF_INET or AF_INET6 to only get the routes of that family.
+                   By default, both.
+    """
+
+    return cast(List[Route], _routes(int(family)))
+
+
+def proc_routes(family: InterfaceType = InterfaceType.AF_UNSPEC) -> List[ProcRouteEntry]:
+    """
+    List the routes of /proc/net/route and /proc/net/ipv6_route, with all their columns.
+
+    This works without netlink nor the ip tool, but /proc/net/route only has the main
+    table, and neither file knows about multipath routes. Only available on Linux.
+
+    :param family: AF_INET or AF_INET6 to only get the routes of that family.
+                   By default, both.
+    """
+
+    return cast(List[ProcRouteEntry], _proc_routes(int(family)))
+
+
+def neighbours(family: InterfaceType = InterfaceType.AF_UNSPEC) -> List[NeighbourEntry]:
+    """
+    List the entries of the neighbour tables: ARP for IPv4, NDP for IPv6.
+
+    Only the netlink and procfs backends, on Linux, list the neighbours. The procfs one
+    reads /proc/net/arp, which has no IPv6 entries and only tells whether an entry is
+    complete or permanent, reported as the NUD_REACHABLE or NUD_PERMANENT state.
+
+    :param family: AF_INET or AF_INET6 to only get the neighbours of that family.
+                   By default, all of them.
+    """
+
+    return cast(List[NeighbourEntry], _neighbours(int(family)))
+
+
+def default_routes(family: InterfaceType = InterfaceType.AF_UNSPEC) -> List[RouteEntry]:
+    """
+    List the default routes of the main routing table, sorted by family, then by metric.
+
+    The kernel sends the default traffic through the first of them whose interface is
+    up; the others are fallbacks, e.g. wifi behind a wired connection. Routes with the
+    same metric share the traffic, see `primary_interface()`. Only the netlink and procfs
+    backends, on Linux, list the routes.
+
+    :param family: AF_INET or AF_INET6 to only get the routes of that family.
+                   By default, both.
+    """
+
+    return cast(List[RouteEntry], _default_routes(int(family)))
+
+
+def primary_interface(family: InterfaceType = InterfaceType.AF_INET) -> Optional[RouteEntry]:
+    """
+    Get the default route currently carrying the traffic of a family: the one with the
+    lowest metric whose interface is up.
+
+    Its "interface" and "gateway" are where the default traffic goes, and its "source"
+    is the address the kernel sends it from.
+    Only the backends listing the routes can find it.
+
+    :param family: AF_INET or AF_INET6
+    :return: the default route, or None if no default route can be used
+    """
+
+    return cast(Optional[RouteEntry], _primary_interface(int(family)))
+
+
+def interface_routes(
+    if_name: InterfaceRef, family: InterfaceType = InterfaceType.AF_UNSPEC
+) -> List[RouteEntry]:
+    """
+    List the routes, from every routing table, that go out through the given interface.
+
+    With the netlink backend, only the routes of that interface are requested from the
+    kernel, so this stays cheap on hosts with a very large number of interfaces or
+    routes. Only the netlink and procfs backends, on Linux, list the routes.
+
+    :param if_name: the interface name, or its index
+    :param family: AF_INET or AF_INET6 to only get the routes of that family.
+                   By default, all the routes.
+    """
+
+    return cast(List[RouteEntry], _interface_routes(if_name, int(family)))
+
+
+def interface_for_address(ip: str) -> Optional[AddressOwner]:
+    """
+    Find the interface holding a local address.
+
+    The same IPv6 link-local address can be held by several interfaces: a zone, as in
+    "fe80::1%eth0", restricts the search to one interface. Otherwise, the interface
+    with the lowest index is returned.
+
+    :param ip: the IPv4 or IPv6 address
+    :return: the interface and the address entry, or None if no interface holds the
+        address
+    """
+
+    return cast(Optional[AddressOwner], _interface_for_address(str(ip)))
+
+
+def interfaces_in_network(network: str) -> List[AddressOwner]:
+    """
+    List the interfaces on a network: the addresses whose subnet, as given by their
+    prefix length, overlaps the network.
+
+    An interface is listed once for each of its addresses on the network, sorted
+    by index.
+
+    :param network: the network in CIDR notation, e.g. "192.168.1.0/24". A bare
+                    address gives the interfaces on a subnet containing it.
+    :return: the interfaces and their address entries
+    """
+
+    return cast(List[AddressOwner], _interfaces_in_network(str(network)))
+
+
+def route_get(
+    dest: str,
+    source: Optional[str] = None,
+    oif: Optional[InterfaceRef] = None,
+    mark: Optional[int] = None,
+) -> Optional[RouteEntry]:
+    """
+    Ask the kernel which route it would use for a packet to a destination, like
+    "ip route get" does.
+
+    The route is reported as it is in its routing table (destination network, table,
+    metric...), with the output interface, the gateway and the preferred source address
+    the kernel picked for this destination.
+    Only available with the netlink backend.
+
+    :param dest: the destination address. A link-local IPv6 address can carry a zone,
+                 e.g. "fe80::1%eth0".
+    :param source: the source address of the packet
+    :param oif: the interface the packet has to go out through, by name or index
+    :param mark: the firewall mark of the packet, for policy routing rules matching it
+    :return: the route, or None if the destination cannot be reached
+    """
+
+    return cast(Optional[RouteEntry], _route_get(str(dest), source, oif, mark))
+
+
+def interface_is_up(if_name: InterfaceRef) -> bool:
     """
     Get whether a given interface is up and can transfer packets.
 
@@ -258,7 +759,50 @@ def interface_is_up(if_name: str) -> bool:
     has a network cable plugged in.  The flag checked by netifaces corresponds to the
     "RUNNING" status shown in ipconfig.
 
+    :param if_name: the interface name, or its index
     :return: True if the interface is up, false otherwise.
     """
 
     return cast(bool, _interface_is_up(if_name))
+
+
+def wait_for_interface(
+    if_name: InterfaceRef,
+    condition: WaitCondition = WaitCondition.Up,
+    timeout: Optional[float] = None,
+) -> bool:
+    """
+    Block until the given interface meets a condition.
+
+    The wait is driven by the kernel's netlink notifications: the condition is only
+    re-evaluated when a link, address or route changes. Only available with the
+    netlink backend.
+
+    :param if_name: the interface name, or its index
+    :param condition: the condition to wait for. By default, for the interface to be up.
+    :param timeout: the maximum time to wait, in seconds, or None (or infinity) to wait forever
+    :return: True if the condition was met, False if the timeout expired first
+    """
+
+    return cast(bool, _wait_for_interface(if_name, condition.value, timeout))
+
+
+def wait_for_network_online(
+    family: InterfaceType = InterfaceType.AF_UNSPEC,
+    timeout: Optional[float] = None,
+) -> bool:
+    """
+    Block until the host is online, which is when it has a default route through a
+    running interface, and a usable source address for that route.
+    This is what systemd calls network-online.
+
+    The wait is driven by the kernel's netlink notifications. Only available with the
+    netlink backend.
+
+    :param family: AF_INET or AF_INET6 to wait for a specific family, or AF_UNSPEC
+                   (the default) to accept either.
+    :param timeout: the maximum time to wait, in seconds, or None (or infinity) to wait forever
+    :return: True if the network is online, False if the timeout expired first
+    """
+
+    return cast(bool, _wait_for_network_online(int(family), timeout))
@@ -0,0 +1,51 @@
+from typing import List, Optional, cast
+
+from .netifaces import _backend, _backends, _set_backend
+
+
+def available_backends() -> List[str]:
+    """
+    List the backends of this platform, the default one first.
+
+    - "netlink": netlink sockets, on Linux
+    - "procfs": the files of /proc/net and /sys/class/net, on Linux
+    - "getifaddrs": getifaddrs and the ioctls of POSIX, on Linux and the other POSIX platforms
+    - "iphlpapi": the IP Helper API, on Windows
+    """
+
+    return cast(List[str], _backends())
+
+
+def get_backend() -> str:
+    """
+    Get the name of the backend answering the queries. It is the default one, unless another
+    was selected with `set_backend()` or the NETIFACES_BACKEND environment variable.
+
+    When the system refuses the default backend with EPERM or EACCES, e.g. in a sandbox
+    forbidding netlink sockets, or when it is unavailable, netifaces falls back to the
+    procfs one on Linux, for the rest of the process. A backend that was selected is kept
+    whatever its errors.
+    """
+
+    return cast(str, _backend())
+
+
+def set_backend(name: Optional[str]) -> None:
+    """
+    Answer the queries with another backend, e.g. to compare what two of them report on the
+    same host. The selection applies to the whole process.
+
+    Every query follows the selection, as does the automatic fallback. A backend which
+    cannot answer one, e.g. the procfs one for `route_get()`, the waits or `enable_cache()`,
+    raises an `UnsupportedOperationError` naming it. Only `proc_routes()`, the netifaces 1
+    compatibility mode and `NetlinkSession` always use the backend they are built on. The
+    cache is only used along with the netlink backend.
+
+    :param name: one of `available_backends()`, or None to go back to the backend of the
+                 NETIFACES_BACKEND environment variable, or to the default one
+    """
+
+    if name is not None and name not in available_backends():
+        raise ValueError(f"Unknown backend {name!r}, the ones of this platform are {available_backends()}")
+
+    _set_backend(name)
@@ -0,0 +1,66 @@
+from typing import Dict, List, Optional, TypeVar
+
+from .netifaces import _cache_generation, _disable_cache, _enable_cache
+
+K = TypeVar("K")
+V = TypeVar("V")
+
+
+class CachedList(List[V]):
+    """
+    A list returned from the cache, along with the cache generation it was read from
+    """
+
+    def __init__(self, items: List[V], generation: int) -> None:
+        super().__init__(items)
+        self.generation = generation
+
+
+class CachedDict(Dict[K, V]):
+    """
+    A dict returned from the cache, along with the cache generation it was read from
+    """
+
+    def __init__(self, items: Dict[K, V], generation: int) -> None:
+        super().__init__(items)
+        self.generation = generation
+
+
+def enable_cache() -> None:
+    """
+    Enable the cache.
+
+    While it is enabled, `interfaces()`, `interfaces_by_index()`, `ifaddresses()` and
+    `gateways()` are answered from an in-memory model of the system, without any system
+    call. A background thread keeps that model up to date from the kernel's netlink
+    notifications.
+
+    The results returned from the cache carry a `generation` attribute. The generation is
+    bumped on every change to the system; compare it with `cache_generation()` to tell
+    whether a result is stale.
+
+    The cache is only used while the netlink backend is selected, see `set_backend()`.
+    Enabling it when it is already enabled does nothing. Only available with the netlink
+    backend.
+    """
+
+    _enable_cache()
+
+
+def disable_cache() -> None:
+    """
+    Disable the cache, and stop its background thread
+    """
+
+    _disable_cache()
+
+
+def cache_generation() -> Optional[int]:
+    """
+    Get the current generation of the cache
+
+    :return: the generation, or None if the cache is not enabled
+    """
+
+    generation: Optional[int] = _cache_generation()
+    return generation
@@ -0,0 +1,148 @@
+"""
+Functions returning exactly what netifaces 1 (al45tair/netifaces) returned, for the
+code written against it, which can then switch libraries without changes:
+
+>>> import netifaces.compat as netifaces
+>>> netifaces.gateways()["default"][netifaces.AF_INET]
+('192.168.0.1', 'eth0')
+>>> netifaces.ifaddresses("eth0")[netifaces.AF_LINK]
+[{'addr': '02:fc:00:00:00:01', 'broadcast': 'ff:ff:ff:ff:ff:ff'}]
+"""
+
+from typing import Dict, List, Tuple
+
+from . import default_gateway as _default_gateway
+from . import gateways as _gateways
+from .defs import LegacyGatewaysTable
+from .netifaces import _compat_ifaddresses, _compat_interfaces
+
+__all__ = [
+    "address_families",
+    "gateways",
+    "ifaddresses",
+    "interfaces",
+    "version",
+]
+
+version = "0.11.0"
+
+# The address family constants of netifaces 1, in the order it defined them
+_FAMILIES = [
+    ("AF_UNSPEC", 0),
+    ("AF_UNIX", 1),
+    ("AF_FILE", 1),
+    ("AF_INET", 2),
+    ("AF_INET6", 10),
+    ("AF_SNA", 22),
+    ("AF_DECnet", 12),
+    ("AF_APPLETALK", 5),
+    ("AF_ROUTE", 16),
+    ("AF_LINK", 17),
+    ("AF_PACKET", 17),
+    ("AF_IPX", 4),
+    ("AF_ISDN", 34),
+    ("AF_NETBEUI", 13),
+    ("AF_ATMPVC", 8),
+    ("AF_ATMSVC", 20),
+    ("AF_IRDA", 23),
+    ("AF_NETROM", 6),
+    ("AF_BRIDGE", 7),
+    ("AF_X25", 9),
+    ("AF_ROSE", 11),
+    ("AF_SECURITY", 14),
+    ("AF_KEY", 15),
+    ("AF_NETLINK", 16),
+    ("AF_ASH", 18),
+    ("AF_ECONET", 19),
+    ("AF_PPPOX", 24),
+    ("AF_WANPIPE", 25),
+    ("AF_BLUETOOTH", 31),
+]
+
+AF_UNSPEC = 0
+AF_UNIX = 1
+AF_FILE = 1
+AF_INET = 2
+AF_INET6 = 10
+AF_SNA = 22
+AF_DECnet = 12
+AF_APPLETALK = 5
+AF_ROUTE = 16
+AF_LINK = 17
+AF_PACKET = 17
+AF_IPX = 4
+AF_ISDN = 34
+AF_NETBEUI = 13
+AF_ATMPVC = 8
+AF_ATMSVC = 20
+AF_IRDA = 23
+AF_NETROM = 6
+AF_BRIDGE = 7
+AF_X25 = 9
+AF_ROSE = 11
+AF_SECURITY = 14
+AF_KEY = 15
+AF_NETLINK = 16
+AF_ASH = 18
+AF_ECONET = 19
+AF_PPPOX = 24
+AF_WANPIPE = 25
+AF_BLUETOOTH = 31
+
+__all__ += [name for name, _ in _FAMILIES]
+
+# Like netifaces 1, the last name defined for a value wins, e.g. 17 is "AF_PACKET"
+address_families: Dict[int, str] = {}
+for _name, _value in _FAMILIES:
+    address_families[_value] = _name
+
+
+def interfaces() -> List[str]:
+    """
+    Get the names of the interfaces, like netifaces 1 did
+
+    IPv4 address labels (such as "eth0:1") are listed as interfaces of their own.
+    """
+
+    return _compat_interfaces()
+
+
+def ifaddresses(ifname: str) -> Dict[int, List[Dict[str, str]]]:
+    """
+    Get the addresses of an interface, like netifaces 1 did
+
+    Every address family maps to a list of dicts of strings, with the "addr",
+    "netmask", "broadcast" and "peer" keys when they apply. Raises ValueError
+    when the interface does not exist.
+    """
+
+    addresses = _compat_ifaddresses(ifname)
+    if addresses is None:
+        raise ValueError("You must specify a valid interface name.")
+
+    return {family: [dict(entry) for entry in entries] for family, entries in addresses}
+
+
+def gateways() -> LegacyGatewaysTable:
+    """
+    Get the routes which have a gateway, like netifaces 1 did
+
+    The "default" key maps each address family to its default gateway, as an
+    (address, interface) tuple; it is an empty dict when there is none. Every
+    address family maps to the list of its gateways, as (address, interface,
+    is_default) tuples.
+    """
+
+    defaults: Dict[int, Tuple[str, str]] = {}
+    table: LegacyGatewaysTable = {"default": defaults}
+
+    for family, (gateway_ip, iface) in _default_gateway(old_api=True).items():
+        defaults[int(family)] = (gateway_ip, iface)
+
+    for family, entries in _gateways(old_api=True).items():
+        legacy: List[Tuple[str, str, bool]] = []
+        for gateway_ip, iface, *is_default in entries:
+            legacy.append((gateway_ip, iface, bool(is_default and is_default[0])))
+        table[int(family)] = legacy
+
+    return table
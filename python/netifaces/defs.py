@@ -1,11 +1,5 @@
-import sys
 from enum import IntEnum
-from typing import Dict, List, Tuple, Union
-
-if sys.version_info >= (3, 8):
-    from typing import Literal
-else:
-    from typing_extensions import Literal
+from typing import Dict, List, Literal, Optional, Tuple, TypedDict, Union
 
 AF_UNSPEC = 0
 AF_UNIX = 1
@@ -122,6 +116,8 @@ class InterfaceType(IntEnum):
 
 
 InterfaceName = str
+# An interface, designated by its name or by its index
+InterfaceRef = Union[InterfaceName, int]
 AddressType = Union[
     Literal["addr"],
     Literal["peer"],
@@ -132,7 +128,186 @@ Address = str
 
 Addresses = Dict[InterfaceType, List[Dict[AddressType, Address]]]
 
+
+
+class PackedAddress(TypedDict, total=False):
+    """
+    An address in binary form, as returned by `packed_addresses()`
+
+    - addr: the address bytes, in network order (4 for IPv4, 16 for IPv6, usually
+      6 for hardware addresses)
+    - prefixlen: the length of the netmask, when it is known and contiguous
+    - broadcast: the broadcast address bytes, when there is one
+    - peer: the bytes of the other end of a point-to-point link
+    """
+
+    addr: bytes
+    prefixlen: int
+    broadcast: bytes
+    peer: bytes
+
+
+PackedAddresses = Dict[InterfaceType, List[PackedAddress]]
+
 GatewayEntry = Union[Tuple[str, str], Tuple[str, str, bool]]
 GatewaysTable = Dict[Union[InterfaceType, int], List[GatewayEntry]]
 
 DefaultGatewayEntry = Dict[Union[InterfaceType, int], Tuple[str, str]]
+
+# The gateways table of netifaces 1: the default gateways under the "default" key, and
+# (gateway, interface, is_default) tuples under the integer address families
+LegacyGatewaysTable = Dict[Union[str, int], Union[Dict[int, Tuple[str, str]], List[Tuple[str, str, bool]]]]
+
+
+class InterfaceSnapshot(TypedDict):
+    """
+    Everything a snapshot knows about an interface
+
+    - name: the interface name
+    - index: the interface index
+    - flags: the IFF_* flags of the interface (always 0 on Windows)
+    - up: same meaning as `interface_is_up()`
+    - mtu: the MTU of the interface, when known
+    - addresses: the addresses of the interface, as returned by `ifaddresses()`
+    """
+
+    name: InterfaceName
+    index: int
+    flags: int
+    up: bool
+    mtu: Optional[int]
+    addresses: Addresses
+
+
+class NextHopEntry(TypedDict):
+    """
+    One of the paths of a route
+
+    - gateway: the gateway, if the path has one
+    - interface: the name of the output interface
+    - index: the index of the output interface
+    - weight: the share of the traffic going through this path, relative to the others
+    - flags: the RTNH_F_* flags of the path, e.g. RTNH_F_DEAD (1) or RTNH_F_LINKDOWN (16)
+    """
+
+    gateway: Optional[str]
+    interface: Optional[InterfaceName]
+    index: Optional[int]
+    weight: int
+    flags: int
+
+
+class RouteEntry(TypedDict):
+    """
+    A route of one of the routing tables
+
+    - family: the address family of the route
+    - destination: the destination network, or None for a default route
+    - prefixlen: the prefix length of the destination network
+    - gateway: the gateway, if the route has one
+    - interface: the name of the output interface
+    - index: the index of the output interface
+    - source: the preferred source address, if the route has one
+    - metric: the metric (priority) of the route
+    - table: the routing table the route is in
+    - protocol: the RTPROT_* protocol which installed the route
+    - scope: the RT_SCOPE_* scope of the route
+    - type: the RTN_* type of the route
+    - nexthops: the paths of the route. Multipath routes have several, and no
+      gateway or interface of their own; other routes have one, made of their
+      gateway and interface
+    - nhid: the id of the nexthop object (or group) the route uses, if any
+    """
+
+    family: int
+    destination: Optional[str]
+    prefixlen: int
+    gateway: Optional[str]
+    interface: Optional[InterfaceName]
+    index: Optional[int]
+    source: Optional[str]
+    metric: Optional[int]
+    table: int
+    protocol: int
+    scope: int
+    type: int
+    nexthops: List[NextHopEntry]
+    nhid: Optional[int]
+
+
+class ProcRouteEntry(TypedDict):
+    """
+    A route, as listed in /proc/net/route or /proc/net/ipv6_route
+
+    - family: the address family of the route
+    - destination: the destination network, or None for a default route
+    - prefixlen: the prefix length of the destination network
+    - gateway: the gateway, if the route has one
+    - interface: the name of the output interface
+    - source: the source network of an IPv6 source-specific route
+    - source_prefixlen: the prefix length of the source network
+    - flags: the RTF_* flags, e.g. RTF_UP (1), RTF_GATEWAY (2) or RTF_HOST (4)
+    - refcnt: the reference count
+    - use: the number of lookups of the route
+    - metric: the metric of the route
+    - mtu, window and irtt: the MTU, TCP window and initial round trip time
+      clamps of the route; only for IPv4
+    """
+
+    family: int
+    destination: Optional[str]
+    prefixlen: int
+    gateway: Optional[str]
+    interface: InterfaceName
+    source: Optional[str]
+    source_prefixlen: Optional[int]
+    flags: int
+    refcnt: int
+    use: int
+    metric: int
+    mtu: Optional[int]
+    window: Optional[int]
+    irtt: Optional[int]
+
+
+class NeighbourEntry(TypedDict):
+    """
+    An entry of the neighbour (ARP or NDP) tables
+
+    - family: the address family of the entry
+    - addr: the network address of the neighbour
+    - lladdr: the link layer address of the neighbour, once resolved
+    - interface: the name of the interface the neighbour is reachable through
+    - index: the index of that interface
+    - state: the NUD_* state of the entry
+    - flags: the NTF_* flags of the entry
+    """
+
+    family: int
+    addr: Optional[str]
+    lladdr: Optional[str]
+    interface: Optional[InterfaceName]
+    index: int
+    state: int
+    flags: int
+
+
+class AddressOwner(TypedDict):
+    """
+    An address held by an interface
+
+    - interface: the name of the interface
+    - index: the index of the interface
+    - family: the address family of the address
+    - prefixlen: the prefix length of the subnet of the address
+    - address: the address, shaped like the entries of `ifaddresses()`
+    """
+
+    interface: InterfaceName
+    index: int
+    family: int
+    prefixlen: int
+    address: Dict[AddressType, Address]
+
+
+InterfaceStats = Dict[str, int]
@@ -0,0 +1,71 @@
+"""
+The exceptions raised by netifaces.
+
+Every error of netifaces is a `NetifacesError`, which is also a `RuntimeError` as
+all errors used to be one.
+"""
+from typing import Optional, Union
+
+__all__ = [
+    "NetifacesError",
+    "InterfaceNotFoundError",
+    "AmbiguousInterfaceError",
+    "PermissionDeniedError",
+    "UnsupportedOperationError",
+    "BackendUnavailableError",
+]
+
+
+class NetifacesError(RuntimeError):
+    """
+    An error of netifaces
+
+    :attr errno: the error number of the failed system call, if any
+    :attr interface: the name or index of the interface the error is about, if any
+    :attr backend: the backend which failed, e.g. "netlink", "procfs", "getifaddrs", "iphlpapi"
+                  or "mock" (see `netifaces.testing`)
+    """
+
+    def __init__(
+        self,
+        message: str,
+        *,
+        errno: Optional[int] = None,
+        interface: Union[str, int, None] = None,
+        backend: Optional[str] = None,
+    ) -> None:
+        super().__init__(message)
+        self.errno = errno
+        self.interface = interface
+        self.backend = backend
+
+
+class InterfaceNotFoundError(NetifacesError):
+    """
+    No interface has the given name or index
+    """
+
+
+class AmbiguousInterfaceError(NetifacesError):
+    """
+    More than a single interface is displayed with the given name, e.g. two interfaces
+    have the same description
+    """
+
+
+class PermissionDeniedError(NetifacesError, PermissionError):
+    """
+    The system refused the request, e.g. because it needs privileges
+    """
+
+
+class UnsupportedOperationError(NetifacesError, NotImplementedError):
+    """
+    The operation is not available on this platform
+    """
+
+
+class BackendUnavailableError(NetifacesError):
+    """
+    The backend cannot be used on this system, e.g. the kernel was built without netlink
+    """
@@ -0,0 +1,128 @@
+import subprocess
+from collections import defaultdict
+from typing import List, Optional, Tuple
+
+from .defs import GatewaysTable, InterfaceType, ProcRouteEntry, RouteEntry
+from .errors import NetifacesError
+
+
+def _safe_split(line: str) -> List[str]:
+    simplified = line.replace("\t", " ")
+    splat = simplified.split(" ")
+    return [x for x in splat if len(x) > 0]
+
+
+RT_TABLE_MAIN = 254
+
+
+def _ip_tool_path_of(words: List[str]) -> Optional[Tuple[str, str]]:
+    """
+    Get the gateway and interface out of the words of a route, or of one of its
+    nexthops, as printed by the ip tool
+    """
+
+    if "via" not in words or "dev" not in words:
+        return None
+
+    gateway = words[words.index("via") + 1 :]
+    # A gateway of another family than the route's, e.g. "via inet6 fe80::1"
+    if gateway[:1] == ["inet"] or gateway[:1] == ["inet6"]:
+        gateway = gateway[1:]
+    iface = words[words.index("dev") + 1 :]
+
+    if not gateway or not iface:
+        return None
+
+    return gateway[0].split("/")[0], iface[0]
+
+
+def routes_parse_ip_tool(ip_tool_path: str, old_api: bool = False) -> GatewaysTable:
+    ipv4_query = subprocess.run([ip_tool_path, "r"], capture_output=True)
+    ipv6_query = subprocess.run([ip_tool_path, "-6", "r"], capture_output=True)
+
+    if ipv4_query.returncode != 0 or ipv6_query.returncode != 0:
+        raise NetifacesError("Cannot use the IP tool; although it is present on the system", backend="ip")
+
+    ipv4_lines = ipv4_query.stdout.decode("UTF-8").splitlines()
+    ipv6_lines = ipv6_query.stdout.decode("UTF-8").splitlines()
+
+    table: GatewaysTable = defaultdict(lambda *_: [])
+
+    for if_type, lines in [
+        (InterfaceType.AF_INET, ipv4_lines),
+        (InterfaceType.AF_INET6, ipv6_lines),
+    ]:
+        default = False
+
+        for line in lines:
+            words = _safe_split(line)
+            if not words:
+                continue
+
+            # The nexthops of a multipath route follow it, one per indented line
+            if words[0] == "nexthop":
+                words = words[1:]
+            else:
+                default = words[0] == "default"
+
+            path = _ip_tool_path_of(words)
+            if path is None:
+                continue
+
+            gateway_ip, iface = path
+            table[if_type.value if old_api else if_type].append(
+                (gateway_ip, iface, True) if default else (gateway_ip, iface)
+            )
+
+    return dict(table)
+
+
+def routes_from_entries(entries: List[RouteEntry], old_api: bool = False) -> GatewaysTable:
+    """
+    Build the gateways table from route entries, keeping the same routes as
+    `routes_parse_ip_tool` does: the ones of the main table which have a gateway.
+    Multipath routes give an entry per nexthop.
+    """
+
+    table: GatewaysTable = defaultdict(lambda *_: [])
+
+    for entry in entries:
+        if entry["table"] != RT_TABLE_MAIN:
+            continue
+
+        if_type = InterfaceType(entry["family"])
+        default = entry["prefixlen"] == 0
+
+        for hop in entry["nexthops"]:
+            gateway_ip, iface = hop["gateway"], hop["interface"]
+            if gateway_ip is None or iface is None:
+                continue
+
+            table[if_type.value if old_api else if_type].append(
+                (gateway_ip, iface, True) if default else (gateway_ip, iface)
+            )
+
+    return dict(table)
+
+
+def routes_from_proc(entries: List[ProcRouteEntry], old_api: bool = False) -> GatewaysTable:
+    """
+    Build the gateways table from the routes of the /proc route files, keeping the
+    ones which have a gateway. IPv6 routes come from every table.
+    """
+
+    table: GatewaysTable = defaultdict(lambda *_: [])
+
+    for entry in entries:
+        gateway_ip, iface = entry["gateway"], entry["interface"]
+        if gateway_ip is None:
+            continue
+
+        if_type = InterfaceType(entry["family"])
+        default = entry["prefixlen"] == 0
+
+        table[if_type.value if old_api else if_type].append(
+            (gateway_ip, iface, True) if default else (gateway_ip, iface)
+        )
+
+    return dict(table)
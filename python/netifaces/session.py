@@ -0,0 +1,90 @@
+from typing import Dict, List, cast
+
+from .defs import Addresses, InterfaceName, InterfaceRef, InterfaceStats, InterfaceType, NeighbourEntry, RouteEntry
+from .errors import UnsupportedOperationError
+
+try:
+    from .netifaces import _NetlinkSession
+except ImportError:  # Only built on Linux
+    _NetlinkSession = None
+
+
+class NetlinkSession:
+    """
+    A netlink socket kept open across queries, along with its buffers.
+
+    Every module-level function of netifaces sets up and tears down its own resources.
+    For callers polling the system many times per second, a session avoids most of
+    that cost. If the socket gets into a bad state (e.g. the kernel dropped messages),
+    the session transparently replaces it. A query left unfinished, e.g. interrupted
+    while its replies were being read, does not disturb the next one: the replies left
+    over are told apart by their sequence number and skipped, and a dump the kernel is
+    still sending gets the socket replaced.
+
+    A session can be shared by several threads, which take turns using its socket.
+    Only available on Linux.
+    """
+
+    def __init__(self) -> None:
+        if _NetlinkSession is None:
+            raise UnsupportedOperationError("Netlink sessions are only available on Linux")
+
+        self._session = _NetlinkSession()
+
+    def interfaces(self) -> List[InterfaceName]:
+        """
+        List the network interfaces, sorted by index
+        """
+
+        return cast(List[InterfaceName], self._session.interfaces())
+
+    def ifaddresses(self, if_name: InterfaceRef) -> Addresses:
+        """
+        List the network addresses for the given interface, like `netifaces.ifaddresses()`
+        """
+
+        return cast(Addresses, self._session.ifaddresses(if_name))
+
+    def interface_is_up(self, if_name: InterfaceRef) -> bool:
+        """
+        Get whether a given interface is up, like `netifaces.interface_is_up()`
+        """
+
+        return cast(bool, self._session.interface_is_up(if_name))
+
+    def routes(self, family: InterfaceType = InterfaceType.AF_UNSPEC) -> List[RouteEntry]:
+        """
+        List the routes of every routing table
+
+        :param family: AF_INET or AF_INET6 to only get the routes of that family.
+                       By default, all the routes.
+        """
+
+        return cast(List[RouteEntry], self._session.routes(int(family)))
+
+    def interface_routes(
+        self, if_name: InterfaceRef, family: InterfaceType = InterfaceType.AF_UNSPEC
+    ) -> List[RouteEntry]:
+        """
+        List the routes of every routing table that go out through the given interface,
+        like `netifaces.interface_routes()`
+        """
+
+        return cast(List[RouteEntry], self._session.interface_routes(if_name, int(family)))
+
+    def neighbours(self, family: InterfaceType = InterfaceType.AF_UNSPEC) -> List[NeighbourEntry]:
+        """
+        List the entries of the neighbour (ARP and NDP) tables
+
+        :param family: AF_INET or AF_INET6 to only get the entries of that family.
+                       By default, all of them.
+        """
+
+        return cast(List[NeighbourEntry], self._session.neighbours(int(family)))
+
+    def stats(self) -> Dict[InterfaceName, InterfaceStats]:
+        """
+        Get the traffic counters (rx_bytes, tx_packets, ...) of every interface
+        """
+
+        return cast(Dict[InterfaceName, InterfaceStats], self._session.stats())
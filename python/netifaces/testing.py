@@ -0,0 +1,160 @@
+"""
+Synthetic hosts, for the tests of the programs using netifaces.
+
+A test describes a host (its interfaces, their addresses and flags, and its routes) and
+loads it; until it is unloaded, every query that goes through the backends, e.g.
+`interfaces()`, `ifaddresses()`, `snapshot()`, `routes()`, `gateways()`,
+`default_gateway()`, `primary_interface()`, `route_get()` or `interface_for_address()`,
+answers from that description instead of from the machine running the tests. The
+answers are formatted by the same code as the ones of the other backends, so they look
+exactly like the ones of a real (Linux) host, on every platform. The host has no
+routing rules: `route_get()` picks the most specific route of the main table.
+
+    from netifaces import testing
+
+    HOST = {
+        "interfaces": [
+            {"name": "lo", "addresses": ["127.0.0.1/8", "::1/128"]},
+            {"name": "eth0", "mac": "52:54:00:12:34:56", "addresses": ["192.0.2.10/24"]},
+        ],
+        "routes": [{"destination": "0.0.0.0/0", "gateway": "192.0.2.1", "interface": "eth0"}],
+    }
+
+    def test_uses_the_default_gateway():
+        with testing.mock_host(HOST):
+            assert netifaces.default_gateway()[netifaces.InterfaceType.AF_INET] == ("192.0.2.1", "eth0")
+
+The description is a dictionary, or the path of a JSON file holding one, with:
+
+- interfaces: the interfaces, each with its name and optionally:
+  - index: its index; by default, its position in the list, starting from 1
+  - up: whether it is up and running (True by default)
+  - loopback: whether it is a loopback interface; by default, whether it is named "lo"
+  - flags: its IFF_* flags; by default, the ones of an Ethernet (or loopback) interface
+    that is up or down, or of a point-to-point one if one of its addresses has a peer
+  - mtu: its MTU; 1500 by default, 65536 for a loopback interface
+  - mac: its hardware address, e.g. "52:54:00:12:34:56"; none by default
+  - description: what it is displayed with in `InterfaceDisplay.HumanReadable`
+  - kind, master, physical: what the filters of `interfaces()` look at
+  - addresses: its addresses, each either a string like "192.0.2.10/24" or a dictionary
+    with that "address" and optionally its "broadcast" (by default, the last address of
+    an IPv4 network of up to /30), its "peer", its IFA_* "flags" and its "label", which
+    makes an IPv4 address the one of an alias like "eth0:1"
+- routes: the routes, each with its "destination" network (e.g. "0.0.0.0/0" for a
+  default route) and optionally its "gateway", "interface", "source", "metric", "table"
+  (the main one by default) and "nexthops", the paths of a multipath route, each with
+  its "gateway", "interface" and "weight"
+"""
+
+import contextlib
+import ipaddress
+import json
+import os
+from typing import Any, Dict, Iterator, List, Union
+
+from .netifaces import _load_mock_host, _unload_mock_host
+
+__all__ = ["load_host", "unload_host", "mock_host"]
+
+RT_TABLE_MAIN = 254
+
+HostDescription = Dict[str, Any]
+
+
+def _address(address: Union[str, Dict[str, Any]]) -> Dict[str, Any]:
+    if isinstance(address, str):
+        address = {"address": address}
+
+    interface = ipaddress.ip_interface(address["address"])
+    broadcast = None
+    if interface.version == 4 and interface.network.prefixlen <= 30 and "peer" not in address:
+        broadcast = str(interface.network.broadcast_address)
+
+    return {
+        "address": str(interface),
+        "broadcast": address.get("broadcast", broadcast),
+        "peer": address.get("peer"),
+        "flags": address.get("flags"),
+        "label": address.get("label"),
+    }
+
+
+def _interface(position: int, interface: Dict[str, Any]) -> Dict[str, Any]:
+    name = interface["name"]
+
+    return {
+        "name": name,
+        "index": interface.get("index", position + 1),
+        "up": interface.get("up", True),
+        "loopback": interface.get("loopback", name == "lo"),
+        "flags": interface.get("flags"),
+        "mtu": interface.get("mtu"),
+        "mac": interface.get("mac"),
+        "description": interface.get("description"),
+        "kind": interface.get("kind"),
+        "master": interface.get("master"),
+        "physical": interface.get("physical", False),
+        "addresses": [_address(address) for address in interface.get("addresses", [])],
+    }
+
+
+def _route(route: Dict[str, Any]) -> Dict[str, Any]:
+    return {
+        "destination": str(ipaddress.ip_network(route["destination"], strict=False)),
+        "gateway": route.get("gateway"),
+        "interface": route.get("interface"),
+        "source": route.get("source"),
+        "metric": route.get("metric"),
+        "table": route.get("table", RT_TABLE_MAIN),
+        "nexthops": [
+            {"gateway": hop.get("gateway"), "interface": hop.get("interface"), "weight": hop.get("weight", 1)}
+            for hop in route.get("nexthops", [])
+        ],
+    }
+
+
+def _normalize(host: HostDescription) -> Dict[str, List[Dict[str, Any]]]:
+    return {
+        "interfaces": [_interface(position, iface) for position, iface in enumerate(host.get("interfaces", []))],
+        "routes": [_route(route) for route in host.get("routes", [])],
+    }
+
+
+def load_host(host: Union[HostDescription, str, "os.PathLike[str]"]) -> None:
+    """
+    Answer the queries with a synthetic host, until `unload_host()` is called.
+
+    The host replaces the one loaded before, if any, and takes precedence over the
+    backend selected with `set_backend()`: `get_backend()` returns "mock" while it is
+    loaded.
+
+    :param host: the description of the host, or the path of a JSON file holding it
+    :raise ValueError: if an address, network or interface of the description is invalid
+    """
+
+    if not isinstance(host, dict):
+        with open(host, encoding="utf-8") as file:
+            host = json.load(file)
+
+    _load_mock_host(_normalize(host))
+
+
+def unload_host() -> None:
+    """
+    Go back to the real host, and to the backend that was selected.
+    """
+
+    _unload_mock_host()
+
+
+@contextlib.contextmanager
+def mock_host(host: Union[HostDescription, str, "os.PathLike[str]"]) -> Iterator[None]:
+    """
+    Answer the queries with a synthetic host within a `with` block; see `load_host()`.
+    """
+
+    load_host(host)
+    try:
+        yield
+    finally:
+        unload_host()
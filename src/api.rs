@@ -0,0 +1,426 @@
+//! The typed API, for Rust programs linking against netifaces.
+//!
+//! The backends answer with these types, which the Python module and the C ABI only
+//! format: every way of using netifaces reports the same interfaces, addresses and
+//! routes.
+
+use crate::backend;
+use crate::common::{InterfaceDisplay, InterfaceFilter, InterfaceRef, IpNetwork};
+use crate::error::NetifacesError;
+use crate::types::{
+    ip_bytes, mask_bytes_to_prefix, AF_INET, AF_INET6, RTNH_F_DEAD, RTNH_F_LINKDOWN,
+};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+pub type Result<T> = std::result::Result<T, NetifacesError>;
+
+/// An interface, along with all its addresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+    pub name: String,
+    pub index: u32,
+    /// The IFF_* flags; always 0 on Windows
+    pub flags: u32,
+    /// Whether the interface is up and can pass traffic
+    pub up: bool,
+    pub mtu: Option<u32>,
+    pub link_address: Option<LinkAddress>,
+    pub addresses: Vec<Address>,
+}
+
+/// The addresses of an interface, or of an IPv4 alias (e.g. `eth0:1`), which only has
+/// the addresses bearing its label and no link layer address.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InterfaceAddresses {
+    pub link_address: Option<LinkAddress>,
+    pub addresses: Vec<Address>,
+}
+
+/// An IP address held by an interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    pub ip: IpAddr,
+    /// The zone of a link-local IPv6 address: the index of its interface
+    pub scope_id: Option<u32>,
+    /// The length of the netmask; None if it is unknown or not contiguous
+    pub prefix_len: Option<u8>,
+    pub netmask: Option<IpAddr>,
+    pub broadcast: Option<IpAddr>,
+    /// The other end of a point-to-point link
+    pub peer: Option<IpAddr>,
+    /// The IFA_F_* flags; always 0 outside of Linux
+    pub flags: u32,
+}
+
+/// The netmask matching a prefix length, in the family of `ip`.
+fn prefix_to_mask(ip: &IpAddr, prefix_len: u8) -> IpAddr {
+    match ip {
+        IpAddr::V4(_) => {
+            let mask = u32::MAX
+                .checked_shl(32 - prefix_len.min(32) as u32)
+                .unwrap_or(0);
+            IpAddr::V4(Ipv4Addr::from(mask))
+        }
+        IpAddr::V6(_) => {
+            let mask = u128::MAX
+                .checked_shl(128 - prefix_len.min(128) as u32)
+                .unwrap_or(0);
+            IpAddr::V6(Ipv6Addr::from(mask))
+        }
+    }
+}
+
+fn is_link_local(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_link_local(),
+        IpAddr::V6(ip) => ip.segments()[0] & 0xffc0 == 0xfe80,
+    }
+}
+
+/// The AF_* family of an address.
+pub(crate) fn family_of(ip: &IpAddr) -> u8 {
+    match ip {
+        IpAddr::V4(_) => AF_INET,
+        IpAddr::V6(_) => AF_INET6,
+    }
+}
+
+impl Address {
+    /// An address with the netmask of a prefix length.
+    pub(crate) fn new(ip: IpAddr, prefix_len: Option<u8>) -> Address {
+        Address {
+            ip,
+            scope_id: None,
+            prefix_len,
+            netmask: prefix_len.map(|prefix_len| prefix_to_mask(&ip, prefix_len)),
+            broadcast: None,
+            peer: None,
+            flags: 0,
+        }
+    }
+
+    /// An address with a netmask, whose prefix length is known if it is contiguous.
+    pub(crate) fn with_netmask(ip: IpAddr, netmask: Option<IpAddr>) -> Address {
+        Address {
+            prefix_len: netmask
+                .as_ref()
+                .and_then(|netmask| mask_bytes_to_prefix(&ip_bytes(netmask))),
+            netmask,
+            ..Address::new(ip, None)
+        }
+    }
+
+    /// The address, held by the interface of the given index: a link-local IPv6
+    /// address is only meaningful in its zone.
+    pub(crate) fn on_interface(self, index: u32) -> Address {
+        let link_local = matches!(self.ip, IpAddr::V6(_)) && is_link_local(&self.ip);
+
+        Address {
+            scope_id: link_local.then_some(index),
+            ..self
+        }
+    }
+
+    /// The AF_INET or AF_INET6 family of the address.
+    pub fn family(&self) -> u8 {
+        family_of(&self.ip)
+    }
+
+    /// The network the address is in.
+    pub fn network(&self) -> Option<IpNetwork> {
+        Some(IpNetwork {
+            addr: self.ip,
+            prefix_len: self.prefix_len?,
+        })
+    }
+}
+
+/// A link layer address, e.g. a MAC address.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HardwareAddress(pub Vec<u8>);
+
+impl fmt::Display for HardwareAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bytes: Vec<String> = self.0.iter().map(|byte| format!("{byte:02x}")).collect();
+        write!(f, "{}", bytes.join(":"))
+    }
+}
+
+/// The link layer address of an interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkAddress {
+    pub address: HardwareAddress,
+    /// The broadcast address of the link, e.g. ff:ff:ff:ff:ff:ff on Ethernet
+    pub broadcast: Option<HardwareAddress>,
+    /// The address of the other end of a point-to-point link
+    pub peer: Option<HardwareAddress>,
+}
+
+/// A route of one of the routing tables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// The destination network; 0.0.0.0/0 or ::/0 for the default routes
+    pub destination: IpNetwork,
+    pub gateway: Option<IpAddr>,
+    /// The output interface
+    pub interface: Option<String>,
+    pub index: Option<u32>,
+    /// The preferred source address
+    pub source: Option<IpAddr>,
+    pub metric: Option<u32>,
+    pub table: u32,
+    /// The RTPROT_* protocol which installed the route
+    pub protocol: u8,
+    /// The RT_SCOPE_* scope
+    pub scope: u8,
+    /// The RTN_* route type
+    pub route_type: u8,
+    /// The paths of the route; multipath routes have several, and no gateway or
+    /// interface of their own
+    pub nexthops: Vec<NextHop>,
+    /// The nexthop object (or group) the route uses, if any
+    pub nexthop_id: Option<u32>,
+}
+
+impl Route {
+    /// The AF_INET or AF_INET6 family of the route.
+    pub fn family(&self) -> u8 {
+        family_of(&self.destination.addr)
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.destination.prefix_len == 0
+    }
+}
+
+/// One of the paths of a route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NextHop {
+    pub gateway: Option<IpAddr>,
+    pub interface: Option<String>,
+    pub index: Option<u32>,
+    /// The share of the traffic going through this path, relative to the other paths
+    pub weight: u32,
+    /// The RTNH_F_* flags
+    pub flags: u32,
+}
+
+impl NextHop {
+    /// Whether the kernel may send traffic through this path.
+    pub fn is_alive(&self) -> bool {
+        self.flags & (RTNH_F_DEAD | RTNH_F_LINKDOWN) == 0
+    }
+}
+
+/// An entry of the neighbour (ARP or NDP) tables, or of the forwarding database of a
+/// bridge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Neighbour {
+    /// The AF_* family of the table; AF_BRIDGE for the forwarding databases
+    pub family: u8,
+    pub ip: Option<IpAddr>,
+    pub hardware_address: Option<HardwareAddress>,
+    pub interface: Option<String>,
+    pub index: u32,
+    /// The NUD_* state
+    pub state: u16,
+    /// The NTF_* flags
+    pub flags: u8,
+}
+
+/// An address, along with the interface holding it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressOwner {
+    pub interface: String,
+    pub index: u32,
+    pub address: Address,
+}
+
+/// Every address of a snapshot, along with the interface holding it, sorted by index.
+pub(crate) fn address_owners(snapshot: Vec<Interface>) -> Vec<AddressOwner> {
+    snapshot
+        .into_iter()
+        .flat_map(|interface| {
+            let (name, index) = (interface.name, interface.index);
+            interface
+                .addresses
+                .into_iter()
+                .map(move |address| AddressOwner {
+                    interface: name.clone(),
+                    index,
+                    address,
+                })
+        })
+        .collect()
+}
+
+/// The source address of the traffic sent to `gateway` out of an interface holding
+/// `addresses`: the one on the gateway's subnet, else the first one that is not
+/// link-local. For the backends which cannot ask the kernel.
+pub(crate) fn source_for(addresses: &[Address], gateway: Option<IpAddr>) -> Option<IpAddr> {
+    let on_subnet = gateway.and_then(|gateway| {
+        let host = IpNetwork {
+            addr: gateway,
+            prefix_len: 8 * ip_bytes(&gateway).len() as u8,
+        };
+        addresses.iter().find(|address| {
+            address
+                .network()
+                .is_some_and(|subnet| subnet.overlaps(&host))
+        })
+    });
+
+    on_subnet
+        .or_else(|| addresses.iter().find(|address| !is_link_local(&address.ip)))
+        .map(|address| address.ip)
+}
+
+/// Find an interface of a snapshot by index, by the name it is displayed with, or by
+/// the name the system knows it by.
+pub(crate) fn find_interface(
+    snapshot: Vec<Interface>,
+    iface: &InterfaceRef,
+    if_nametoindex: impl Fn(&str) -> Option<u32>,
+) -> std::result::Result<Interface, Box<dyn std::error::Error>> {
+    let index = match iface {
+        InterfaceRef::Index(index) => Some(*index),
+        InterfaceRef::Name(name) => snapshot
+            .iter()
+            .find(|interface| interface.name == *name)
+            .map(|interface| interface.index)
+            .or_else(|| if_nametoindex(name)),
+    };
+
+    match snapshot
+        .into_iter()
+        .find(|interface| Some(interface.index) == index)
+    {
+        Some(interface) => Ok(interface),
+        None => Err(Box::new(iface.not_found())),
+    }
+}
+
+/// Every interface of the system, sorted by index.
+pub fn interfaces() -> Result<Vec<Interface>> {
+    snapshot(InterfaceDisplay::MachineReadable)
+}
+
+/// An interface, by index or by any of the names it can be displayed with.
+pub fn interface(iface: &InterfaceRef) -> Result<Interface> {
+    snapshot_interface(iface, InterfaceDisplay::MachineReadable)
+}
+
+/// Every interface of the system, sorted by index, named the way `interface_display`
+/// asks.
+pub fn snapshot(interface_display: InterfaceDisplay) -> Result<Vec<Interface>> {
+    backend::query(|backend| backend.snapshot(interface_display))
+        .map_err(NetifacesError::from_boxed)
+}
+
+/// Like [interface], named the way `interface_display` asks.
+pub fn snapshot_interface(
+    iface: &InterfaceRef,
+    interface_display: InterfaceDisplay,
+) -> Result<Interface> {
+    backend::query(|backend| backend.interface(iface, interface_display))
+        .map_err(NetifacesError::from_boxed)
+}
+
+/// The names of the interfaces passing a filter, sorted by index.
+pub fn interface_names(
+    interface_display: InterfaceDisplay,
+    filter: &InterfaceFilter,
+) -> Result<Vec<String>> {
+    backend::query(|backend| backend.interfaces(interface_display, filter))
+        .map_err(NetifacesError::from_boxed)
+}
+
+/// The names of the interfaces passing a filter, by index.
+pub fn interfaces_by_index(
+    interface_display: InterfaceDisplay,
+    filter: &InterfaceFilter,
+) -> Result<BTreeMap<u32, String>> {
+    backend::query(|backend| backend.interfaces_by_index(interface_display, filter))
+        .map_err(NetifacesError::from_boxed)
+}
+
+/// The addresses of an interface, or of an IPv4 alias (e.g. `eth0:1`).
+pub fn ifaddresses(iface: &InterfaceRef) -> Result<InterfaceAddresses> {
+    backend::query(|backend| backend.ifaddresses(iface)).map_err(NetifacesError::from_boxed)
+}
+
+/// Whether an interface is up and can pass traffic.
+pub fn interface_is_up(iface: &InterfaceRef) -> Result<bool> {
+    backend::query(|backend| backend.interface_is_up(iface)).map_err(NetifacesError::from_boxed)
+}
+
+/// The index of the interface with the given name.
+pub fn if_nametoindex(if_name: &str) -> Result<u32> {
+    backend::query(|backend| backend.if_nametoindex(if_name)).map_err(NetifacesError::from_boxed)
+}
+
+/// The name an interface is displayed with, given its index.
+pub fn if_indextoname(if_index: u32, interface_display: InterfaceDisplay) -> Result<String> {
+    backend::query(|backend| backend.if_indextoname(if_index, interface_display))
+        .map_err(NetifacesError::from_boxed)
+}
+
+/// The IPv4 and IPv6 routes of a family (AF_UNSPEC for both), in every routing table.
+/// Only the netlink and procfs backends can list them.
+pub fn routes(family: u8) -> Result<Vec<Route>> {
+    backend::query(|backend| backend.routes(family)).map_err(NetifacesError::from_boxed)
+}
+
+/// The default routes of a family in the main table, sorted by metric. Only the
+/// backends listing the routes can find them.
+pub fn default_routes(family: u8) -> Result<Vec<Route>> {
+    backend::query(|backend| backend.default_routes(family)).map_err(NetifacesError::from_boxed)
+}
+
+/// The default route currently carrying the traffic of a family, if any, with the
+/// gateway and interface of the path in use. Only the backends listing the routes can
+/// find it.
+pub fn primary_interface(family: u8) -> Result<Option<Route>> {
+    backend::query(|backend| backend.primary_interface(family)).map_err(NetifacesError::from_boxed)
+}
+
+/// The routes of a family (AF_UNSPEC for both) going out through an interface, by any
+/// of their paths.
+pub fn interface_routes(iface: &InterfaceRef, family: u8) -> Result<Vec<Route>> {
+    backend::query(|backend| backend.interface_routes(iface, family))
+        .map_err(NetifacesError::from_boxed)
+}
+
+/// The route the kernel would use for a packet to `destination` (which may carry an
+/// IPv6 zone), optionally from `source`, out of `oif`, or with a firewall mark. None if
+/// there is no route. Only available with the netlink backend.
+pub fn route_get(
+    destination: &str,
+    source: Option<&str>,
+    oif: Option<&InterfaceRef>,
+    mark: Option<u32>,
+) -> Result<Option<Route>> {
+    backend::query(|backend| backend.route_get(destination, source, oif, mark))
+        .map_err(NetifacesError::from_boxed)
+}
+
+/// The interface holding a local address, which may carry an IPv6 zone
+/// (e.g. `fe80::1%eth0`).
+pub fn interface_for_address(ip: &str) -> Result<Option<AddressOwner>> {
+    backend::query(|backend| backend.interface_for_address(ip)).map_err(NetifacesError::from_boxed)
+}
+
+/// The addresses on a network given in CIDR notation, along with their interfaces,
+/// sorted by index.
+pub fn interfaces_in_network(network: &str) -> Result<Vec<AddressOwner>> {
+    backend::query(|backend| backend.interfaces_in_network(network))
+        .map_err(NetifacesError::from_boxed)
+}
+
+/// The entries of the neighbour tables of a family (AF_UNSPEC for all of them). Only
+/// available on Linux.
+pub fn neighbours(family: u8) -> Result<Vec<Neighbour>> {
+    backend::query(|backend| backend.neighbours(family)).map_err(NetifacesError::from_boxed)
+}
@@ -0,0 +1,824 @@
+//! The backends answering the queries, and which of them is in use.
+//!
+//! Every backend this platform has is built in, so that they can be compared on the same
+//! host. The first one of [all] is used unless another is selected, through
+//! [set_backend] or the NETIFACES_BACKEND environment variable. When the system refuses
+//! it, e.g. in a sandbox forbidding netlink sockets, netifaces falls back to the one of
+//! [fallback].
+
+use crate::api::{self, AddressOwner, Interface, InterfaceAddresses, Neighbour, Route};
+use crate::common::{parse_zoned_ip, InterfaceDisplay, InterfaceFilter, InterfaceRef, IpNetwork};
+use crate::error::NetifacesError;
+use crate::types::{IfacesByIndex, AF_INET, AF_INET6, RTN_UNICAST, RT_TABLE_MAIN};
+#[cfg(all(feature = "python", target_os = "linux"))]
+use crate::wait::{WaitTarget, Waiter};
+use std::net::IpAddr;
+use std::sync::RwLock;
+
+/// The environment variable selecting the backend, by name.
+pub const BACKEND_VARIABLE: &str = "NETIFACES_BACKEND";
+
+/// A way of querying the interfaces, their addresses and status, and the routes.
+pub(crate) trait Backend: Send + Sync {
+    /// The name the backend is selected by, which is also the one of its errors.
+    fn name(&self) -> &'static str;
+
+    fn interfaces(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>>;
+
+    fn interfaces_by_index(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<IfacesByIndex, Box<dyn std::error::Error>>;
+
+    fn if_nametoindex(&self, if_name: &str) -> Result<u32, Box<dyn std::error::Error>>;
+
+    fn if_indextoname(
+        &self,
+        if_index: u32,
+        display: InterfaceDisplay,
+    ) -> Result<String, Box<dyn std::error::Error>>;
+
+    fn ifaddresses(
+        &self,
+        iface: &InterfaceRef,
+    ) -> Result<InterfaceAddresses, Box<dyn std::error::Error>>;
+
+    /// Every interface with its addresses, sorted by index.
+    fn snapshot(
+        &self,
+        display: InterfaceDisplay,
+    ) -> Result<Vec<Interface>, Box<dyn std::error::Error>>;
+
+    /// A single interface with its addresses, by index or by any name it can be
+    /// displayed with.
+    fn interface(
+        &self,
+        iface: &InterfaceRef,
+        display: InterfaceDisplay,
+    ) -> Result<Interface, Box<dyn std::error::Error>> {
+        api::find_interface(self.snapshot(display)?, iface, |name| {
+            self.if_nametoindex(name).ok()
+        })
+    }
+
+    fn interface_is_up(&self, iface: &InterfaceRef) -> Result<bool, Box<dyn std::error::Error>>;
+
+    /// The routes of a family (AF_UNSPEC for all of them).
+    fn routes(&self, family: u8) -> Result<Vec<Route>, Box<dyn std::error::Error>> {
+        let _ = family;
+        Err(self.unsupported("list the routes"))
+    }
+
+    /// The default routes of the main table in a family (AF_UNSPEC for all of them),
+    /// sorted by family then by metric: the kernel uses the first one that can be used.
+    fn default_routes(&self, family: u8) -> Result<Vec<Route>, Box<dyn std::error::Error>> {
+        let mut routes: Vec<Route> = self
+            .routes(family)?
+            .into_iter()
+            .filter(|route| route.is_default() && route.route_type == RTN_UNICAST)
+            .filter(|route| route.table == RT_TABLE_MAIN)
+            .collect();
+        // IPv4 routes without a metric have a metric of 0
+        routes.sort_by_key(|route| (route.family(), route.metric.unwrap_or(0)));
+
+        Ok(routes)
+    }
+
+    /// The default route currently carrying the traffic of a family: the one with the
+    /// lowest metric whose interface is running, reported with the gateway and
+    /// interface of its first usable path. Without a preferred source, its source is
+    /// the one of [Backend::default_source].
+    fn primary_interface(&self, family: u8) -> Result<Option<Route>, Box<dyn std::error::Error>> {
+        check_ip_family(family, "find the primary interface")?;
+
+        for route in self.default_routes(family)? {
+            for hop in route.nexthops.iter().filter(|hop| hop.is_alive()) {
+                let iface = match hop.index {
+                    Some(index) => InterfaceRef::Index(index),
+                    None => continue,
+                };
+                if !self.interface_is_up(&iface).unwrap_or(false) {
+                    continue;
+                }
+
+                let source = match route.source {
+                    Some(source) => Some(source),
+                    None => self.default_source(family, &iface, hop.gateway)?,
+                };
+
+                return Ok(Some(Route {
+                    gateway: hop.gateway,
+                    interface: hop.interface.clone(),
+                    index: hop.index,
+                    source,
+                    ..route.clone()
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// The address the default traffic of a family is sent from, out of an interface
+    /// towards a gateway, when the route does not set one: the address of that interface
+    /// on the gateway's subnet.
+    fn default_source(
+        &self,
+        family: u8,
+        iface: &InterfaceRef,
+        gateway: Option<IpAddr>,
+    ) -> Result<Option<IpAddr>, Box<dyn std::error::Error>> {
+        let addresses: Vec<api::Address> = self
+            .ifaddresses(iface)?
+            .addresses
+            .into_iter()
+            .filter(|address| address.family() == family)
+            .collect();
+
+        Ok(api::source_for(&addresses, gateway))
+    }
+
+    /// The routes of a family (AF_UNSPEC for all of them) going out through an
+    /// interface, by any of their paths.
+    fn interface_routes(
+        &self,
+        iface: &InterfaceRef,
+        family: u8,
+    ) -> Result<Vec<Route>, Box<dyn std::error::Error>> {
+        let index = self
+            .interface(iface, InterfaceDisplay::MachineReadable)?
+            .index;
+
+        Ok(self
+            .routes(family)?
+            .into_iter()
+            .filter(|route| route.nexthops.iter().any(|hop| hop.index == Some(index)))
+            .collect())
+    }
+
+    /// The route the kernel would use for a packet to `destination`, with the output
+    /// interface, gateway and preferred source address resolved. None if there is no
+    /// route.
+    fn route_get(
+        &self,
+        destination: &str,
+        source: Option<&str>,
+        oif: Option<&InterfaceRef>,
+        mark: Option<u32>,
+    ) -> Result<Option<Route>, Box<dyn std::error::Error>> {
+        let _ = (destination, source, oif, mark);
+        Err(self.unsupported("look up routes"))
+    }
+
+    /// The interface holding a local address. An IPv6 zone (e.g. "fe80::1%eth0")
+    /// restricts the search to one interface; otherwise, the one with the lowest index
+    /// is returned.
+    fn interface_for_address(
+        &self,
+        ip: &str,
+    ) -> Result<Option<AddressOwner>, Box<dyn std::error::Error>> {
+        let (addr, zone) = parse_zoned_ip(ip)?;
+        let zone = match zone {
+            Some(zone) => Some(self.if_nametoindex(zone)?),
+            None => None,
+        };
+
+        Ok(
+            api::address_owners(self.snapshot(InterfaceDisplay::MachineReadable)?)
+                .into_iter()
+                .find(|owner| {
+                    owner.address.ip == addr && zone.is_none_or(|index| owner.index == index)
+                }),
+        )
+    }
+
+    /// The addresses whose subnet overlaps a network, i.e. the interfaces on that
+    /// network, sorted by index.
+    fn interfaces_in_network(
+        &self,
+        network: &str,
+    ) -> Result<Vec<AddressOwner>, Box<dyn std::error::Error>> {
+        let network = IpNetwork::parse(network)?;
+
+        Ok(
+            api::address_owners(self.snapshot(InterfaceDisplay::MachineReadable)?)
+                .into_iter()
+                .filter(|owner| {
+                    owner
+                        .address
+                        .network()
+                        .is_some_and(|subnet| subnet.overlaps(&network))
+                })
+                .collect(),
+        )
+    }
+
+    /// The entries of the neighbour (ARP and NDP) tables of a family (AF_UNSPEC for all
+    /// of them).
+    fn neighbours(&self, family: u8) -> Result<Vec<Neighbour>, Box<dyn std::error::Error>> {
+        let _ = family;
+        Err(self.unsupported("list the neighbours"))
+    }
+
+    /// Start waiting for an interface or for the network; the wait is run by the caller.
+    #[cfg(all(feature = "python", target_os = "linux"))]
+    fn waiter(&self, target: WaitTarget) -> Result<Waiter, Box<dyn std::error::Error>> {
+        let _ = target;
+        Err(self.unsupported("wait for changes"))
+    }
+
+    /// Keep the interfaces, addresses and routes in memory, up to date with the changes
+    /// the kernel notifies.
+    #[cfg(all(feature = "python", target_os = "linux"))]
+    fn enable_cache(&self) -> Result<(), Box<dyn std::error::Error>> {
+        Err(self.unsupported("keep a cache"))
+    }
+
+    /// The error of the operations the backend cannot do.
+    fn unsupported(&self, operation: &str) -> Box<dyn std::error::Error> {
+        Box::new(NetifacesError::Unsupported(format!(
+            "The {} backend cannot {operation}",
+            self.name()
+        )))
+    }
+}
+
+/// Reject the families other than AF_INET and AF_INET6, for the queries about a
+/// single one; `operation` tells what cannot be done, e.g. "find the primary interface".
+pub(crate) fn check_ip_family(family: u8, operation: &str) -> Result<(), NetifacesError> {
+    match family {
+        AF_INET | AF_INET6 => Ok(()),
+        _ => Err(NetifacesError::Other(format!(
+            "Cannot {operation} in family {family}"
+        ))),
+    }
+}
+
+/// Netlink, on Linux.
+#[cfg(target_os = "linux")]
+struct Netlink;
+
+#[cfg(target_os = "linux")]
+impl Backend for Netlink {
+    fn name(&self) -> &'static str {
+        "netlink"
+    }
+
+    fn interfaces(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_interfaces(display, filter)
+    }
+
+    fn interfaces_by_index(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<IfacesByIndex, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_interfaces_by_index(display, filter)
+    }
+
+    fn if_nametoindex(&self, if_name: &str) -> Result<u32, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_if_nametoindex(if_name)
+    }
+
+    fn if_indextoname(
+        &self,
+        if_index: u32,
+        display: InterfaceDisplay,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_if_indextoname(if_index, display)
+    }
+
+    fn ifaddresses(
+        &self,
+        iface: &InterfaceRef,
+    ) -> Result<InterfaceAddresses, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_ifaddresses(iface)
+    }
+
+    fn snapshot(
+        &self,
+        display: InterfaceDisplay,
+    ) -> Result<Vec<Interface>, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_snapshot(display)
+    }
+
+    fn interface(
+        &self,
+        iface: &InterfaceRef,
+        display: InterfaceDisplay,
+    ) -> Result<Interface, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_interface(iface, display)
+    }
+
+    fn interface_is_up(&self, iface: &InterfaceRef) -> Result<bool, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_interface_is_up(iface)
+    }
+
+    fn routes(&self, family: u8) -> Result<Vec<Route>, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_routes(family)
+    }
+
+    // The kernel tells which source address it picks
+    fn default_source(
+        &self,
+        family: u8,
+        iface: &InterfaceRef,
+        _gateway: Option<IpAddr>,
+    ) -> Result<Option<IpAddr>, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_default_source(family, iface)
+    }
+
+    fn interface_routes(
+        &self,
+        iface: &InterfaceRef,
+        family: u8,
+    ) -> Result<Vec<Route>, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_interface_routes(iface, family)
+    }
+
+    fn route_get(
+        &self,
+        destination: &str,
+        source: Option<&str>,
+        oif: Option<&InterfaceRef>,
+        mark: Option<u32>,
+    ) -> Result<Option<Route>, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_route_get(destination, source, oif, mark)
+    }
+
+    fn interface_for_address(
+        &self,
+        ip: &str,
+    ) -> Result<Option<AddressOwner>, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_interface_for_address(ip)
+    }
+
+    fn interfaces_in_network(
+        &self,
+        network: &str,
+    ) -> Result<Vec<AddressOwner>, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_interfaces_in_network(network)
+    }
+
+    fn neighbours(&self, family: u8) -> Result<Vec<Neighbour>, Box<dyn std::error::Error>> {
+        crate::linux_netlink::netlink_neighbours(family)
+    }
+
+    #[cfg(feature = "python")]
+    fn waiter(&self, target: WaitTarget) -> Result<Waiter, Box<dyn std::error::Error>> {
+        Waiter::new(target)
+    }
+
+    #[cfg(feature = "python")]
+    fn enable_cache(&self) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(crate::cache::enable()?)
+    }
+}
+
+/// The files of /proc/net and /sys/class/net, which stay readable where netlink sockets
+/// are forbidden.
+#[cfg(target_os = "linux")]
+struct Procfs;
+
+#[cfg(target_os = "linux")]
+impl Backend for Procfs {
+    fn name(&self) -> &'static str {
+        "procfs"
+    }
+
+    fn interfaces(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        crate::linux_procfs::procfs_interfaces(display, filter)
+    }
+
+    fn interfaces_by_index(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<IfacesByIndex, Box<dyn std::error::Error>> {
+        crate::linux_procfs::procfs_interfaces_by_index(display, filter)
+    }
+
+    fn if_nametoindex(&self, if_name: &str) -> Result<u32, Box<dyn std::error::Error>> {
+        crate::linux_procfs::procfs_if_nametoindex(if_name)
+    }
+
+    fn if_indextoname(
+        &self,
+        if_index: u32,
+        display: InterfaceDisplay,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        crate::linux_procfs::procfs_if_indextoname(if_index, display)
+    }
+
+    fn ifaddresses(
+        &self,
+        iface: &InterfaceRef,
+    ) -> Result<InterfaceAddresses, Box<dyn std::error::Error>> {
+        crate::linux_procfs::procfs_ifaddresses(iface)
+    }
+
+    fn snapshot(
+        &self,
+        display: InterfaceDisplay,
+    ) -> Result<Vec<Interface>, Box<dyn std::error::Error>> {
+        crate::linux_procfs::procfs_snapshot(display)
+    }
+
+    fn interface(
+        &self,
+        iface: &InterfaceRef,
+        display: InterfaceDisplay,
+    ) -> Result<Interface, Box<dyn std::error::Error>> {
+        crate::linux_procfs::procfs_interface(iface, display)
+    }
+
+    fn interface_is_up(&self, iface: &InterfaceRef) -> Result<bool, Box<dyn std::error::Error>> {
+        crate::linux_procfs::procfs_interface_is_up(iface)
+    }
+
+    fn routes(&self, family: u8) -> Result<Vec<Route>, Box<dyn std::error::Error>> {
+        crate::procfs::procfs_ip_routes(family)
+    }
+
+    fn neighbours(&self, family: u8) -> Result<Vec<Neighbour>, Box<dyn std::error::Error>> {
+        match family {
+            crate::types::AF_INET6 => Err(self.unsupported("list the IPv6 neighbours")),
+            _ => crate::linux_procfs::procfs_neighbours(family),
+        }
+    }
+}
+
+/// getifaddrs and the ioctls of the POSIX platforms.
+#[cfg(not(target_family = "windows"))]
+struct Getifaddrs;
+
+#[cfg(not(target_family = "windows"))]
+impl Backend for Getifaddrs {
+    fn name(&self) -> &'static str {
+        "getifaddrs"
+    }
+
+    fn interfaces(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        crate::linux::posix_interfaces(display, filter)
+    }
+
+    fn interfaces_by_index(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<IfacesByIndex, Box<dyn std::error::Error>> {
+        crate::linux::posix_interfaces_by_index(display, filter)
+    }
+
+    fn if_nametoindex(&self, if_name: &str) -> Result<u32, Box<dyn std::error::Error>> {
+        crate::linux::posix_if_nametoindex(if_name)
+    }
+
+    fn if_indextoname(
+        &self,
+        if_index: u32,
+        display: InterfaceDisplay,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        crate::linux::posix_if_indextoname(if_index, display)
+    }
+
+    fn ifaddresses(
+        &self,
+        iface: &InterfaceRef,
+    ) -> Result<InterfaceAddresses, Box<dyn std::error::Error>> {
+        crate::linux::posix_ifaddresses(iface)
+    }
+
+    // Interface names only have one form with getifaddrs
+    fn snapshot(
+        &self,
+        _display: InterfaceDisplay,
+    ) -> Result<Vec<Interface>, Box<dyn std::error::Error>> {
+        crate::linux::posix_snapshot()
+    }
+
+    fn interface_is_up(&self, iface: &InterfaceRef) -> Result<bool, Box<dyn std::error::Error>> {
+        crate::linux::posix_interface_is_up(iface)
+    }
+}
+
+/// The IP Helper API of Windows.
+#[cfg(target_family = "windows")]
+struct Iphlpapi;
+
+#[cfg(target_family = "windows")]
+impl Backend for Iphlpapi {
+    fn name(&self) -> &'static str {
+        "iphlpapi"
+    }
+
+    fn interfaces(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        crate::win::windows_interfaces(display, filter)
+    }
+
+    fn interfaces_by_index(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<IfacesByIndex, Box<dyn std::error::Error>> {
+        crate::win::windows_interfaces_by_index(display, filter)
+    }
+
+    fn if_nametoindex(&self, if_name: &str) -> Result<u32, Box<dyn std::error::Error>> {
+        crate::win::windows_if_nametoindex(if_name)
+    }
+
+    fn if_indextoname(
+        &self,
+        if_index: u32,
+        display: InterfaceDisplay,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        crate::win::windows_if_indextoname(if_index, display)
+    }
+
+    fn ifaddresses(
+        &self,
+        iface: &InterfaceRef,
+    ) -> Result<InterfaceAddresses, Box<dyn std::error::Error>> {
+        crate::win::windows_ifaddresses(iface)
+    }
+
+    fn snapshot(
+        &self,
+        display: InterfaceDisplay,
+    ) -> Result<Vec<Interface>, Box<dyn std::error::Error>> {
+        crate::win::windows_snapshot(display)
+    }
+
+    fn interface_is_up(&self, iface: &InterfaceRef) -> Result<bool, Box<dyn std::error::Error>> {
+        crate::win::windows_interface_is_up(iface)
+    }
+}
+
+/// A synthetic host, loaded by `netifaces.testing`.
+#[cfg(feature = "python")]
+pub(crate) struct Mock;
+
+#[cfg(feature = "python")]
+impl Backend for Mock {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn interfaces(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        crate::mock::mock_interfaces(display, filter)
+    }
+
+    fn interfaces_by_index(
+        &self,
+        display: InterfaceDisplay,
+        filter: &InterfaceFilter,
+    ) -> Result<IfacesByIndex, Box<dyn std::error::Error>> {
+        crate::mock::mock_interfaces_by_index(display, filter)
+    }
+
+    fn if_nametoindex(&self, if_name: &str) -> Result<u32, Box<dyn std::error::Error>> {
+        crate::mock::mock_if_nametoindex(if_name)
+    }
+
+    fn if_indextoname(
+        &self,
+        if_index: u32,
+        display: InterfaceDisplay,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        crate::mock::mock_if_indextoname(if_index, display)
+    }
+
+    fn ifaddresses(
+        &self,
+        iface: &InterfaceRef,
+    ) -> Result<InterfaceAddresses, Box<dyn std::error::Error>> {
+        crate::mock::mock_ifaddresses(iface)
+    }
+
+    fn snapshot(
+        &self,
+        display: InterfaceDisplay,
+    ) -> Result<Vec<Interface>, Box<dyn std::error::Error>> {
+        crate::mock::mock_snapshot(display)
+    }
+
+    fn interface(
+        &self,
+        iface: &InterfaceRef,
+        display: InterfaceDisplay,
+    ) -> Result<Interface, Box<dyn std::error::Error>> {
+        crate::mock::mock_interface(iface, display)
+    }
+
+    fn interface_is_up(&self, iface: &InterfaceRef) -> Result<bool, Box<dyn std::error::Error>> {
+        crate::mock::mock_interface_is_up(iface)
+    }
+
+    fn routes(&self, family: u8) -> Result<Vec<Route>, Box<dyn std::error::Error>> {
+        crate::mock::mock_routes(family)
+    }
+
+    fn route_get(
+        &self,
+        destination: &str,
+        source: Option<&str>,
+        oif: Option<&InterfaceRef>,
+        mark: Option<u32>,
+    ) -> Result<Option<Route>, Box<dyn std::error::Error>> {
+        crate::mock::mock_route_get(destination, source, oif, mark)
+    }
+}
+
+/// The backends of this platform, the default one first.
+fn all() -> Vec<&'static dyn Backend> {
+    vec![
+        #[cfg(target_os = "linux")]
+        &Netlink,
+        #[cfg(target_os = "linux")]
+        &Procfs,
+        #[cfg(not(target_family = "windows"))]
+        &Getifaddrs,
+        #[cfg(target_family = "windows")]
+        &Iphlpapi,
+    ]
+}
+
+fn find(name: &str) -> Option<&'static dyn Backend> {
+    all().into_iter().find(|backend| backend.name() == name)
+}
+
+/// The backend taking over when the system refuses the default one, e.g. when a seccomp
+/// profile forbids netlink sockets.
+fn fallback() -> Option<&'static dyn Backend> {
+    #[cfg(target_os = "linux")]
+    return Some(&Procfs);
+
+    #[cfg(not(target_os = "linux"))]
+    None
+}
+
+/// The backend answering the queries, and whether it was picked by netifaces rather
+/// than by the user, in which case it may fall back to another one.
+#[derive(Clone, Copy)]
+struct Selection {
+    backend: &'static dyn Backend,
+    automatic: bool,
+}
+
+/// The selection; None until the first query, which reads NETIFACES_BACKEND.
+static SELECTED: RwLock<Option<Selection>> = RwLock::new(None);
+
+/// The backend of NETIFACES_BACKEND, or the default one.
+fn from_environment() -> Selection {
+    let default = Selection {
+        backend: all()[0],
+        automatic: true,
+    };
+
+    match std::env::var(BACKEND_VARIABLE) {
+        Ok(name) => match find(&name) {
+            Some(backend) => Selection {
+                backend,
+                automatic: false,
+            },
+            None => {
+                log::warn!("netifaces ignores the unknown backend {name} of {BACKEND_VARIABLE}");
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
+/// A backend answering the queries in place of the selected one, whatever the
+/// selection; the selection is used again once it is removed.
+static OVERRIDE: RwLock<Option<&'static dyn Backend>> = RwLock::new(None);
+
+fn selection() -> Selection {
+    if let Some(backend) = *OVERRIDE.read().unwrap() {
+        return Selection {
+            backend,
+            automatic: false,
+        };
+    }
+
+    if let Some(selection) = *SELECTED.read().unwrap() {
+        return selection;
+    }
+
+    *SELECTED
+        .write()
+        .unwrap()
+        .get_or_insert_with(from_environment)
+}
+
+/// The backend answering the queries.
+pub(crate) fn current() -> &'static dyn Backend {
+    selection().backend
+}
+
+/// Run a query on the current backend. When the system refuses a backend netifaces
+/// picked, the fallback one answers this query and the next ones; a backend selected
+/// by the user is kept whatever its errors.
+pub(crate) fn query<T>(
+    run: impl Fn(&'static dyn Backend) -> Result<T, Box<dyn std::error::Error>>,
+) -> Result<T, Box<dyn std::error::Error>> {
+    query_naming_backend(run).map_err(|(err, _)| err.into())
+}
+
+/// Like [query], with the name of the backend which failed along with its error: the
+/// fallback one when it took over.
+pub(crate) fn query_naming_backend<T>(
+    run: impl Fn(&'static dyn Backend) -> Result<T, Box<dyn std::error::Error>>,
+) -> Result<T, (NetifacesError, &'static str)> {
+    let selection = selection();
+    let err = match run(selection.backend) {
+        Ok(result) => return Ok(result),
+        Err(err) => NetifacesError::from_boxed(err),
+    };
+
+    let fallback = match fallback() {
+        Some(fallback) if selection.automatic && fallback.name() != selection.backend.name() => {
+            fallback
+        }
+        _ => return Err((err, selection.backend.name())),
+    };
+    if !err.is_permission_denied() && !err.is_backend_unavailable() {
+        return Err((err, selection.backend.name()));
+    }
+
+    log::warn!(
+        "netifaces falls back to the {} backend, the {} one failed: {err}",
+        fallback.name(),
+        selection.backend.name()
+    );
+    *SELECTED.write().unwrap() = Some(Selection {
+        backend: fallback,
+        automatic: true,
+    });
+
+    run(fallback).map_err(|err| (NetifacesError::from_boxed(err), fallback.name()))
+}
+
+/// Answer the queries with a backend that cannot be selected, e.g. the mock one, until
+/// it is removed with None.
+#[cfg(feature = "python")]
+pub(crate) fn set_override(backend: Option<&'static dyn Backend>) {
+    *OVERRIDE.write().unwrap() = backend;
+}
+
+/// The names of the backends of this platform, the default one first.
+pub fn backends() -> Vec<&'static str> {
+    all().into_iter().map(|backend| backend.name()).collect()
+}
+
+/// The name of the backend answering the queries.
+pub fn backend() -> &'static str {
+    current().name()
+}
+
+/// Answer the queries with the backend of the given name, or with the one of
+/// NETIFACES_BACKEND (or the default one) if None.
+pub fn set_backend(name: Option<&str>) -> Result<(), NetifacesError> {
+    let selection = match name {
+        Some(name) => Selection {
+            backend: find(name).ok_or_else(|| {
+                NetifacesError::Unsupported(format!(
+                    "Unknown backend {name}, the ones of this platform are {}",
+                    backends().join(", ")
+                ))
+            })?,
+            automatic: false,
+        },
+        None => from_environment(),
+    };
+    *SELECTED.write().unwrap() = Some(selection);
+
+    Ok(())
+}
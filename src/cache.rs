@@ -0,0 +1,334 @@
+//! An opt-in cache of the links, addresses and routes of the system.
+//!
+//! Once enabled, a background thread listens to the kernel's netlink notifications
+//! and keeps an in-memory model up to date, so that queries can be answered without
+//! any system call. Every change bumps the model's generation, which lets callers
+//! tell whether a result they hold is stale.
+
+use crate::api;
+use crate::common::{InterfaceDisplay, InterfaceFilter, InterfaceRef};
+use crate::linux_netlink::{
+    display_name, filter_links, interface_addresses, ip_routes, LinkSource,
+};
+use crate::netlink::{
+    Address, Link, Message, NetlinkSocket, Route, RTM_DELADDR, RTM_DELLINK, RTM_DELNEXTHOP,
+    RTM_DELROUTE, RTM_NEWADDR, RTM_NEWLINK, RTM_NEWNEXTHOP, RTM_NEWROUTE, RTNLGRP_IPV4_IFADDR,
+    RTNLGRP_IPV4_ROUTE, RTNLGRP_IPV6_IFADDR, RTNLGRP_IPV6_ROUTE, RTNLGRP_LINK, RTNLGRP_NEXTHOP,
+};
+use crate::types::{IfacesByIndex, AF_INET, AF_UNSPEC};
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// The result of a fallible query, along with the generation of the model it was
+/// read from; None if the cache is not enabled.
+type CachedResult<T> = Option<Result<(u64, T), Box<dyn std::error::Error>>>;
+
+/// How often the background thread checks whether it was asked to stop.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The cached state of the system.
+pub struct Model {
+    /// Incremented on every change to the model
+    pub generation: u64,
+    /// The links, by index
+    pub links: BTreeMap<u32, Link>,
+    pub addresses: Vec<Address>,
+    pub routes: Vec<Route>,
+    /// Cleared if the background thread stopped tracking the system, e.g. after an error
+    valid: bool,
+}
+
+impl Model {
+    fn load(sock: &mut NetlinkSocket, generation: u64) -> io::Result<Model> {
+        Ok(Model {
+            generation,
+            links: sock
+                .links()?
+                .into_iter()
+                .map(|link| (link.index, link))
+                .collect(),
+            addresses: sock.addresses(AF_UNSPEC)?,
+            routes: sock.routes(AF_UNSPEC)?,
+            valid: true,
+        })
+    }
+
+    pub fn link_by_name(&self, name: &str) -> Option<&Link> {
+        self.links.values().find(|link| link.has_name(name))
+    }
+
+    pub fn link(&self, iface: &InterfaceRef) -> Option<&Link> {
+        match iface {
+            InterfaceRef::Name(name) => self.link_by_name(name),
+            InterfaceRef::Index(index) => self.links.get(index),
+        }
+    }
+
+    /// Apply a link or address notification. Route notifications are not applied one
+    /// by one; the routes are dumped again instead.
+    fn apply(&mut self, message: &Message) {
+        match message.kind {
+            // Bridges also report changes to their ports with AF_BRIDGE messages, which
+            // only carry some of the attributes of the link
+            RTM_NEWLINK | RTM_DELLINK if message.payload.first() != Some(&AF_UNSPEC) => {}
+            RTM_NEWLINK => {
+                if let Some(link) = Link::parse(&message.payload) {
+                    self.links.insert(link.index, link);
+                }
+            }
+            RTM_DELLINK => {
+                if let Some(link) = Link::parse(&message.payload) {
+                    self.links.remove(&link.index);
+                    self.addresses.retain(|address| address.index != link.index);
+                }
+            }
+            RTM_NEWADDR | RTM_DELADDR => {
+                if let Some(address) = Address::parse(&message.payload) {
+                    self.addresses
+                        .retain(|known| !same_address(known, &address));
+                    if message.kind == RTM_NEWADDR {
+                        self.addresses.push(address);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'a> LinkSource for &'a Model {
+    type Link = &'a Link;
+    type Address = &'a Address;
+
+    fn link(
+        &mut self,
+        iface: &InterfaceRef,
+    ) -> Result<Option<&'a Link>, Box<dyn std::error::Error>> {
+        Ok(Model::link(self, iface))
+    }
+
+    fn links(&mut self) -> Result<Vec<&'a Link>, Box<dyn std::error::Error>> {
+        Ok(self.links.values().collect())
+    }
+
+    fn addresses_on(
+        &mut self,
+        link: &Link,
+        _family: u8,
+    ) -> Result<Vec<&'a Address>, Box<dyn std::error::Error>> {
+        let index = link.index;
+        Ok(self
+            .addresses
+            .iter()
+            .filter(|address| address.index == index)
+            .collect())
+    }
+}
+
+/// Whether a notification may have changed the routes. Routes using a nexthop object
+/// change along with it, and the kernel removes the IPv4 routes through a link going
+/// down or away, or through a removed IPv4 address, without notifications of their own.
+fn is_route_change(model: &Model, message: &Message) -> bool {
+    match message.kind {
+        RTM_NEWROUTE | RTM_DELROUTE | RTM_NEWNEXTHOP | RTM_DELNEXTHOP => true,
+        RTM_NEWLINK | RTM_DELLINK if message.payload.first() != Some(&AF_UNSPEC) => false,
+        RTM_DELLINK => true,
+        RTM_NEWLINK => match Link::parse(&message.payload) {
+            Some(link) => model
+                .links
+                .get(&link.index)
+                .is_some_and(|known| known.is_up() != link.is_up()),
+            None => false,
+        },
+        RTM_DELADDR => message.payload.first() == Some(&AF_INET),
+        _ => false,
+    }
+}
+
+/// Whether two addresses are the same, i.e. one is an update of the other.
+fn same_address(a: &Address, b: &Address) -> bool {
+    a.index == b.index
+        && a.family == b.family
+        && a.prefix_len == b.prefix_len
+        && a.local_ip() == b.local_ip()
+}
+
+struct Cache {
+    model: Arc<RwLock<Model>>,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+static CACHE: Mutex<Option<Cache>> = Mutex::new(None);
+
+/// Keep the model up to date until asked to stop.
+fn track(
+    mut events: NetlinkSocket,
+    mut queries: NetlinkSocket,
+    model: &RwLock<Model>,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    while !stop.load(Ordering::Relaxed) {
+        if !events.wait_readable(Some(STOP_CHECK_INTERVAL))? {
+            continue;
+        }
+
+        match events.receive() {
+            Ok(messages) => {
+                // Dump the routes before taking the write lock, so readers are not held up
+                let routes_changed = {
+                    let state = model.read().unwrap();
+                    messages
+                        .iter()
+                        .any(|message| is_route_change(&state, message))
+                };
+                let routes = match routes_changed {
+                    true => Some(queries.routes(AF_UNSPEC)?),
+                    false => None,
+                };
+
+                let mut state = model.write().unwrap();
+                for message in &messages {
+                    state.apply(message);
+                }
+                if let Some(routes) = routes {
+                    state.routes = routes;
+                }
+                state.generation += 1;
+            }
+            // Notifications were lost, so start over from a full dump
+            Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => {
+                let generation = model.read().unwrap().generation + 1;
+                let reloaded = Model::load(&mut queries, generation)?;
+                *model.write().unwrap() = reloaded;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Enable the cache. Returns once the model is loaded; does nothing if the cache
+/// is already enabled.
+pub fn enable() -> io::Result<()> {
+    let mut cache = CACHE.lock().unwrap();
+
+    if cache.is_some() {
+        return Ok(());
+    }
+
+    // Subscribe before loading the model, so that no change is missed in between
+    let events = NetlinkSocket::new()?;
+    events.subscribe(&[
+        RTNLGRP_LINK,
+        RTNLGRP_IPV4_IFADDR,
+        RTNLGRP_IPV6_IFADDR,
+        RTNLGRP_IPV4_ROUTE,
+        RTNLGRP_IPV6_ROUTE,
+    ])?;
+    // Kernels before 5.3 have neither nexthop objects nor this group
+    let _ = events.subscribe(&[RTNLGRP_NEXTHOP]);
+    let mut queries = NetlinkSocket::new()?;
+
+    let model = Arc::new(RwLock::new(Model::load(&mut queries, 0)?));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let thread = {
+        let model = model.clone();
+        let stop = stop.clone();
+
+        std::thread::Builder::new()
+            .name("netifaces-cache".to_string())
+            .spawn(move || {
+                if let Err(e) = track(events, queries, &model, &stop) {
+                    log::warn!("netifaces cache stopped tracking the system: {e}");
+                    model.write().unwrap().valid = false;
+                }
+            })?
+    };
+
+    *cache = Some(Cache {
+        model,
+        stop,
+        thread,
+    });
+
+    Ok(())
+}
+
+/// Disable the cache, and wait for its background thread to finish.
+pub fn disable() {
+    let cache = CACHE.lock().unwrap().take();
+
+    if let Some(cache) = cache {
+        cache.stop.store(true, Ordering::Relaxed);
+        // The thread only ends early after logging its error, there is nothing more to report
+        let _ = cache.thread.join();
+    }
+}
+
+/// Run a query against the model. Returns None if the cache is not enabled, or
+/// cannot be trusted anymore.
+fn with_model<T>(query: impl FnOnce(&Model) -> T) -> Option<T> {
+    let model = CACHE.lock().unwrap().as_ref()?.model.clone();
+    let model = model.read().unwrap();
+
+    match model.valid {
+        true => Some(query(&model)),
+        false => None,
+    }
+}
+
+/// The current generation of the model.
+pub fn generation() -> Option<u64> {
+    with_model(|model| model.generation)
+}
+
+/// The names of the interfaces passing a filter, sorted by index.
+pub fn interfaces(
+    display: InterfaceDisplay,
+    filter: &InterfaceFilter,
+) -> CachedResult<Vec<String>> {
+    Some(
+        interfaces_by_index(display, filter)?
+            .map(|(generation, names)| (generation, names.into_values().collect())),
+    )
+}
+
+pub fn interfaces_by_index(
+    display: InterfaceDisplay,
+    filter: &InterfaceFilter,
+) -> CachedResult<IfacesByIndex> {
+    with_model(|model| {
+        let links = filter_links(model.links.values(), &model.addresses, filter)?;
+        let names = links
+            .into_iter()
+            .map(|link| (link.index, display_name(link, display)));
+
+        Ok((model.generation, names.collect()))
+    })
+}
+
+pub fn ifaddresses(iface: &InterfaceRef) -> CachedResult<api::InterfaceAddresses> {
+    with_model(|mut model| {
+        let if_addrs = interface_addresses(&mut model, iface)?;
+        Ok((model.generation, if_addrs))
+    })
+}
+
+/// All the routes, from every table.
+pub fn routes() -> Option<(u64, Vec<api::Route>)> {
+    with_model(|model| {
+        let names: HashMap<u32, String> = model
+            .links
+            .values()
+            .map(|link| (link.index, link.name.clone()))
+            .collect();
+        (model.generation, ip_routes(&model.routes, &names))
+    })
+}
@@ -0,0 +1,438 @@
+//! The C ABI, for the C and Go (through cgo) programs that need the same interface
+//! inventory as the Python module. Its header is generated from this file by build.rs,
+//! with cbindgen, and committed as include/netifaces.h.
+//!
+//! A snapshot owns everything its interfaces and addresses point to, so every pointer
+//! handed out stays valid until the snapshot is freed; the iterators have to be freed
+//! before it. Given NULL instead of an object, the functions answer NULL, 0, -1 or
+//! false. The rules are repeated in the header, which is where C programmers read them.
+#![allow(non_camel_case_types)]
+#![allow(clippy::missing_safety_doc)]
+
+use crate::api::{self, Address, Interface};
+use crate::error::NetifacesError;
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::net::IpAddr;
+use std::{ptr, slice};
+
+/// The version of the ABI, only raised by incompatible changes.
+pub const NETIFACES_ABI_VERSION: u32 = 1;
+
+/// The outcome of a call, which mirrors the exceptions of the Python module.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum netifaces_status {
+    NETIFACES_OK = 0,
+    /// No interface has that name or index
+    NETIFACES_INTERFACE_NOT_FOUND = 1,
+    /// More than a single interface is displayed with that name
+    NETIFACES_AMBIGUOUS_INTERFACE = 2,
+    /// The system refused the request
+    NETIFACES_PERMISSION_DENIED = 3,
+    /// The operation is not available on this platform
+    NETIFACES_UNSUPPORTED = 4,
+    /// The backend cannot be used, e.g. the kernel was built without netlink
+    NETIFACES_BACKEND_UNAVAILABLE = 5,
+    /// Any other failure
+    NETIFACES_ERROR = 6,
+    /// A required pointer was NULL
+    NETIFACES_INVALID_ARGUMENT = 7,
+}
+
+/// Every interface of the system, with their addresses, as of one point in time.
+pub struct netifaces_snapshot {
+    interfaces: Vec<netifaces_interface>,
+}
+
+/// An interface of a snapshot.
+pub struct netifaces_interface {
+    interface: Interface,
+    name: CString,
+    addresses: Vec<netifaces_address>,
+}
+
+/// An IP address of an interface.
+pub struct netifaces_address {
+    address: Address,
+    octets: Vec<u8>,
+    text: CString,
+    broadcast: Option<CString>,
+    peer: Option<CString>,
+}
+
+/// Goes over the interfaces of a snapshot, by index.
+pub struct netifaces_interface_iter {
+    interfaces: slice::Iter<'static, netifaces_interface>,
+}
+
+/// Goes over the addresses of an interface.
+pub struct netifaces_address_iter {
+    addresses: slice::Iter<'static, netifaces_address>,
+}
+
+thread_local! {
+    /// The message and errno of the last failed call of the thread.
+    static LAST_ERROR: RefCell<(Option<CString>, c_int)> = const { RefCell::new((None, 0)) };
+}
+
+fn c_string(text: String) -> CString {
+    // Neither interface names nor addresses can hold a NUL
+    CString::new(text).unwrap_or_default()
+}
+
+fn fail(err: NetifacesError) -> netifaces_status {
+    let status = match &err {
+        NetifacesError::InterfaceNotFound(_) => netifaces_status::NETIFACES_INTERFACE_NOT_FOUND,
+        NetifacesError::AmbiguousInterface(_) => netifaces_status::NETIFACES_AMBIGUOUS_INTERFACE,
+        NetifacesError::Unsupported(_) => netifaces_status::NETIFACES_UNSUPPORTED,
+        _ if err.is_permission_denied() => netifaces_status::NETIFACES_PERMISSION_DENIED,
+        _ if err.is_backend_unavailable() => netifaces_status::NETIFACES_BACKEND_UNAVAILABLE,
+        NetifacesError::System { .. } | NetifacesError::Other(_) => {
+            netifaces_status::NETIFACES_ERROR
+        }
+    };
+    let errno = err.errno().unwrap_or(0);
+    LAST_ERROR.with(|last| *last.borrow_mut() = (Some(c_string(err.to_string())), errno));
+
+    status
+}
+
+impl From<Address> for netifaces_address {
+    fn from(address: Address) -> Self {
+        netifaces_address {
+            octets: match address.ip {
+                IpAddr::V4(ip) => ip.octets().to_vec(),
+                IpAddr::V6(ip) => ip.octets().to_vec(),
+            },
+            text: c_string(address.ip.to_string()),
+            broadcast: address.broadcast.map(|ip| c_string(ip.to_string())),
+            peer: address.peer.map(|ip| c_string(ip.to_string())),
+            address,
+        }
+    }
+}
+
+impl From<Interface> for netifaces_interface {
+    fn from(mut interface: Interface) -> Self {
+        netifaces_interface {
+            name: c_string(interface.name.clone()),
+            addresses: interface.addresses.drain(..).map(Into::into).collect(),
+            interface,
+        }
+    }
+}
+
+fn optional_str(text: &Option<CString>) -> *const c_char {
+    text.as_ref().map_or(ptr::null(), |text| text.as_ptr())
+}
+
+/// The version of the ABI the library implements, NETIFACES_ABI_VERSION.
+#[no_mangle]
+pub extern "C" fn netifaces_abi_version() -> u32 {
+    NETIFACES_ABI_VERSION
+}
+
+/// The message of the last call of this thread that failed, or NULL. It stays valid
+/// until the next failure on the thread.
+#[no_mangle]
+pub extern "C" fn netifaces_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| optional_str(&last.borrow().0))
+}
+
+/// The errno of the system call behind the last failure of this thread, or 0.
+#[no_mangle]
+pub extern "C" fn netifaces_last_errno() -> c_int {
+    LAST_ERROR.with(|last| last.borrow().1)
+}
+
+/// Take a snapshot of the interfaces of the system. On success, `*snapshot` has to be
+/// released with netifaces_snapshot_free.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_snapshot_new(
+    snapshot: *mut *mut netifaces_snapshot,
+) -> netifaces_status {
+    if snapshot.is_null() {
+        return netifaces_status::NETIFACES_INVALID_ARGUMENT;
+    }
+
+    match api::interfaces() {
+        Ok(interfaces) => {
+            let interfaces = interfaces.into_iter().map(Into::into).collect();
+            *snapshot = Box::into_raw(Box::new(netifaces_snapshot { interfaces }));
+            netifaces_status::NETIFACES_OK
+        }
+        Err(err) => fail(err),
+    }
+}
+
+/// Release a snapshot, along with its interfaces and addresses. NULL is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_snapshot_free(snapshot: *mut netifaces_snapshot) {
+    if !snapshot.is_null() {
+        drop(Box::from_raw(snapshot));
+    }
+}
+
+/// The number of interfaces of a snapshot; 0 for NULL.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_snapshot_len(snapshot: *const netifaces_snapshot) -> usize {
+    snapshot
+        .as_ref()
+        .map_or(0, |snapshot| snapshot.interfaces.len())
+}
+
+/// The interface of a snapshot with the given name, or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_snapshot_find(
+    snapshot: *const netifaces_snapshot,
+    name: *const c_char,
+) -> *const netifaces_interface {
+    let Some(snapshot) = snapshot.as_ref() else {
+        return ptr::null();
+    };
+    if name.is_null() {
+        return ptr::null();
+    }
+    let name = CStr::from_ptr(name);
+
+    snapshot
+        .interfaces
+        .iter()
+        .find(|interface| interface.name.as_c_str() == name)
+        .map_or(ptr::null(), |interface| interface as *const _)
+}
+
+/// Iterate over the interfaces of a snapshot, by index. The iterator has to be released
+/// with netifaces_interface_iter_free, before the snapshot. NULL for a NULL snapshot.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_snapshot_interfaces(
+    snapshot: *const netifaces_snapshot,
+) -> *mut netifaces_interface_iter {
+    let Some(snapshot) = snapshot.as_ref() else {
+        return ptr::null_mut();
+    };
+    let interfaces = snapshot.interfaces.iter();
+
+    Box::into_raw(Box::new(netifaces_interface_iter { interfaces }))
+}
+
+/// The next interface, or NULL once they have all been seen.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_interface_iter_next(
+    iter: *mut netifaces_interface_iter,
+) -> *const netifaces_interface {
+    iter.as_mut()
+        .and_then(|iter| iter.interfaces.next())
+        .map_or(ptr::null(), |interface| interface as *const _)
+}
+
+/// Release an iterator over interfaces. NULL is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_interface_iter_free(iter: *mut netifaces_interface_iter) {
+    if !iter.is_null() {
+        drop(Box::from_raw(iter));
+    }
+}
+
+/// The name of the interface.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_interface_name(
+    interface: *const netifaces_interface,
+) -> *const c_char {
+    interface
+        .as_ref()
+        .map_or(ptr::null(), |interface| interface.name.as_ptr())
+}
+
+/// The index of the interface.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_interface_index(interface: *const netifaces_interface) -> u32 {
+    interface
+        .as_ref()
+        .map_or(0, |interface| interface.interface.index)
+}
+
+/// The IFF_* flags of the interface; always 0 on Windows.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_interface_flags(interface: *const netifaces_interface) -> u32 {
+    interface
+        .as_ref()
+        .map_or(0, |interface| interface.interface.flags)
+}
+
+/// Whether the interface is up and can pass traffic.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_interface_is_up(interface: *const netifaces_interface) -> bool {
+    interface
+        .as_ref()
+        .is_some_and(|interface| interface.interface.up)
+}
+
+/// The MTU of the interface, or 0 if it is unknown.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_interface_mtu(interface: *const netifaces_interface) -> u32 {
+    interface
+        .as_ref()
+        .and_then(|interface| interface.interface.mtu)
+        .unwrap_or(0)
+}
+
+/// The length of the hardware address of the interface, which `*address` is pointed
+/// to; 0 if it has none.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_interface_hardware_address(
+    interface: *const netifaces_interface,
+    address: *mut *const u8,
+) -> usize {
+    let Some(address) = address.as_mut() else {
+        return 0;
+    };
+    let link_address = interface
+        .as_ref()
+        .and_then(|interface| interface.interface.link_address.as_ref());
+
+    match link_address {
+        Some(link_address) => {
+            *address = link_address.address.0.as_ptr();
+            link_address.address.0.len()
+        }
+        None => {
+            *address = ptr::null();
+            0
+        }
+    }
+}
+
+/// Iterate over the IP addresses of an interface. The iterator has to be released with
+/// netifaces_address_iter_free, before the snapshot. NULL for a NULL interface.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_interface_addresses(
+    interface: *const netifaces_interface,
+) -> *mut netifaces_address_iter {
+    let Some(interface) = interface.as_ref() else {
+        return ptr::null_mut();
+    };
+    let addresses = interface.addresses.iter();
+
+    Box::into_raw(Box::new(netifaces_address_iter { addresses }))
+}
+
+/// The next address, or NULL once they have all been seen.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_address_iter_next(
+    iter: *mut netifaces_address_iter,
+) -> *const netifaces_address {
+    iter.as_mut()
+        .and_then(|iter| iter.addresses.next())
+        .map_or(ptr::null(), |address| address as *const _)
+}
+
+/// Release an iterator over addresses. NULL is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_address_iter_free(iter: *mut netifaces_address_iter) {
+    if !iter.is_null() {
+        drop(Box::from_raw(iter));
+    }
+}
+
+/// The IP version of the address, 4 or 6; 0 for NULL.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_address_version(address: *const netifaces_address) -> u8 {
+    match address.as_ref().map(|address| address.address.ip) {
+        Some(IpAddr::V4(_)) => 4,
+        Some(IpAddr::V6(_)) => 6,
+        None => 0,
+    }
+}
+
+/// The length of the address in network order (4 or 16 bytes), which `*octets` is
+/// pointed to.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_address_octets(
+    address: *const netifaces_address,
+    octets: *mut *const u8,
+) -> usize {
+    let Some(octets) = octets.as_mut() else {
+        return 0;
+    };
+
+    match address.as_ref() {
+        Some(address) => {
+            *octets = address.octets.as_ptr();
+            address.octets.len()
+        }
+        None => {
+            *octets = ptr::null();
+            0
+        }
+    }
+}
+
+/// The address, in its text form, without the zone of link-local IPv6 addresses.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_address_string(
+    address: *const netifaces_address,
+) -> *const c_char {
+    address
+        .as_ref()
+        .map_or(ptr::null(), |address| address.text.as_ptr())
+}
+
+/// The zone of a link-local IPv6 address, which is the index of its interface; 0 for
+/// the other addresses.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_address_scope_id(address: *const netifaces_address) -> u32 {
+    address
+        .as_ref()
+        .and_then(|address| address.address.scope_id)
+        .unwrap_or(0)
+}
+
+/// The length of the netmask of the address, or -1 if it is unknown.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_address_prefix_len(address: *const netifaces_address) -> c_int {
+    address
+        .as_ref()
+        .and_then(|address| address.address.prefix_len)
+        .map_or(-1, |prefix_len| prefix_len as c_int)
+}
+
+/// The broadcast address, in its text form, or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_address_broadcast(
+    address: *const netifaces_address,
+) -> *const c_char {
+    address
+        .as_ref()
+        .map_or(ptr::null(), |address| optional_str(&address.broadcast))
+}
+
+/// The other end of a point-to-point link, in its text form, or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_address_peer(
+    address: *const netifaces_address,
+) -> *const c_char {
+    address
+        .as_ref()
+        .map_or(ptr::null(), |address| optional_str(&address.peer))
+}
+
+/// The IFA_F_* flags of the address; always 0 outside of Linux.
+#[no_mangle]
+pub unsafe extern "C" fn netifaces_address_flags(address: *const netifaces_address) -> u32 {
+    address.as_ref().map_or(0, |address| address.address.flags)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn header_is_up_to_date() {
+        assert!(
+            include_str!("../include/netifaces.h")
+                == include_str!(concat!(env!("OUT_DIR"), "/netifaces.h")),
+            "include/netifaces.h is stale: replace it with {}",
+            concat!(env!("OUT_DIR"), "/netifaces.h")
+        );
+    }
+}
@@ -1,20 +1,331 @@
-use pyo3::exceptions::PyTypeError;
-use pyo3::PyErr;
+use crate::NetifacesError;
+#[cfg(feature = "python")]
+use pyo3::FromPyObject;
+use regex::Regex;
+use std::fmt;
+use std::net::IpAddr;
+
+/// An interface, designated either by its name or by its index.
+///
+/// Names get reused, e.g. after hotplug, so callers keeping track of interfaces over
+/// time are better off with indexes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+pub enum InterfaceRef {
+    Index(u32),
+    Name(String),
+}
+
+impl InterfaceRef {
+    /// The error reported when no interface matches.
+    pub fn not_found(&self) -> NetifacesError {
+        NetifacesError::InterfaceNotFound(self.clone())
+    }
+}
+
+/// The name of an IPv4 label alias (e.g. `eth0:1`) and the name of the link it belongs
+/// to, if `iface` looks like an alias.
+pub fn split_alias(iface: &InterfaceRef) -> Option<(&str, &str)> {
+    match iface {
+        InterfaceRef::Name(name) => name.split_once(':').map(|(base, _)| (name.as_str(), base)),
+        InterfaceRef::Index(_) => None,
+    }
+}
+
+impl From<&str> for InterfaceRef {
+    fn from(name: &str) -> Self {
+        InterfaceRef::Name(name.to_string())
+    }
+}
+
+impl From<u32> for InterfaceRef {
+    fn from(index: u32) -> Self {
+        InterfaceRef::Index(index)
+    }
+}
+
+/// An IP network, e.g. "192.168.1.0/24"; a bare address is a network of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    pub addr: IpAddr,
+    pub prefix_len: u8,
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+impl IpNetwork {
+    /// Parse a network in CIDR notation. Bits set past the prefix are ignored, like
+    /// `ipaddress.ip_network(..., strict=False)` does.
+    pub fn parse(text: &str) -> Result<IpNetwork, NetifacesError> {
+        let invalid = || NetifacesError::Other(format!("Invalid network {text}"));
+
+        let (addr, prefix_len) = match text.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (text, None),
+        };
+        let addr: IpAddr = addr.parse().map_err(|_| invalid())?;
+        let max_len = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len.parse().map_err(|_| invalid())?,
+            None => max_len,
+        };
+
+        match prefix_len <= max_len {
+            true => Ok(IpNetwork { addr, prefix_len }),
+            false => Err(invalid()),
+        }
+    }
+
+    /// Whether two networks have addresses in common, i.e. one of them contains the other.
+    pub fn overlaps(&self, other: &IpNetwork) -> bool {
+        let prefix_len = self.prefix_len.min(other.prefix_len);
+
+        match (self.addr, other.addr) {
+            (IpAddr::V4(a), IpAddr::V4(b)) => {
+                let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+                u32::from(a) & mask == u32::from(b) & mask
+            }
+            (IpAddr::V6(a), IpAddr::V6(b)) => {
+                let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+                u128::from(a) & mask == u128::from(b) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+/// Parse an IP address, which may carry an IPv6 zone (e.g. "fe80::1%eth0"): the name of
+/// the interface it is meaningful on.
+pub(crate) fn parse_zoned_ip(ip: &str) -> Result<(IpAddr, Option<&str>), NetifacesError> {
+    let (addr, zone) = match ip.split_once('%') {
+        Some((addr, zone)) => (addr, Some(zone)),
+        None => (ip, None),
+    };
+    let addr = addr
+        .parse()
+        .map_err(|_| NetifacesError::Other(format!("Invalid IP address {ip}")))?;
+
+    Ok((addr, zone))
+}
 
 /// Controls what is the interface name returned to the user.
+#[derive(Clone, Copy)]
 pub enum InterfaceDisplay {
+    /// The description of the interface, falling back to its name on Linux
     HumanReadable = 0,
+    /// The name of the interface
     MachineReadable = 1,
+    /// The first alternative name of the interface, falling back to its name
+    AltName = 2,
+    /// The index of the interface, in decimal
+    Index = 3,
+}
+
+/// The condition an interface has to meet for a wait on it to end.
+#[cfg(feature = "python")]
+#[derive(Clone, Copy)]
+pub enum WaitCondition {
+    /// The interface exists
+    Present = 0,
+    /// The interface is up and can pass traffic, in the sense of `interface_is_up`
+    Up = 1,
+    /// The interface has a global address that is done with duplicate address detection
+    HasAddress = 2,
+}
+
+/// Filters on the interfaces listed by `interfaces` and `interfaces_by_index`. An
+/// interface is listed if it passes all of them; the default filter lets every
+/// interface through.
+///
+/// The Python module extracts it from the dictionary built by the Python wrappers.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "python", derive(FromPyObject))]
+pub struct InterfaceFilter {
+    /// Only the interfaces that are up, in the sense of `interface_is_up`
+    #[cfg_attr(feature = "python", pyo3(item))]
+    pub up: bool,
+    #[cfg_attr(feature = "python", pyo3(item))]
+    pub exclude_loopback: bool,
+    /// Only the interfaces backed by a hardware device
+    #[cfg_attr(feature = "python", pyo3(item))]
+    pub physical: bool,
+    /// Only the interfaces with an address in this family
+    #[cfg_attr(feature = "python", pyo3(item))]
+    pub has_address: Option<u8>,
+    /// Only the interfaces of this link kind, e.g. "bridge" or "veth"
+    #[cfg_attr(feature = "python", pyo3(item))]
+    pub kind: Option<String>,
+    /// A shell-style pattern the whole name must match
+    #[cfg_attr(feature = "python", pyo3(item))]
+    pub name: Option<String>,
+    /// A regular expression that must match somewhere in the name
+    #[cfg_attr(feature = "python", pyo3(item))]
+    pub name_regex: Option<String>,
+    /// Only the interfaces enslaved to this one, e.g. the ports of a bridge
+    #[cfg_attr(feature = "python", pyo3(item))]
+    pub master: Option<String>,
+}
+
+/// What an [InterfaceFilter] looks at, for one interface.
+pub struct InterfaceFacts<'a> {
+    pub name: &'a str,
+    pub up: bool,
+    pub loopback: bool,
+    pub physical: bool,
+    pub kind: Option<&'a str>,
+    pub master: Option<&'a str>,
+    /// The families the interface has an address in
+    pub families: &'a [u8],
 }
 
-impl TryFrom<i32> for InterfaceDisplay {
-    type Error = PyErr;
+impl InterfaceFilter {
+    /// Whether the filter looks at the flags or the addresses of the interfaces, as
+    /// opposed to only their names and how they relate to each other.
+    pub fn looks_at_state(&self) -> bool {
+        self.up || self.exclude_loopback || self.has_address.is_some()
+    }
+
+    /// Fail if the filter uses criteria that the platform cannot evaluate.
+    pub fn check_supported(
+        &self,
+        physical: bool,
+        kind: bool,
+        master: bool,
+    ) -> Result<(), NetifacesError> {
+        let unsupported = [
+            ("physical", self.physical && !physical),
+            ("kind", self.kind.is_some() && !kind),
+            ("master", self.master.is_some() && !master),
+        ];
 
-    fn try_from(value: i32) -> Result<Self, Self::Error> {
-        match value {
-            0 => Ok(InterfaceDisplay::HumanReadable),
-            1 => Ok(InterfaceDisplay::MachineReadable),
-            _ => Err(PyTypeError::new_err("Error message")),
+        match unsupported.iter().find(|(_, unsupported)| *unsupported) {
+            Some((criterion, _)) => Err(NetifacesError::Unsupported(format!(
+                "Filtering interfaces by {criterion} is not supported on this platform"
+            ))),
+            None => Ok(()),
         }
     }
+
+    /// Compile the name patterns, so that they are only parsed once per enumeration.
+    pub fn matcher(&self) -> Result<FilterMatcher<'_>, Box<dyn std::error::Error>> {
+        let pattern = |regex: &str| {
+            Regex::new(regex)
+                .map_err(|e| NetifacesError::Other(format!("Invalid name pattern: {e}")))
+        };
+
+        Ok(FilterMatcher {
+            filter: self,
+            name: self
+                .name
+                .as_deref()
+                .map(glob_to_regex)
+                .map(|regex| pattern(&regex))
+                .transpose()?,
+            name_regex: self.name_regex.as_deref().map(pattern).transpose()?,
+        })
+    }
+}
+
+/// An [InterfaceFilter] ready to be evaluated.
+pub struct FilterMatcher<'a> {
+    filter: &'a InterfaceFilter,
+    name: Option<Regex>,
+    name_regex: Option<Regex>,
+}
+
+impl FilterMatcher<'_> {
+    /// Whether the name alone lets the interface through; this is cheaper to check
+    /// than everything else.
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.name.as_ref().is_none_or(|regex| regex.is_match(name))
+            && self
+                .name_regex
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(name))
+    }
+
+    pub fn matches(&self, facts: &InterfaceFacts) -> bool {
+        let filter = self.filter;
+
+        self.matches_name(facts.name)
+            && (!filter.up || facts.up)
+            && (!filter.exclude_loopback || !facts.loopback)
+            && (!filter.physical || facts.physical)
+            && filter
+                .has_address
+                .is_none_or(|family| facts.families.contains(&family))
+            && filter
+                .kind
+                .as_deref()
+                .is_none_or(|kind| facts.kind == Some(kind))
+            && filter
+                .master
+                .as_deref()
+                .is_none_or(|master| facts.master == Some(master))
+    }
+}
+
+/// Translate a shell-style pattern (`*`, `?` and `[...]`, as in `fnmatch`) into a
+/// regular expression matching whole names.
+fn glob_to_regex(glob: &str) -> String {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut regex = String::from("^");
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            // Like fnmatch, an unterminated class is a literal "["
+            '[' => match class_end(&chars, i) {
+                Some(end) => {
+                    regex.push('[');
+                    for (position, c) in chars[i + 1..end].iter().enumerate() {
+                        match c {
+                            '!' if position == 0 => regex.push('^'),
+                            // Characters that have a meaning in a regex class, but not in a glob
+                            '\\' | '[' | ']' | '^' | '&' | '~' => {
+                                regex.push('\\');
+                                regex.push(*c);
+                            }
+                            _ => regex.push(*c),
+                        }
+                    }
+                    regex.push(']');
+                    i = end;
+                }
+                None => regex.push_str(r"\["),
+            },
+            c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+        i += 1;
+    }
+
+    regex.push('$');
+    regex
+}
+
+/// The position of the "]" closing the class opened at `start`. A "]" right after
+/// the opening "[" (or "[!") is part of the class.
+fn class_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    if chars.get(i) == Some(&'!') {
+        i += 1;
+    }
+    if chars.get(i) == Some(&']') {
+        i += 1;
+    }
+
+    chars[i.min(chars.len())..]
+        .iter()
+        .position(|c| *c == ']')
+        .map(|offset| i + offset)
 }
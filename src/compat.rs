@@ -0,0 +1,222 @@
+//! The addresses of the interfaces, formatted exactly as netifaces 1 formatted them.
+//!
+//! netifaces 1 walked the `getifaddrs` list and turned each entry into a dict of strings.
+//! Reproducing its output means walking the same list, with the same quirks: IPv4 label
+//! aliases are interfaces of their own, MAC addresses are lowercase, IPv6 netmasks end
+//! with their prefix length and link-local addresses with their zone.
+
+use std::ffi::CStr;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// An address entry: its "addr", "netmask", "broadcast" and "peer" keys, in that order.
+pub type LegacyEntry = Vec<(&'static str, String)>;
+
+/// The entries of an interface, by address family, in the order they were first seen.
+pub type LegacyAddrs = Vec<(i32, Vec<LegacyEntry>)>;
+
+/// The list built by `getifaddrs`, freed when dropped.
+struct IfAddrsList(*mut libc::ifaddrs);
+
+impl IfAddrsList {
+    fn new() -> io::Result<IfAddrsList> {
+        let mut head = std::ptr::null_mut();
+        match unsafe { libc::getifaddrs(&mut head) } {
+            0 => Ok(IfAddrsList(head)),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &libc::ifaddrs> {
+        std::iter::successors(unsafe { self.0.as_ref() }, |entry| unsafe {
+            entry.ifa_next.as_ref()
+        })
+    }
+}
+
+impl Drop for IfAddrsList {
+    fn drop(&mut self) {
+        unsafe { libc::freeifaddrs(self.0) }
+    }
+}
+
+fn entry_name(entry: &libc::ifaddrs) -> Option<String> {
+    match entry.ifa_name.is_null() {
+        true => None,
+        false => Some(
+            unsafe { CStr::from_ptr(entry.ifa_name) }
+                .to_string_lossy()
+                .into_owned(),
+        ),
+    }
+}
+
+/// Format an IPv6 address as glibc's `inet_ntop` does, which writes the last 32 bits of
+/// IPv4-compatible addresses in dotted form.
+fn ipv6_to_string(ip: &Ipv6Addr) -> String {
+    let segments = ip.segments();
+
+    if segments[..6].iter().all(|segment| *segment == 0) && (segments[6] != 0 || segments[7] > 1) {
+        let [.., a, b, c, d] = ip.octets();
+        return format!("::{}", Ipv4Addr::new(a, b, c, d));
+    }
+
+    ip.to_string()
+}
+
+/// Format an IPv6 netmask as netifaces 1 did: the bytes up to the prefix length, in
+/// groups of two, then the prefix length, e.g. "ffff:ffff:ffff:ffff::/64".
+pub fn ipv6_netmask_to_string(mask: &Ipv6Addr) -> String {
+    let prefix_len = 128 - u128::from(*mask).trailing_zeros();
+    let len = (2 * prefix_len.div_ceil(16)) as usize;
+    let octets = mask.octets();
+
+    let groups: Vec<String> = octets[..len]
+        .chunks(2)
+        .map(|group| format!("{:02x}{:02x}", group[0], group[1]))
+        .collect();
+    let elision = if len < 16 { "::" } else { "" };
+
+    format!("{}{elision}/{prefix_len}", groups.join(":"))
+}
+
+/// Format a hardware address as netifaces 1 did: lowercase, with colons.
+pub fn mac_to_string(mac: &[u8]) -> String {
+    let bytes: Vec<String> = mac.iter().map(|byte| format!("{byte:02x}")).collect();
+    bytes.join(":")
+}
+
+/// The zone of a link-local address, as `getnameinfo` writes it: the name of the
+/// interface, or its index if it has none.
+fn zone(scope_id: u32) -> String {
+    let mut name = [0 as libc::c_char; libc::IF_NAMESIZE];
+
+    match unsafe { libc::if_indextoname(scope_id, name.as_mut_ptr()) }.is_null() {
+        true => scope_id.to_string(),
+        false => unsafe { CStr::from_ptr(name.as_ptr()) }
+            .to_string_lossy()
+            .into_owned(),
+    }
+}
+
+/// Format an address of one of the families `getifaddrs` reports; None for the others.
+fn sockaddr_to_string(addr: *const libc::sockaddr) -> Option<String> {
+    let family = unsafe { addr.as_ref() }?.sa_family as i32;
+
+    match family {
+        libc::AF_INET => {
+            let sin = unsafe { &*(addr as *const libc::sockaddr_in) };
+            Some(Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr)).to_string())
+        }
+        libc::AF_INET6 => {
+            let sin6 = unsafe { &*(addr as *const libc::sockaddr_in6) };
+            let ip = Ipv6Addr::from(sin6.sin6_addr.s6_addr);
+            let link_local = ip.is_unicast_link_local() || ip.segments()[0] & 0xff0f == 0xff02;
+
+            match (link_local, sin6.sin6_scope_id) {
+                (true, scope_id) if scope_id != 0 => {
+                    Some(format!("{}%{}", ipv6_to_string(&ip), zone(scope_id)))
+                }
+                _ => Some(ipv6_to_string(&ip)),
+            }
+        }
+        libc::AF_PACKET => {
+            let sll = unsafe { &*(addr as *const libc::sockaddr_ll) };
+            let len = (sll.sll_halen as usize).min(sll.sll_addr.len());
+            Some(mac_to_string(&sll.sll_addr[..len]))
+        }
+        _ => None,
+    }
+}
+
+fn netmask_to_string(netmask: *const libc::sockaddr) -> Option<String> {
+    let family = unsafe { netmask.as_ref() }?.sa_family as i32;
+
+    match family {
+        libc::AF_INET6 => {
+            let sin6 = unsafe { &*(netmask as *const libc::sockaddr_in6) };
+            Some(ipv6_netmask_to_string(&Ipv6Addr::from(
+                sin6.sin6_addr.s6_addr,
+            )))
+        }
+        _ => sockaddr_to_string(netmask),
+    }
+}
+
+/// Turn a `getifaddrs` entry into the dict netifaces 1 built for it.
+fn legacy_entry(entry: &libc::ifaddrs) -> LegacyEntry {
+    let mut legacy = LegacyEntry::new();
+    let family = unsafe { &*entry.ifa_addr }.sa_family as i32;
+
+    if let Some(addr) = sockaddr_to_string(entry.ifa_addr) {
+        legacy.push(("addr", addr));
+    }
+    if let Some(netmask) = netmask_to_string(entry.ifa_netmask) {
+        legacy.push(("netmask", netmask));
+    }
+
+    let mut broadcast = sockaddr_to_string(entry.ifa_ifu);
+    // netifaces 1 dropped the broadcast addresses of 169.254.0.0/16
+    if family == libc::AF_INET {
+        let sin = unsafe { &*(entry.ifa_addr as *const libc::sockaddr_in) };
+        if u32::from_be(sin.sin_addr.s_addr) & 0xffff0000 == 0xa9fe0000 {
+            broadcast = None;
+        }
+    }
+    if let Some(broadcast) = broadcast {
+        let peer = (libc::IFF_POINTOPOINT | libc::IFF_LOOPBACK) as u32;
+        match entry.ifa_flags & peer != 0 {
+            true => legacy.push(("peer", broadcast)),
+            false => legacy.push(("broadcast", broadcast)),
+        }
+    }
+
+    legacy
+}
+
+/// The names of the interfaces, in the order `getifaddrs` lists them, IPv4 label
+/// aliases included.
+pub fn compat_interfaces() -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let list = IfAddrsList::new()?;
+    let mut names: Vec<String> = Vec::new();
+
+    for name in list.iter().filter_map(entry_name) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    Ok(names)
+}
+
+/// The addresses of an interface, or None if `getifaddrs` does not know it. Families
+/// without any address are left out, as are the entries without an address.
+pub fn compat_ifaddresses(name: &str) -> Result<Option<LegacyAddrs>, Box<dyn std::error::Error>> {
+    let list = IfAddrsList::new()?;
+    let mut addrs = LegacyAddrs::new();
+    let mut found = false;
+
+    for entry in list.iter() {
+        if entry_name(entry).as_deref() != Some(name) {
+            continue;
+        }
+        found = true;
+
+        if entry.ifa_addr.is_null() {
+            continue;
+        }
+
+        let legacy = legacy_entry(entry);
+        if legacy.is_empty() {
+            continue;
+        }
+
+        let family = unsafe { &*entry.ifa_addr }.sa_family as i32;
+        match addrs.iter_mut().find(|(known, _)| *known == family) {
+            Some((_, entries)) => entries.push(legacy),
+            None => addrs.push((family, vec![legacy])),
+        }
+    }
+
+    Ok(found.then_some(addrs))
+}
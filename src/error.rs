@@ -0,0 +1,158 @@
+//! The errors of netifaces, and (with the `python` feature) the Python exceptions they
+//! are raised as.
+//!
+//! The exceptions are defined in python/netifaces/errors.py rather than here, so that
+//! `PermissionDeniedError` can also be a `PermissionError`; they are looked up when an
+//! error is raised.
+
+use crate::common::InterfaceRef;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyDict;
+use std::io;
+
+#[derive(Debug, thiserror::Error)]
+pub enum NetifacesError {
+    /// No interface has that name or index
+    #[error("netifaces error: Failed to find an interface with the {}", describe(.0))]
+    InterfaceNotFound(InterfaceRef),
+    /// More than a single interface is displayed with that name
+    #[error("netifaces error: More than a single interface with the description '{0}'")]
+    AmbiguousInterface(String),
+    /// The operation is not available on this platform
+    #[error("netifaces error: {0}")]
+    Unsupported(String),
+    /// A system call failed, with that errno
+    #[error("netifaces error: {message}")]
+    System { message: String, errno: i32 },
+    #[error("netifaces error: {0}")]
+    Other(String),
+}
+
+fn describe(iface: &InterfaceRef) -> String {
+    match iface {
+        InterfaceRef::Name(name) => format!("name {name}"),
+        InterfaceRef::Index(index) => format!("index {index}"),
+    }
+}
+
+/// The errnos telling that the backend cannot be used at all, e.g. that the kernel was
+/// built without netlink.
+#[cfg(unix)]
+const UNAVAILABLE_ERRNOS: [i32; 3] = [
+    libc::EAFNOSUPPORT,
+    libc::EPROTONOSUPPORT,
+    libc::ESOCKTNOSUPPORT,
+];
+#[cfg(not(unix))]
+const UNAVAILABLE_ERRNOS: [i32; 0] = [];
+
+/// The errnos of the requests the system refused.
+#[cfg(unix)]
+const PERMISSION_ERRNOS: [i32; 2] = [libc::EPERM, libc::EACCES];
+#[cfg(not(unix))]
+const PERMISSION_ERRNOS: [i32; 1] = [5]; // ERROR_ACCESS_DENIED
+
+impl NetifacesError {
+    /// Turn any error of the backends into a [NetifacesError], keeping the errno of the
+    /// failed system calls. Unlike the boxed errors, the result can be sent across threads.
+    pub fn from_boxed(err: Box<dyn std::error::Error>) -> NetifacesError {
+        let err = match err.downcast::<NetifacesError>() {
+            Ok(err) => return *err,
+            Err(err) => err,
+        };
+
+        let errno = match err.downcast_ref::<io::Error>() {
+            Some(err) => err.raw_os_error(),
+            #[cfg(unix)]
+            None => err
+                .downcast_ref::<nix::errno::Errno>()
+                .map(|errno| *errno as i32),
+            #[cfg(not(unix))]
+            None => None,
+        };
+
+        match errno {
+            Some(errno) => NetifacesError::System {
+                message: err.to_string(),
+                errno,
+            },
+            None => NetifacesError::Other(err.to_string()),
+        }
+    }
+
+    /// The errno of the failed system call, if the error comes from one.
+    pub fn errno(&self) -> Option<i32> {
+        match self {
+            NetifacesError::System { errno, .. } => Some(*errno),
+            _ => None,
+        }
+    }
+
+    /// Whether the system refused the request, e.g. for lack of privileges.
+    pub fn is_permission_denied(&self) -> bool {
+        self.errno()
+            .is_some_and(|errno| PERMISSION_ERRNOS.contains(&errno))
+    }
+
+    /// Whether the backend cannot be used at all on this system.
+    pub fn is_backend_unavailable(&self) -> bool {
+        self.errno()
+            .is_some_and(|errno| UNAVAILABLE_ERRNOS.contains(&errno))
+    }
+
+    /// The name of the exception of netifaces.errors the error is raised as.
+    #[cfg(feature = "python")]
+    fn exception_name(&self) -> &'static str {
+        match self {
+            NetifacesError::InterfaceNotFound(_) => "InterfaceNotFoundError",
+            NetifacesError::AmbiguousInterface(_) => "AmbiguousInterfaceError",
+            NetifacesError::Unsupported(_) => "UnsupportedOperationError",
+            _ if self.is_permission_denied() => "PermissionDeniedError",
+            _ if self.is_backend_unavailable() => "BackendUnavailableError",
+            NetifacesError::System { .. } | NetifacesError::Other(_) => "NetifacesError",
+        }
+    }
+
+    #[cfg(feature = "python")]
+    fn exception(&self, py: Python<'_>, backend: Option<&str>) -> PyResult<PyErr> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("errno", self.errno())?;
+        match self {
+            NetifacesError::InterfaceNotFound(InterfaceRef::Name(name))
+            | NetifacesError::AmbiguousInterface(name) => kwargs.set_item("interface", name)?,
+            NetifacesError::InterfaceNotFound(InterfaceRef::Index(index)) => {
+                kwargs.set_item("interface", index)?
+            }
+            _ => kwargs.set_item("interface", py.None())?,
+        }
+        kwargs.set_item("backend", backend)?;
+        let value = py
+            .import("netifaces.errors")?
+            .getattr(self.exception_name())?
+            .call((self.to_string(),), Some(&kwargs))?;
+
+        Ok(PyErr::from_value(value))
+    }
+
+    /// The Python exception for the error, as raised by the given backend; None when no
+    /// backend was queried, e.g. for an unknown backend name.
+    #[cfg(feature = "python")]
+    pub fn into_py_err(self, backend: Option<&str>) -> PyErr {
+        Python::with_gil(|py| self.exception(py, backend).unwrap_or_else(|e| e))
+    }
+}
+
+#[cfg(feature = "python")]
+impl From<NetifacesError> for PyErr {
+    fn from(err: NetifacesError) -> PyErr {
+        err.into_py_err(None)
+    }
+}
+
+/// The exception raised by the operations which are not available on this platform.
+#[cfg(all(feature = "python", not(target_os = "linux")))]
+pub fn unsupported(message: &str) -> PyErr {
+    NetifacesError::Unsupported(message.to_string()).into()
+}
@@ -1,44 +1,54 @@
 #![allow(non_snake_case)]
+//! netifaces: the network interfaces of the system, their addresses and the routes.
+//!
+//! The crate is both the Python extension module (with the `python` feature, on by
+//! default) and a Rust library, whose typed API is in [api] and re-exported here. The
+//! `capi` feature adds a C ABI, described by include/netifaces.h.
 
 extern crate core;
 
-use pyo3::exceptions::PyRuntimeError;
-use pyo3::prelude::*;
-use std::fmt;
 use std::fmt::Write;
 
+pub mod api;
+mod backend;
+#[cfg(feature = "capi")]
+mod capi;
+mod common;
+mod error;
+#[cfg(feature = "python")]
+mod mock;
+#[cfg(feature = "python")]
+mod objects;
+#[cfg(feature = "python")]
+mod python;
 mod types;
 
+#[cfg(all(feature = "python", target_os = "linux"))]
+mod cache;
+#[cfg(all(feature = "python", target_os = "linux"))]
+mod compat;
 #[cfg(not(target_family = "windows"))]
 mod linux;
-
-#[cfg(not(target_family = "windows"))]
-use linux::{
-    posix_ifaddresses as ifaddresses, posix_interface_is_up as interface_is_up,
-    posix_interfaces as interfaces, posix_interfaces_by_index as interfaces_by_index,
-};
-
-mod common;
+#[cfg(target_os = "linux")]
+mod linux_netlink;
+#[cfg(target_os = "linux")]
+mod linux_procfs;
+#[cfg(target_os = "linux")]
+mod netlink;
+#[cfg(target_os = "linux")]
+mod procfs;
+#[cfg(all(feature = "python", target_os = "linux"))]
+mod session;
+#[cfg(all(feature = "python", target_os = "linux"))]
+mod wait;
 #[cfg(target_family = "windows")]
 mod win;
 
-use crate::common::InterfaceDisplay;
-#[cfg(target_family = "windows")]
-use win::{
-    windows_ifaddresses as ifaddresses, windows_interface_is_up as interface_is_up,
-    windows_interfaces as interfaces, windows_interfaces_by_index as interfaces_by_index,
-};
-
-#[derive(Debug)]
-pub struct NetifacesError(String);
-
-impl fmt::Display for NetifacesError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "netifaces error: {}", self.0)
-    }
-}
-
-impl std::error::Error for NetifacesError {}
+pub use api::*;
+pub use backend::{backend, backends, set_backend, BACKEND_VARIABLE};
+pub use common::{InterfaceDisplay, InterfaceFilter, InterfaceRef, IpNetwork};
+pub use error::NetifacesError;
+pub use types::{AF_INET, AF_INET6, AF_UNSPEC};
 
 /// Given an u32 in little endian, return the String representation
 /// of it into the colloquial IPV4 string format
@@ -60,7 +70,7 @@ pub fn ip_to_string(ip: u32) -> String {
 
 /// Given the bytes that makes up a mac address, return the String
 /// representation as it would be expected in the colloquial form.
-pub fn mac_to_string(mac: &Vec<u8>) -> String {
+pub fn mac_to_string(mac: &[u8]) -> String {
     let mut s = String::new();
 
     for i in 0..mac.len() {
@@ -72,60 +82,3 @@ pub fn mac_to_string(mac: &Vec<u8>) -> String {
 
     s
 }
-
-#[pyfunction]
-pub fn _ip_to_string(ip: u32) -> String {
-    ip_to_string(ip)
-}
-
-#[pyfunction]
-fn _interfaces(interface_display: i32) -> PyResult<Vec<String>> {
-    let interface_display = InterfaceDisplay::try_from(interface_display)?;
-    let maybe_ifs = interfaces(interface_display);
-
-    maybe_ifs.map_err(|e| {
-        let str_message = e.to_string();
-        PyErr::new::<PyRuntimeError, _>(str_message)
-    })
-}
-
-#[pyfunction]
-fn _interfaces_by_index(interface_display: i32) -> PyResult<types::IfacesByIndex> {
-    let interface_display = InterfaceDisplay::try_from(interface_display)?;
-    let maybe_ifs = interfaces_by_index(interface_display);
-
-    maybe_ifs.map_err(|e| {
-        let str_message = e.to_string();
-        PyErr::new::<PyRuntimeError, _>(str_message)
-    })
-}
-
-#[pyfunction]
-fn _ifaddresses(if_name: &str) -> PyResult<types::IfAddrs> {
-    let maybe_ifaddrs = ifaddresses(if_name);
-
-    maybe_ifaddrs.map_err(|e| {
-        let str_message = e.to_string();
-        PyErr::new::<PyRuntimeError, _>(str_message)
-    })
-}
-
-#[pyfunction]
-fn _interface_is_up(if_name: &str) -> PyResult<bool> {
-    let maybe_if_status = interface_is_up(if_name);
-
-    maybe_if_status.map_err(|e| {
-        let str_message = e.to_string();
-        PyErr::new::<PyRuntimeError, _>(str_message)
-    })
-}
-
-#[pymodule]
-fn netifaces(_py: Python, m: &PyModule) -> PyResult<()> {
-    m.add_function(wrap_pyfunction!(_interfaces, m)?)?;
-    m.add_function(wrap_pyfunction!(_interfaces_by_index, m)?)?;
-    m.add_function(wrap_pyfunction!(_ifaddresses, m)?)?;
-    m.add_function(wrap_pyfunction!(_ip_to_string, m)?)?;
-    m.add_function(wrap_pyfunction!(_interface_is_up, m)?)?;
-    Ok(())
-}
@@ -1,120 +1,212 @@
-use crate::common::InterfaceDisplay;
-use crate::types::{
-    AddrPairs, IfAddrs, IfacesByIndex, ADDR_ADDR, AF_ALG, AF_INET, AF_INET6, AF_NETLINK, AF_PACKET,
-    AF_VSOCK, BROADCAST_ADDR, MASK_ADDR, PEER_ADDR,
-};
+use crate::api::{Address, HardwareAddress, Interface, InterfaceAddresses, LinkAddress};
+use crate::common::{split_alias, InterfaceDisplay, InterfaceFacts, InterfaceFilter, InterfaceRef};
+use crate::types::{IfacesByIndex, AF_INET, AF_INET6, AF_PACKET};
 use crate::NetifacesError;
-use nix::ifaddrs;
+use nix::ifaddrs::InterfaceAddress;
 use nix::net::if_::if_nameindex;
-use std::collections::{HashMap, HashSet};
-use std::fmt::Display;
-use std::net::UdpSocket;
+use nix::net::if_::if_nametoindex;
+use nix::sys::socket::{LinkAddr, SockaddrStorage};
+use std::collections::HashMap;
+use std::net::{IpAddr, UdpSocket};
 use std::os::fd::AsRawFd;
 
+/// The names of all the interfaces, sorted by index.
+///
+/// Like `posix_interfaces_by_index`, this enumerates the interfaces with `if_nameindex`,
+/// so the interfaces without any address are listed too.
+/// On Linux, the default netlink backend answers both from a dump of the links instead.
 pub fn posix_interfaces(
-    _display: InterfaceDisplay,
+    display: InterfaceDisplay,
+    filter: &InterfaceFilter,
 ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let mut s: HashSet<String> = HashSet::new();
-
-    let addrs = ifaddrs::getifaddrs()?;
-
-    for addr in addrs {
-        s.insert(addr.interface_name);
-    }
-
-    Ok(Vec::from_iter(s))
+    Ok(posix_interfaces_by_index(display, filter)?
+        .into_values()
+        .collect())
 }
 
 pub fn posix_interfaces_by_index(
     _display: InterfaceDisplay,
+    filter: &InterfaceFilter,
 ) -> Result<IfacesByIndex, Box<dyn std::error::Error>> {
+    filter.check_supported(false, false, false)?;
+    let matcher = filter.matcher()?;
+
+    // The flags and address families of the interfaces, only read if the filter needs them
+    let mut states: HashMap<String, (u32, Vec<u8>)> = HashMap::new();
+    if filter.looks_at_state() {
+        for if_addr in nix::ifaddrs::getifaddrs()? {
+            let (flags, families) = states.entry(if_addr.interface_name).or_default();
+            *flags |= if_addr.flags.bits() as u32;
+            families.extend(if_addr.address.as_ref().and_then(family));
+        }
+    }
+
     let mut interfaces = IfacesByIndex::new();
 
     let iface_names_idxes = if_nameindex()?;
 
     for iface in &iface_names_idxes {
-        let iface_index = iface.index() as usize;
-        interfaces.insert(iface_index, iface.name().to_string_lossy().to_string());
+        let name = iface.name().to_string_lossy().to_string();
+        let (flags, families) = states.get(&name).cloned().unwrap_or_default();
+
+        let facts = InterfaceFacts {
+            name: &name,
+            up: flags & libc::IFF_RUNNING as u32 != 0,
+            loopback: flags & libc::IFF_LOOPBACK as u32 != 0,
+            physical: false,
+            kind: None,
+            master: None,
+            families: &families,
+        };
+
+        if matcher.matches(&facts) {
+            interfaces.insert(iface.index(), name);
+        }
     }
 
     Ok(interfaces)
 }
 
-fn add_to_types_mat(
-    af_class: u8,
-    addr: &dyn Display,
-    class: &str,
-    types_mat: &mut HashMap<i32, Vec<AddrPairs>>,
-    any: &mut bool,
-) {
-    let e = types_mat.entry(af_class.into()).or_default();
-
-    if !*any {
-        *any = true;
-        e.push(HashMap::new());
+/// The family of an address, for the families netifaces reports.
+fn family(address: &SockaddrStorage) -> Option<u8> {
+    if address.as_link_addr().is_some() {
+        Some(AF_PACKET)
+    } else if address.as_sockaddr_in().is_some() {
+        Some(AF_INET)
+    } else if address.as_sockaddr_in6().is_some() {
+        Some(AF_INET6)
+    } else {
+        None
+    }
+}
+
+fn hardware_address(link_addr: &LinkAddr) -> HardwareAddress {
+    HardwareAddress(link_addr.addr().map_or(Vec::new(), |addr| addr.to_vec()))
+}
+
+fn ip(address: &SockaddrStorage) -> Option<IpAddr> {
+    match (address.as_sockaddr_in(), address.as_sockaddr_in6()) {
+        (Some(inet_addr), _) => Some(inet_addr.ip().into()),
+        (_, Some(inet_addr)) => Some(inet_addr.ip().into()),
+        _ => None,
+    }
+}
+
+/// Add the address of one `getifaddrs` entry to an interface's addresses.
+fn add_if_addr(if_addr: &InterfaceAddress, addresses: &mut InterfaceAddresses) {
+    let address = match &if_addr.address {
+        Some(address) => address,
+        None => return,
     };
+    let hardware_of = |address: &Option<SockaddrStorage>| {
+        address
+            .as_ref()
+            .and_then(|address| address.as_link_addr().map(hardware_address))
+    };
+    let ip_of = |address: &Option<SockaddrStorage>| address.as_ref().and_then(ip);
 
-    let l = e.len();
-    e[l - 1].insert(class.to_string(), format!("{addr}"));
+    if let Some(link_addr) = address.as_link_addr() {
+        addresses.link_address = Some(LinkAddress {
+            address: hardware_address(link_addr),
+            broadcast: hardware_of(&if_addr.broadcast),
+            peer: hardware_of(&if_addr.destination),
+        });
+    } else if let Some(ip) = ip(address) {
+        addresses.addresses.push(Address {
+            scope_id: address
+                .as_sockaddr_in6()
+                .map(|inet_addr| inet_addr.scope_id())
+                .filter(|scope_id| *scope_id != 0),
+            broadcast: ip_of(&if_addr.broadcast),
+            peer: ip_of(&if_addr.destination),
+            ..Address::with_netmask(ip, ip_of(&if_addr.netmask))
+        });
+    }
 }
 
-pub fn posix_ifaddresses(if_name: &str) -> Result<IfAddrs, Box<dyn std::error::Error>> {
-    let mut types_mat: HashMap<i32, Vec<AddrPairs>> = HashMap::new();
-    let if_addrs = nix::ifaddrs::getifaddrs()?;
+/// The name of the interface a `getifaddrs` entry belongs to: on Linux, the entries of
+/// the IPv4 label aliases (e.g. `eth0:1`) are named after the alias.
+fn base_name(if_addr: &InterfaceAddress) -> String {
+    let name = InterfaceRef::Name(if_addr.interface_name.clone());
+
+    match split_alias(&name) {
+        Some((_, base)) => base.to_string(),
+        None => if_addr.interface_name.clone(),
+    }
+}
+
+/// The addresses of an interface, including the ones of its aliases, or of a single
+/// alias when `iface` names one.
+///
+/// On Linux, the default netlink backend answers `ifaddresses` instead, so that only
+/// the addresses of the requested interface are fetched from the kernel.
+pub fn posix_ifaddresses(
+    iface: &InterfaceRef,
+) -> Result<InterfaceAddresses, Box<dyn std::error::Error>> {
+    let if_name = &interface_name(iface)?;
+    let mut addresses = InterfaceAddresses::default();
     let mut found_any = false;
 
-    for if_addr in if_addrs {
-        if if_name != if_addr.interface_name {
+    for if_addr in nix::ifaddrs::getifaddrs()? {
+        if *if_name != if_addr.interface_name && *if_name != base_name(&if_addr) {
             continue;
         }
         found_any = true;
 
-        // Addr of the interface
-        let mut any = false;
-
-        for (name, ss) in vec![
-            (ADDR_ADDR, if_addr.address),
-            (MASK_ADDR, if_addr.netmask),
-            (BROADCAST_ADDR, if_addr.broadcast),
-            (PEER_ADDR, if_addr.destination),
-        ] {
-            if let Some(address) = ss {
-                if let Some(mac_addr) = address.as_link_addr() {
-                    add_to_types_mat(AF_PACKET, mac_addr, name, &mut types_mat, &mut any);
-                }
+        add_if_addr(&if_addr, &mut addresses);
+    }
 
-                #[cfg(not(any(target_os = "ios", target_os = "macos")))]
-                if let Some(net_link) = address.as_netlink_addr() {
-                    add_to_types_mat(AF_NETLINK, net_link, name, &mut types_mat, &mut any);
-                }
+    if found_any {
+        Ok(addresses)
+    } else {
+        Err(Box::new(iface.not_found()))
+    }
+}
 
-                #[cfg(not(any(target_os = "ios", target_os = "macos")))]
-                if let Some(vsock_addr) = address.as_vsock_addr() {
-                    add_to_types_mat(AF_VSOCK, vsock_addr, name, &mut types_mat, &mut any);
-                }
+/// Take a snapshot of every interface: the interfaces of `if_nameindex`, with the
+/// addresses of a single call to `getifaddrs`, the ones of aliases included.
+pub fn posix_snapshot() -> Result<Vec<Interface>, Box<dyn std::error::Error>> {
+    let mut snapshot: Vec<Interface> = Vec::new();
+    let mut snapshot_addrs: Vec<InterfaceAddresses> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
 
-                if let Some(inet_addr) = address.as_sockaddr_in() {
-                    add_to_types_mat(AF_INET, &inet_addr.ip(), name, &mut types_mat, &mut any);
-                }
+    for iface in &if_nameindex()? {
+        let name = iface.name().to_string_lossy().to_string();
+        positions.insert(name.clone(), snapshot.len());
+        snapshot.push(Interface {
+            name,
+            index: iface.index(),
+            flags: 0,
+            up: false,
+            mtu: None,
+            link_address: None,
+            addresses: Vec::new(),
+        });
+        snapshot_addrs.push(InterfaceAddresses::default());
+    }
 
-                #[cfg(not(any(target_os = "ios", target_os = "macos")))]
-                if let Some(alg_addr) = address.as_alg_addr() {
-                    add_to_types_mat(AF_ALG, alg_addr, name, &mut types_mat, &mut any);
-                }
+    for if_addr in nix::ifaddrs::getifaddrs()? {
+        let position = match positions.get(&base_name(&if_addr)) {
+            Some(position) => *position,
+            None => continue,
+        };
 
-                if let Some(inet_addr) = address.as_sockaddr_in6() {
-                    add_to_types_mat(AF_INET6, &inet_addr.ip(), name, &mut types_mat, &mut any);
-                }
-            }
-        }
+        // The entries of an alias carry the flags of its interface
+        let iface = &mut snapshot[position];
+        iface.flags |= if_addr.flags.bits() as u32;
+        iface.up = iface.flags & libc::IFF_RUNNING as u32 != 0;
+
+        add_if_addr(&if_addr, &mut snapshot_addrs[position]);
     }
 
-    if found_any {
-        return Ok(types_mat);
-    } else {
-        let err_msg = format!("Failed to find an interface with the name {}", if_name);
-        return Err(Box::new(NetifacesError(err_msg)));
+    for (iface, addresses) in snapshot.iter_mut().zip(snapshot_addrs) {
+        iface.link_address = addresses.link_address;
+        iface.addresses = addresses.addresses;
     }
+
+    snapshot.sort_by_key(|iface| iface.index);
+
+    Ok(snapshot)
 }
 
 // SIOCGIFFLAGS constant currently not available from the libc crate on Apple platforms.
@@ -143,29 +235,41 @@ fn read_interface_flags(if_name: &str) -> Result<libc::c_short, Box<dyn std::err
 
         // Copy in the name.
         // We checked the length earlier so we know it will fit.
-        for byte_idx in 0..if_name.as_bytes().len() {
+        for byte_idx in 0..if_name.len() {
             ifreq.ifr_name[byte_idx] = if_name.as_bytes()[byte_idx] as libc::c_char;
         }
-        ifreq.ifr_name[if_name.as_bytes().len()] = 0;
+        ifreq.ifr_name[if_name.len()] = 0;
 
-        // Run ioctl
+        // Run ioctl.
+        // The request type of ioctl differs between the libcs, hence the conversion.
+        #[allow(clippy::useless_conversion)]
         let ioctl_ret = libc::ioctl(socket.as_raw_fd(), SIOCGIFFLAGS.try_into()?, &ifreq);
 
         match ioctl_ret {
             0 => Ok(ifreq.ifr_ifru.ifru_flags),
             _ => {
-                let err_msg = format!(
-                    "Error reading interface flags for {if_name}: {}",
-                    nix::errno::Errno::last()
-                );
-                Err(Box::new(NetifacesError(err_msg)))
+                let errno = nix::errno::Errno::last();
+                match errno {
+                    nix::errno::Errno::ENODEV | nix::errno::Errno::ENXIO => Err(Box::new(
+                        NetifacesError::InterfaceNotFound(InterfaceRef::Name(if_name.to_string())),
+                    )),
+                    _ => Err(Box::new(NetifacesError::System {
+                        message: format!("Error reading interface flags for {if_name}: {errno}"),
+                        errno: errno as i32,
+                    })),
+                }
             }
         }
     }
 }
 
 /// Get the status of an interface (up/down) on POSIX
-pub fn posix_interface_is_up(if_name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+///
+/// On Linux, the default netlink backend answers this instead, as it can look the
+/// interface up by index without going through its name.
+pub fn posix_interface_is_up(iface: &InterfaceRef) -> Result<bool, Box<dyn std::error::Error>> {
+    let if_name = &interface_name(iface)?;
+
     // Testing on Linux, it looks like the flag we want is IFF_RUNNING, not IFF_UP.
     // IFF_UP is the "administrative" status, i.e. "ip link set <name> up/down".
     // IFF_RUNNING only sets when the interface is both administratively up
@@ -177,3 +281,37 @@ pub fn posix_interface_is_up(if_name: &str) -> Result<bool, Box<dyn std::error::
     // The only way to tell is the absence of an IP address.
     Ok((read_interface_flags(if_name)? & libc::IFF_RUNNING as libc::c_short) != 0)
 }
+
+/// Look up the index of an interface by its name.
+pub fn posix_if_nametoindex(if_name: &str) -> Result<u32, Box<dyn std::error::Error>> {
+    match if_nametoindex(if_name) {
+        Ok(index) => Ok(index),
+        Err(_) => Err(Box::new(
+            InterfaceRef::Name(if_name.to_string()).not_found(),
+        )),
+    }
+}
+
+/// Look up the name of an interface by its index.
+pub fn posix_if_indextoname(
+    if_index: u32,
+    _display: InterfaceDisplay,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut buf = [0 as libc::c_char; libc::IF_NAMESIZE];
+
+    let ret = unsafe { libc::if_indextoname(if_index as libc::c_uint, buf.as_mut_ptr()) };
+    if ret.is_null() {
+        return Err(Box::new(InterfaceRef::Index(if_index).not_found()));
+    }
+
+    let name = unsafe { std::ffi::CStr::from_ptr(buf.as_ptr()) };
+    Ok(name.to_string_lossy().into_owned())
+}
+
+/// The name of an interface, looking it up if it is designated by its index.
+fn interface_name(iface: &InterfaceRef) -> Result<String, Box<dyn std::error::Error>> {
+    match iface {
+        InterfaceRef::Name(name) => Ok(name.clone()),
+        InterfaceRef::Index(index) => posix_if_indextoname(*index, InterfaceDisplay::HumanReadable),
+    }
+}
@@ -0,0 +1,827 @@
+//! Interface queries answered from rtnetlink dumps, on Linux.
+//!
+//! The links and addresses of the dumps are turned into the types of [crate::api],
+//! with the broadcast and point-to-point addresses told apart the way `getifaddrs`
+//! does, so that results are identical whichever way they were obtained.
+
+use crate::api::{self, HardwareAddress};
+use crate::common::{
+    self, split_alias, InterfaceDisplay, InterfaceFacts, InterfaceFilter, InterfaceRef, IpNetwork,
+};
+use crate::netlink::{Address, Link, Neighbour, NetlinkSocket, Route, RouteQuery};
+#[cfg(feature = "python")]
+use crate::types::IfStats;
+use crate::types::{IfacesByIndex, AF_INET, AF_INET6, AF_PACKET, AF_UNSPEC};
+use crate::NetifacesError;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+/// The names of the counters of `struct rtnl_link_stats64`, in order.
+#[cfg(feature = "python")]
+const LINK_STATS64_FIELDS: [&str; 24] = [
+    "rx_packets",
+    "tx_packets",
+    "rx_bytes",
+    "tx_bytes",
+    "rx_errors",
+    "tx_errors",
+    "rx_dropped",
+    "tx_dropped",
+    "multicast",
+    "collisions",
+    "rx_length_errors",
+    "rx_over_errors",
+    "rx_crc_errors",
+    "rx_frame_errors",
+    "rx_fifo_errors",
+    "rx_missed_errors",
+    "tx_aborted_errors",
+    "tx_carrier_errors",
+    "tx_fifo_errors",
+    "tx_heartbeat_errors",
+    "tx_window_errors",
+    "rx_compressed",
+    "tx_compressed",
+    "rx_nohandler",
+];
+
+/// `getifaddrs` stores the broadcast and the point-to-point destination addresses
+/// in the same field, which is then interpreted according to the link's flags.
+enum Secondary {
+    Broadcast,
+    Peer,
+}
+
+fn secondary_kind(link_flags: u32) -> Option<Secondary> {
+    if link_flags & libc::IFF_POINTOPOINT as u32 != 0 {
+        Some(Secondary::Peer)
+    } else if link_flags & libc::IFF_BROADCAST as u32 != 0 {
+        Some(Secondary::Broadcast)
+    } else {
+        None
+    }
+}
+
+/// The hardware address of a link, if it has one.
+pub fn link_address(link: &Link) -> Option<api::LinkAddress> {
+    let mut link_address = api::LinkAddress {
+        address: HardwareAddress(link.address.clone()?),
+        broadcast: None,
+        peer: None,
+    };
+    let secondary = link.broadcast.clone().map(HardwareAddress);
+
+    match secondary_kind(link.flags) {
+        Some(Secondary::Peer) => link_address.peer = secondary,
+        Some(Secondary::Broadcast) => link_address.broadcast = secondary,
+        None => {}
+    }
+
+    Some(link_address)
+}
+
+/// An IPv4 or IPv6 address, on a link with the given flags.
+pub fn ip_address(link_flags: u32, address: &Address) -> Option<api::Address> {
+    let mut ip_address = api::Address {
+        flags: address.flags,
+        ..api::Address::new(address.local_ip()?, Some(address.prefix_len))
+    }
+    .on_interface(address.index);
+
+    // When the kernel gives both a local address and IFA_ADDRESS, the latter is the
+    // destination; an explicit broadcast address takes precedence over it.
+    let secondary = address.broadcast.or(address.local.and(address.address));
+
+    match secondary_kind(link_flags) {
+        Some(Secondary::Peer) => ip_address.peer = secondary,
+        Some(Secondary::Broadcast) => ip_address.broadcast = secondary,
+        None => {}
+    }
+
+    Some(ip_address)
+}
+
+/// The addresses of a link, which are the ones of `addresses` it holds.
+pub fn link_addresses<'a>(
+    link: &Link,
+    addresses: impl Iterator<Item = &'a Address>,
+) -> api::InterfaceAddresses {
+    api::InterfaceAddresses {
+        link_address: link_address(link),
+        addresses: addresses
+            .filter(|address| address.index == link.index)
+            .filter_map(|address| ip_address(link.flags, address))
+            .collect(),
+    }
+}
+
+/// A link along with its addresses, named the way `display` asks.
+pub fn link_interface<'a>(
+    link: &Link,
+    addresses: impl Iterator<Item = &'a Address>,
+    display: InterfaceDisplay,
+) -> api::Interface {
+    let addresses = link_addresses(link, addresses);
+
+    api::Interface {
+        name: display_name(link, display),
+        index: link.index,
+        flags: link.flags,
+        up: link.is_running(),
+        mtu: link.mtu,
+        link_address: addresses.link_address,
+        addresses: addresses.addresses,
+    }
+}
+
+/// The names of the links, by index.
+pub fn link_names(links: &[Link]) -> HashMap<u32, String> {
+    links
+        .iter()
+        .map(|link| (link.index, link.name.clone()))
+        .collect()
+}
+
+/// A route, if it is an IPv4 or IPv6 one: the other families (e.g. MPLS) have no IP
+/// destination.
+pub fn ip_route(route: &Route, names: &HashMap<u32, String>) -> Option<api::Route> {
+    let unspecified = match route.family {
+        AF_INET => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        AF_INET6 => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        _ => return None,
+    };
+
+    Some(api::Route {
+        destination: IpNetwork {
+            addr: route.destination.unwrap_or(unspecified),
+            prefix_len: route.dst_len,
+        },
+        gateway: route.gateway,
+        interface: route.oif.and_then(|oif| names.get(&oif).cloned()),
+        index: route.oif,
+        source: route.prefsrc,
+        metric: route.priority,
+        table: route.table,
+        protocol: route.protocol,
+        scope: route.scope,
+        route_type: route.kind,
+        nexthops: route
+            .nexthops
+            .iter()
+            .map(|hop| api::NextHop {
+                gateway: hop.gateway,
+                interface: hop.oif.and_then(|oif| names.get(&oif).cloned()),
+                index: hop.oif,
+                weight: hop.weight,
+                flags: hop.flags,
+            })
+            .collect(),
+        nexthop_id: route.nexthop_id,
+    })
+}
+
+/// The IPv4 and IPv6 ones of some routes.
+pub fn ip_routes<'a>(
+    routes: impl IntoIterator<Item = &'a Route>,
+    names: &HashMap<u32, String>,
+) -> Vec<api::Route> {
+    routes
+        .into_iter()
+        .filter_map(|route| ip_route(route, names))
+        .collect()
+}
+
+pub fn neighbour(neighbour: &Neighbour, names: &HashMap<u32, String>) -> api::Neighbour {
+    api::Neighbour {
+        family: neighbour.family,
+        ip: neighbour.destination,
+        hardware_address: neighbour.lladdr.clone().map(HardwareAddress),
+        interface: names.get(&neighbour.index).cloned(),
+        index: neighbour.index,
+        state: neighbour.state,
+        flags: neighbour.flags,
+    }
+}
+
+/// The counters of a link, if the kernel reported them.
+#[cfg(feature = "python")]
+pub fn link_stats(link: &Link) -> Option<IfStats> {
+    let counters = link.stats.as_ref()?;
+
+    Some(
+        LINK_STATS64_FIELDS
+            .iter()
+            .zip(counters)
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect(),
+    )
+}
+
+/// Take a snapshot of every interface, from a single dump of the links and a single
+/// dump of the addresses.
+pub fn netlink_snapshot(
+    display: InterfaceDisplay,
+) -> Result<Vec<api::Interface>, Box<dyn std::error::Error>> {
+    let mut sock = NetlinkSocket::new()?;
+    let links = sock.links()?;
+    let addresses = sock.addresses(AF_UNSPEC)?;
+
+    Ok(links_snapshot(&links, &addresses, display))
+}
+
+/// A snapshot of the links, with their addresses, sorted by index.
+pub fn links_snapshot(
+    links: &[Link],
+    addresses: &[Address],
+    display: InterfaceDisplay,
+) -> Vec<api::Interface> {
+    // Group the addresses by link first, to keep this linear in the number of links.
+    // An address whose link appeared after the links were dumped is skipped.
+    let mut by_link: HashMap<u32, Vec<&Address>> = HashMap::with_capacity(links.len());
+    for address in addresses {
+        by_link.entry(address.index).or_default().push(address);
+    }
+
+    let mut snapshot: Vec<api::Interface> = links
+        .iter()
+        .map(|link| {
+            let link_addrs = by_link.remove(&link.index).unwrap_or_default();
+            link_interface(link, link_addrs.into_iter(), display)
+        })
+        .collect();
+
+    snapshot.sort_by_key(|iface| iface.index);
+
+    snapshot
+}
+
+/// Whether a link is backed by a hardware device: the kernel names its parent device,
+/// or (before Linux 5.16) sysfs links to it.
+fn is_physical(link: &Link) -> bool {
+    link.parent_device.is_some()
+        || Path::new("/sys/class/net")
+            .join(&link.name)
+            .join("device")
+            .exists()
+}
+
+/// The links passing a filter. `addresses` only has to hold the addresses of the
+/// filter's family, when it has one.
+pub fn filter_links<'a>(
+    links: impl Iterator<Item = &'a Link>,
+    addresses: &[Address],
+    filter: &InterfaceFilter,
+) -> Result<Vec<&'a Link>, Box<dyn std::error::Error>> {
+    let matcher = filter.matcher()?;
+    let links: Vec<&Link> = links.collect();
+    let names: HashMap<u32, &str> = links
+        .iter()
+        .map(|link| (link.index, link.name.as_str()))
+        .collect();
+
+    let mut families: HashMap<u32, Vec<u8>> = HashMap::new();
+    for address in addresses {
+        families
+            .entry(address.index)
+            .or_default()
+            .push(address.family);
+    }
+
+    Ok(links
+        .into_iter()
+        .filter(|link| matcher.matches_name(&link.name))
+        .filter(|link| {
+            let mut link_families = families.get(&link.index).cloned().unwrap_or_default();
+            if link.address.is_some() {
+                link_families.push(AF_PACKET);
+            }
+
+            matcher.matches(&InterfaceFacts {
+                name: &link.name,
+                up: link.is_running(),
+                loopback: link.flags & libc::IFF_LOOPBACK as u32 != 0,
+                // Only look at sysfs when the answer matters
+                physical: filter.physical && is_physical(link),
+                kind: link.kind.as_deref(),
+                master: link.master.and_then(|master| names.get(&master).copied()),
+                families: &link_families,
+            })
+        })
+        .collect())
+}
+
+/// The addresses a filter needs to be evaluated.
+fn filter_addresses(
+    sock: &mut NetlinkSocket,
+    filter: &InterfaceFilter,
+) -> Result<Vec<Address>, Box<dyn std::error::Error>> {
+    match filter.has_address {
+        Some(AF_PACKET) | None => Ok(Vec::new()),
+        Some(family) => Ok(sock.addresses(family)?),
+    }
+}
+
+/// The names of the interfaces passing a filter, sorted by index, from a single dump
+/// of the links.
+pub fn netlink_interfaces(
+    display: InterfaceDisplay,
+    filter: &InterfaceFilter,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    Ok(netlink_interfaces_by_index(display, filter)?
+        .into_values()
+        .collect())
+}
+
+pub fn netlink_interfaces_by_index(
+    display: InterfaceDisplay,
+    filter: &InterfaceFilter,
+) -> Result<IfacesByIndex, Box<dyn std::error::Error>> {
+    let mut sock = NetlinkSocket::new()?;
+    let links = sock.links()?;
+    let addresses = filter_addresses(&mut sock, filter)?;
+
+    Ok(filter_links(links.iter(), &addresses, filter)?
+        .into_iter()
+        .map(|link| (link.index, display_name(link, display)))
+        .collect())
+}
+
+/// The name a link is displayed with.
+pub fn display_name(link: &Link, display: InterfaceDisplay) -> String {
+    match display {
+        InterfaceDisplay::HumanReadable => link.ifalias.as_ref().unwrap_or(&link.name).clone(),
+        InterfaceDisplay::MachineReadable => link.name.clone(),
+        InterfaceDisplay::AltName => link.altnames.first().unwrap_or(&link.name).clone(),
+        InterfaceDisplay::Index => link.index.to_string(),
+    }
+}
+
+/// Find a link by one of the names it can be displayed with that the kernel does not
+/// look up: its index or its description. Descriptions need not be unique, so an
+/// ambiguous one is an error.
+pub fn link_by_display_name<'a>(
+    links: impl Iterator<Item = &'a Link> + Clone,
+    name: &str,
+) -> Result<Option<&'a Link>, NetifacesError> {
+    if let Some(link) = links.clone().find(|link| link.index.to_string() == name) {
+        return Ok(Some(link));
+    }
+
+    let mut described = links.filter(|link| link.ifalias.as_deref() == Some(name));
+    match (described.next(), described.next()) {
+        (Some(_), Some(_)) => Err(NetifacesError::AmbiguousInterface(name.to_string())),
+        (link, _) => Ok(link),
+    }
+}
+
+/// Where links and their addresses are looked up: a netlink socket, or a model of the
+/// system held in memory. Interface references are resolved against it, so that every
+/// way of answering a query understands the same names.
+pub trait LinkSource {
+    /// An owned link from a socket, or a borrowed one from a model
+    type Link: Borrow<Link>;
+    type Address: Borrow<Address>;
+
+    /// The link the kernel would find itself: by name, alternative name or index.
+    fn link(
+        &mut self,
+        iface: &InterfaceRef,
+    ) -> Result<Option<Self::Link>, Box<dyn std::error::Error>>;
+
+    fn links(&mut self) -> Result<Vec<Self::Link>, Box<dyn std::error::Error>>;
+
+    /// The addresses of a link. Others of the given family may be left out.
+    fn addresses_on(
+        &mut self,
+        link: &Link,
+        family: u8,
+    ) -> Result<Vec<Self::Address>, Box<dyn std::error::Error>>;
+}
+
+impl LinkSource for NetlinkSocket {
+    type Link = Link;
+    type Address = Address;
+
+    fn link(&mut self, iface: &InterfaceRef) -> Result<Option<Link>, Box<dyn std::error::Error>> {
+        Ok(NetlinkSocket::link(self, iface)?)
+    }
+
+    fn links(&mut self) -> Result<Vec<Link>, Box<dyn std::error::Error>> {
+        Ok(NetlinkSocket::links(self)?)
+    }
+
+    fn addresses_on(
+        &mut self,
+        link: &Link,
+        family: u8,
+    ) -> Result<Vec<Address>, Box<dyn std::error::Error>> {
+        Ok(NetlinkSocket::addresses_on(self, link.index, family)?)
+    }
+}
+
+/// Look up a link by any name it can be displayed with, or by its index.
+pub fn find_link<S: LinkSource>(
+    source: &mut S,
+    iface: &InterfaceRef,
+) -> Result<Option<S::Link>, Box<dyn std::error::Error>> {
+    if let Some(link) = source.link(iface)? {
+        return Ok(Some(link));
+    }
+
+    match iface {
+        InterfaceRef::Name(name) => {
+            let links = source.links()?;
+            let index = link_by_display_name(links.iter().map(Borrow::borrow), name)?
+                .map(|link| link.index);
+            Ok(links
+                .into_iter()
+                .find(|link| Some(link.borrow().index) == index))
+        }
+        InterfaceRef::Index(_) => Ok(None),
+    }
+}
+
+/// The link an interface reference designates and its addresses. The flag tells
+/// whether the reference is an IPv4 alias (e.g. `eth0:1`), whose addresses are then
+/// only the ones bearing its label.
+///
+/// Aliases are not interfaces and are never enumerated, their addresses are reported
+/// with their link's. They can still be looked up by name, as netifaces always allowed.
+type Lookup<S> = (
+    <S as LinkSource>::Link,
+    Vec<<S as LinkSource>::Address>,
+    bool,
+);
+
+fn lookup_addresses<S: LinkSource>(
+    source: &mut S,
+    iface: &InterfaceRef,
+) -> Result<Lookup<S>, Box<dyn std::error::Error>> {
+    if let Some(link) = source.link(iface)? {
+        let addresses = source.addresses_on(link.borrow(), AF_UNSPEC)?;
+        return Ok((link, addresses, false));
+    }
+
+    if let Some((alias, base)) = split_alias(iface) {
+        if let Some(link) = source.link(&InterfaceRef::Name(base.to_string()))? {
+            let addresses: Vec<S::Address> = source
+                .addresses_on(link.borrow(), AF_INET)?
+                .into_iter()
+                .filter(|address| {
+                    let address: &Address = address.borrow();
+                    address.family == AF_INET && address.label.as_deref() == Some(alias)
+                })
+                .collect();
+
+            if !addresses.is_empty() {
+                return Ok((link, addresses, true));
+            }
+        }
+    }
+
+    if let Some(link) = find_link(source, iface)? {
+        let addresses = source.addresses_on(link.borrow(), AF_UNSPEC)?;
+        return Ok((link, addresses, false));
+    }
+
+    Err(Box::new(iface.not_found()))
+}
+
+/// The addresses of one interface, or of one alias.
+pub fn interface_addresses<S: LinkSource>(
+    source: &mut S,
+    iface: &InterfaceRef,
+) -> Result<api::InterfaceAddresses, Box<dyn std::error::Error>> {
+    let (link, addresses, alias) = lookup_addresses(source, iface)?;
+    let link = link.borrow();
+    let addresses = addresses.iter().map(Borrow::borrow);
+
+    Ok(match alias {
+        true => api::InterfaceAddresses {
+            link_address: None,
+            addresses: addresses
+                .filter_map(|address| ip_address(link.flags, address))
+                .collect(),
+        },
+        false => link_addresses(link, addresses),
+    })
+}
+
+/// An interface with its addresses, looked up like the kernel would, or by the names
+/// it is displayed with.
+pub fn interface<S: LinkSource>(
+    source: &mut S,
+    iface: &InterfaceRef,
+    display: InterfaceDisplay,
+) -> Result<api::Interface, Box<dyn std::error::Error>> {
+    let link = match find_link(source, iface)? {
+        Some(link) => link,
+        None => return Err(Box::new(iface.not_found())),
+    };
+    let addresses = source.addresses_on(link.borrow(), AF_UNSPEC)?;
+
+    Ok(link_interface(
+        link.borrow(),
+        addresses.iter().map(Borrow::borrow),
+        display,
+    ))
+}
+
+/// The addresses of one interface, or of one alias.
+pub fn netlink_ifaddresses(
+    iface: &InterfaceRef,
+) -> Result<api::InterfaceAddresses, Box<dyn std::error::Error>> {
+    interface_addresses(&mut NetlinkSocket::new()?, iface)
+}
+
+pub fn netlink_interface(
+    iface: &InterfaceRef,
+    display: InterfaceDisplay,
+) -> Result<api::Interface, Box<dyn std::error::Error>> {
+    interface(&mut NetlinkSocket::new()?, iface, display)
+}
+
+pub fn netlink_interface_is_up(iface: &InterfaceRef) -> Result<bool, Box<dyn std::error::Error>> {
+    match find_link(&mut NetlinkSocket::new()?, iface)? {
+        Some(link) => Ok(link.is_running()),
+        None => Err(Box::new(iface.not_found())),
+    }
+}
+
+/// Look up the index of an interface by its name, or by any other name it can be
+/// displayed with.
+pub fn netlink_if_nametoindex(if_name: &str) -> Result<u32, Box<dyn std::error::Error>> {
+    let iface = InterfaceRef::Name(if_name.to_string());
+
+    match find_link(&mut NetlinkSocket::new()?, &iface)? {
+        Some(link) => Ok(link.index),
+        None => Err(Box::new(iface.not_found())),
+    }
+}
+
+/// Look up the name of an interface by its index, displayed as requested.
+pub fn netlink_if_indextoname(
+    if_index: u32,
+    display: InterfaceDisplay,
+) -> Result<String, Box<dyn std::error::Error>> {
+    match NetlinkSocket::new()?.link_by_index(if_index)? {
+        Some(link) => Ok(display_name(&link, display)),
+        None => Err(Box::new(InterfaceRef::Index(if_index).not_found())),
+    }
+}
+
+/// The routes of the given family (AF_UNSPEC for all of them), from every table, that go
+/// out through one interface. Only that interface's routes are dumped by the kernel.
+pub fn netlink_interface_routes(
+    iface: &InterfaceRef,
+    family: u8,
+) -> Result<Vec<api::Route>, Box<dyn std::error::Error>> {
+    let mut sock = NetlinkSocket::new()?;
+
+    let link = match find_link(&mut sock, iface)? {
+        Some(link) => link,
+        None => return Err(Box::new(iface.not_found())),
+    };
+    let mut names = HashMap::from([(link.index, link.name.clone())]);
+    let routes = sock.routes_via(link.index, family)?;
+    // The other nexthops of multipath routes go out through other interfaces
+    names.extend(route_names(&mut sock, &routes, &names)?);
+
+    Ok(ip_routes(&routes, &names))
+}
+
+/// Look up the names of the interfaces the routes go out through that are not `known`
+/// yet, one at a time; this is cheaper than a dump for the routes of a few interfaces.
+pub fn route_names(
+    sock: &mut NetlinkSocket,
+    routes: &[Route],
+    known: &HashMap<u32, String>,
+) -> io::Result<HashMap<u32, String>> {
+    let oifs = routes.iter().flat_map(|route| {
+        let hops = route.nexthops.iter().filter_map(|hop| hop.oif);
+        route.oif.into_iter().chain(hops)
+    });
+    let mut names = HashMap::new();
+
+    for index in oifs {
+        if known.contains_key(&index) || names.contains_key(&index) {
+            continue;
+        }
+        if let Some(link) = sock.link_by_index(index)? {
+            names.insert(link.index, link.name);
+        }
+    }
+
+    Ok(names)
+}
+
+/// All the routes of the given family (AF_UNSPEC for all of them), from every table, with
+/// the nexthops of multipath routes and of the routes using nexthop objects.
+pub fn netlink_routes(family: u8) -> Result<Vec<api::Route>, Box<dyn std::error::Error>> {
+    let mut sock = NetlinkSocket::new()?;
+    let routes = sock.routes(family)?;
+    let names = link_names(&sock.links()?);
+
+    Ok(ip_routes(&routes, &names))
+}
+
+/// The neighbour tables of the given family (AF_UNSPEC for all of them).
+pub fn netlink_neighbours(family: u8) -> Result<Vec<api::Neighbour>, Box<dyn std::error::Error>> {
+    let mut sock = NetlinkSocket::new()?;
+    let neighbours = sock.neighbours(family)?;
+    let names = link_names(&sock.links()?);
+
+    Ok(neighbours
+        .iter()
+        .map(|entry| neighbour(entry, &names))
+        .collect())
+}
+
+/// An address of a link, along with the link holding it.
+fn address_owner(link: &Link, address: &Address) -> Option<api::AddressOwner> {
+    Some(api::AddressOwner {
+        interface: link.name.clone(),
+        index: link.index,
+        address: ip_address(link.flags, address)?,
+    })
+}
+
+/// Every address of a family, along with the link holding it, sorted by index.
+fn address_owners(
+    sock: &mut NetlinkSocket,
+    family: u8,
+    keep: impl Fn(&Address) -> bool,
+) -> Result<Vec<api::AddressOwner>, Box<dyn std::error::Error>> {
+    let links: HashMap<u32, Link> = sock
+        .links()?
+        .into_iter()
+        .map(|link| (link.index, link))
+        .collect();
+
+    let mut owners: Vec<api::AddressOwner> = sock
+        .addresses(family)?
+        .iter()
+        .filter(|address| keep(address))
+        .filter_map(|address| address_owner(links.get(&address.index)?, address))
+        .collect();
+    owners.sort_by_key(|owner| owner.index);
+
+    Ok(owners)
+}
+
+/// Parse an IP address, which may carry an IPv6 zone (e.g. "fe80::1%eth0"), returned
+/// as the index of the interface it designates.
+fn parse_zoned_ip(
+    sock: &mut NetlinkSocket,
+    ip: &str,
+) -> Result<(IpAddr, Option<u32>), Box<dyn std::error::Error>> {
+    let (addr, zone) = common::parse_zoned_ip(ip)?;
+
+    let zone = match zone {
+        Some(zone) => {
+            let iface = InterfaceRef::Name(zone.to_string());
+            match find_link(sock, &iface)? {
+                Some(link) => Some(link.index),
+                None => return Err(Box::new(iface.not_found())),
+            }
+        }
+        None => None,
+    };
+
+    Ok((addr, zone))
+}
+
+/// The interface holding a local address. An IPv6 zone (e.g. "fe80::1%eth0") restricts
+/// the search to one interface, since the same link-local address can be held by
+/// several; otherwise, the one with the lowest index is returned.
+pub fn netlink_interface_for_address(
+    ip: &str,
+) -> Result<Option<api::AddressOwner>, Box<dyn std::error::Error>> {
+    let mut sock = NetlinkSocket::new()?;
+    let (addr, zone) = parse_zoned_ip(&mut sock, ip)?;
+
+    let owners = address_owners(&mut sock, api::family_of(&addr), |address| {
+        address.local_ip() == Some(addr) && zone.is_none_or(|index| address.index == index)
+    })?;
+
+    Ok(owners.into_iter().next())
+}
+
+/// The addresses whose subnet overlaps a network, i.e. the interfaces on that network,
+/// sorted by index. An interface is listed once per matching address.
+pub fn netlink_interfaces_in_network(
+    network: &str,
+) -> Result<Vec<api::AddressOwner>, Box<dyn std::error::Error>> {
+    let network = IpNetwork::parse(network)?;
+
+    address_owners(
+        &mut NetlinkSocket::new()?,
+        api::family_of(&network.addr),
+        |address| {
+            address.local_ip().is_some_and(|addr| {
+                let subnet = IpNetwork {
+                    addr,
+                    prefix_len: address.prefix_len,
+                };
+                subnet.overlaps(&network)
+            })
+        },
+    )
+}
+
+/// The route the kernel would use for a packet to `destination`, as "ip route get"
+/// reports it: the route as it is in its table, with the output interface, gateway and
+/// preferred source address resolved for this destination. None if there is no route.
+pub fn netlink_route_get(
+    destination: &str,
+    source: Option<&str>,
+    oif: Option<&InterfaceRef>,
+    mark: Option<u32>,
+) -> Result<Option<api::Route>, Box<dyn std::error::Error>> {
+    let mut sock = NetlinkSocket::new()?;
+
+    let (destination, zone) = parse_zoned_ip(&mut sock, destination)?;
+    let source = match source {
+        Some(source) => Some(
+            source
+                .parse::<IpAddr>()
+                .map_err(|_| NetifacesError::Other(format!("Invalid IP address {source}")))?,
+        ),
+        None => None,
+    };
+    if source.is_some_and(|source| api::family_of(&source) != api::family_of(&destination)) {
+        return Err(Box::new(NetifacesError::Other(
+            "The source and destination addresses are not of the same family".to_string(),
+        )));
+    }
+    let oif = match oif {
+        Some(iface) => match find_link(&mut sock, iface)? {
+            Some(link) => Some(link.index),
+            None => return Err(Box::new(iface.not_found())),
+        },
+        None => zone,
+    };
+
+    let query = RouteQuery {
+        destination,
+        source,
+        oif,
+        mark,
+    };
+    let resolved = match sock.route_get(&query, false)? {
+        Some(route) => route,
+        None => return Ok(None),
+    };
+    // Kernels before 4.13 do not know RTM_F_FIB_MATCH, and resolve the route again
+    let mut route = match sock.route_get(&query, true)? {
+        Some(matched) => Route {
+            oif: resolved.oif,
+            gateway: resolved.gateway,
+            prefsrc: resolved.prefsrc,
+            ..matched
+        },
+        None => resolved,
+    };
+    // The kernel only picks a source address if none was given
+    route.prefsrc = route.prefsrc.or(source);
+    sock.resolve_nexthops(std::slice::from_mut(&mut route))?;
+
+    let names = route_names(&mut sock, std::slice::from_ref(&route), &HashMap::new())?;
+
+    Ok(ip_route(&route, &names))
+}
+
+/// A global address standing for any destination reached through a default route, to
+/// ask the kernel which source address it picks for them. Nothing is ever sent to it.
+fn default_destination(family: u8) -> IpAddr {
+    match family {
+        AF_INET6 => IpAddr::V6(Ipv6Addr::new(0x2001, 0x4860, 0x4860, 0, 0, 0, 0, 0x8888)),
+        _ => IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8)),
+    }
+}
+
+/// The source address the kernel picks for the destinations of a family reached
+/// through a default route going out of an interface.
+pub fn netlink_default_source(
+    family: u8,
+    iface: &InterfaceRef,
+) -> Result<Option<IpAddr>, Box<dyn std::error::Error>> {
+    let mut sock = NetlinkSocket::new()?;
+    let oif = match find_link(&mut sock, iface)? {
+        Some(link) => link.index,
+        None => return Err(Box::new(iface.not_found())),
+    };
+    let query = RouteQuery {
+        destination: default_destination(family),
+        source: None,
+        oif: Some(oif),
+        mark: None,
+    };
+
+    Ok(sock
+        .route_get(&query, false)?
+        .and_then(|resolved| resolved.prefsrc))
+}
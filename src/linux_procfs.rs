@@ -0,0 +1,543 @@
+//! Interface queries answered from the files of /proc/net and /sys/class/net, on Linux.
+//!
+//! These files usually stay readable where netlink sockets and the interface ioctls are
+//! forbidden, e.g. by seccomp profiles or in gVisor. The links and addresses are read
+//! into the same structures as the netlink dumps, and formatted by the same code.
+//!
+//! No file lists the IPv4 addresses: they are the local addresses of the local routing
+//! table (/proc/net/fib_trie), each held by the interface of the most specific subnet
+//! route containing it. The addresses without such a route, e.g. the ones of
+//! point-to-point links, are missing, and so are the labels of the addresses; IPv4
+//! aliases (e.g. `eth0:1`) cannot be looked up.
+
+use crate::api;
+use crate::common::{InterfaceDisplay, InterfaceFilter, InterfaceRef, IpNetwork};
+use crate::linux_netlink::{
+    display_name, filter_links, link_addresses, link_by_display_name, link_interface, link_names,
+    links_snapshot, neighbour,
+};
+use crate::netlink::{
+    Address, Link, Neighbour, NTF_PROXY, NUD_INCOMPLETE, NUD_PERMANENT, NUD_REACHABLE,
+    RT_SCOPE_HOST, RT_SCOPE_LINK, RT_SCOPE_SITE, RT_SCOPE_UNIVERSE,
+};
+use crate::procfs::{
+    field, hex_ipv6, hex_u32, hex_u8, parse_lines, proc_routes, read_error, read_file, RTF_REJECT,
+};
+use crate::types::{IfacesByIndex, AF_INET, AF_INET6, AF_PACKET, AF_UNSPEC};
+use crate::NetifacesError;
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::Path;
+
+const DEV_FILE: &str = "/proc/net/dev";
+const IF_INET6_FILE: &str = "/proc/net/if_inet6";
+const FIB_TRIE_FILE: &str = "/proc/net/fib_trie";
+const ARP_FILE: &str = "/proc/net/arp";
+const SYSFS_NET: &str = "/sys/class/net";
+
+/// The IF_OPER_* operational states, by the names sysfs gives them.
+const OPERSTATES: [&str; 7] = [
+    "unknown",
+    "notpresent",
+    "down",
+    "lowerlayerdown",
+    "testing",
+    "dormant",
+    "up",
+];
+
+// The scopes of /proc/net/if_inet6
+const IFA_HOST: u8 = 0x10;
+const IFA_LINK: u8 = 0x20;
+const IFA_SITE: u8 = 0x40;
+
+// The flags of /proc/net/arp
+const ATF_COM: u32 = 0x02;
+const ATF_PERM: u32 = 0x04;
+const ATF_PUBL: u32 = 0x08;
+
+/// An attribute of a link in sysfs, without its final newline. None if the link is gone,
+/// or if the attribute has no value in the state of the link, e.g. the carrier of a
+/// link that is down.
+fn attribute(name: &str, attribute: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let path = format!("{SYSFS_NET}/{name}/{attribute}");
+
+    match std::fs::read_to_string(&path) {
+        Ok(value) => Ok(Some(value.trim_end_matches('\n').to_string())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound || e.raw_os_error() == Some(libc::EINVAL) => {
+            Ok(None)
+        }
+        Err(e) => Err(read_error(&path, e)),
+    }
+}
+
+fn parsed<T: std::str::FromStr>(value: Option<String>) -> Option<T> {
+    value?.parse().ok()
+}
+
+/// A hardware address of sysfs: hexadecimal bytes separated by colons, or nothing for
+/// the links without one.
+fn hw_address(value: Option<String>) -> Option<Vec<u8>> {
+    let value = value.filter(|value| !value.is_empty())?;
+
+    value
+        .split(':')
+        .map(|byte| u8::from_str_radix(byte, 16).ok())
+        .collect()
+}
+
+/// The flags of a link as the kernel reports them: the ones of sysfs are only the flags
+/// set on the link, without the ones telling its operational state.
+fn link_flags(
+    name: &str,
+    flags: u32,
+    operstate: Option<&str>,
+) -> Result<u32, Box<dyn std::error::Error>> {
+    let state_flags = (libc::IFF_RUNNING | libc::IFF_LOWER_UP | libc::IFF_DORMANT) as u32;
+    let mut flags = flags & !state_flags;
+
+    if flags & libc::IFF_UP as u32 != 0 {
+        if matches!(operstate, Some("up") | Some("unknown")) {
+            flags |= libc::IFF_RUNNING as u32;
+        }
+        if attribute(name, "carrier")?.as_deref() == Some("1") {
+            flags |= libc::IFF_LOWER_UP as u32;
+        }
+        if operstate == Some("dormant") {
+            flags |= libc::IFF_DORMANT as u32;
+        }
+    }
+
+    Ok(flags)
+}
+
+/// Read a link from sysfs; None if it is gone.
+fn read_link(name: &str) -> Result<Option<Link>, Box<dyn std::error::Error>> {
+    let index = match parsed(attribute(name, "ifindex")?) {
+        Some(index) => index,
+        None => return Ok(None),
+    };
+    let flags = attribute(name, "flags")?
+        .and_then(|flags| u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok())
+        .unwrap_or(0);
+    let operstate = attribute(name, "operstate")?;
+
+    Ok(Some(Link {
+        index,
+        flags: link_flags(name, flags, operstate.as_deref())?,
+        name: name.to_string(),
+        mtu: parsed(attribute(name, "mtu")?),
+        operstate: operstate
+            .and_then(|state| OPERSTATES.iter().position(|known| *known == state))
+            .map(|state| state as u8),
+        address: hw_address(attribute(name, "address")?),
+        broadcast: hw_address(attribute(name, "broadcast")?),
+        stats: None,
+        // sysfs does not tell the kind of the virtual links
+        kind: None,
+        master: parsed(attribute(name, "master/ifindex")?),
+        parent_device: None,
+        ifalias: attribute(name, "ifalias")?.filter(|alias| !alias.is_empty()),
+        altnames: Vec::new(),
+    }))
+}
+
+/// The names of the links of /proc/net/dev, which has two lines of headers, then a line
+/// per link: its name, a colon, and its counters.
+fn names() -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    Ok(read_file(DEV_FILE, false)?
+        .lines()
+        .skip(2)
+        .filter_map(|line| Some(line.split_once(':')?.0.trim().to_string()))
+        .collect())
+}
+
+/// Every link, sorted by index. The links removed while they are read are skipped.
+fn links() -> Result<Vec<Link>, Box<dyn std::error::Error>> {
+    // Without sysfs, every link would look removed
+    if !Path::new(SYSFS_NET).is_dir() {
+        return Err(Box::new(NetifacesError::Other(format!(
+            "Cannot read the interfaces, {SYSFS_NET} is missing"
+        ))));
+    }
+
+    let mut links = Vec::new();
+    for name in names()? {
+        links.extend(read_link(&name)?);
+    }
+    links.sort_by_key(|link| link.index);
+
+    Ok(links)
+}
+
+/// The indexes of the links, by name, as far as they can be read.
+pub fn link_indexes() -> HashMap<String, u32> {
+    names()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|name| {
+            let index = parsed(attribute(&name, "ifindex").ok()?)?;
+            Some((name, index))
+        })
+        .collect()
+}
+
+/// Look up a link by any name it can be displayed with, or by its index. A link named
+/// by its name is the only one read from sysfs.
+fn find_link(iface: &InterfaceRef) -> Result<Option<Link>, Box<dyn std::error::Error>> {
+    if let InterfaceRef::Name(name) = iface {
+        if names()?.contains(name) {
+            if let Some(link) = read_link(name)? {
+                return Ok(Some(link));
+            }
+        }
+    }
+
+    let links = links()?;
+    match iface {
+        InterfaceRef::Index(index) => Ok(links.into_iter().find(|link| link.index == *index)),
+        InterfaceRef::Name(name) => Ok(link_by_display_name(links.iter(), name)?.cloned()),
+    }
+}
+
+/// Parse a line of /proc/net/if_inet6: "address index prefix_len scope flags name", in
+/// hexadecimal. Only the 8 lower bits of the flags are listed.
+fn parse_if_inet6_line(line: &str) -> Option<Address> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+
+    Some(Address {
+        family: AF_INET6,
+        prefix_len: field(&fields, 2, hex_u8)?,
+        flags: field(&fields, 4, hex_u8)? as u32,
+        scope: match field(&fields, 3, hex_u8)? {
+            IFA_HOST => RT_SCOPE_HOST,
+            IFA_LINK => RT_SCOPE_LINK,
+            IFA_SITE => RT_SCOPE_SITE,
+            _ => RT_SCOPE_UNIVERSE,
+        },
+        index: field(&fields, 1, hex_u32)?,
+        address: field(&fields, 0, hex_ipv6)?.into(),
+        local: None,
+        broadcast: None,
+        label: None,
+    })
+}
+
+fn ipv6_addresses() -> Result<Vec<Address>, Box<dyn std::error::Error>> {
+    let content = read_file(IF_INET6_FILE, true)?;
+
+    Ok(parse_lines(
+        IF_INET6_FILE,
+        &content,
+        false,
+        parse_if_inet6_line,
+    )?)
+}
+
+/// The local and broadcast routes of /proc/net/fib_trie, which lists each prefix of the
+/// routing tables ("|-- 192.0.2.2"), followed by a line per route to it ("/32 host
+/// LOCAL"), in a tree. The other routes are skipped.
+fn fib_local_routes(content: &str) -> (BTreeSet<(Ipv4Addr, u8)>, BTreeSet<Ipv4Addr>) {
+    let mut locals = BTreeSet::new();
+    let mut broadcasts = BTreeSet::new();
+    let mut prefix = None;
+
+    for line in content.lines().map(str::trim) {
+        if let Some(ip) = line.strip_prefix("|-- ") {
+            prefix = ip.parse::<Ipv4Addr>().ok();
+        } else if let (Some(route), Some(ip)) = (line.strip_prefix('/'), prefix) {
+            let words: Vec<&str> = route.split_whitespace().collect();
+            let prefix_len = words.first().and_then(|len| len.parse().ok());
+
+            match (prefix_len, words.get(2).copied()) {
+                (Some(prefix_len), Some("LOCAL")) => {
+                    locals.insert((ip, prefix_len));
+                }
+                (Some(32), Some("BROADCAST")) => {
+                    broadcasts.insert(ip);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    (locals, broadcasts)
+}
+
+fn contains(network: IpAddr, prefix_len: u8, ip: IpAddr) -> bool {
+    let network = IpNetwork {
+        addr: network,
+        prefix_len,
+    };
+    let host = IpNetwork {
+        addr: ip,
+        prefix_len: 32,
+    };
+
+    prefix_len <= 32 && network.overlaps(&host)
+}
+
+/// The IPv4 addresses held by some of the links. An address belongs to the link of the
+/// most specific subnet route containing it, whose prefix is the address's, or to the
+/// loopback link when the local table has a subnet of it, like 127.0.0.0/8. Its
+/// broadcast address is the one of its subnet, if the local table has it.
+fn ipv4_addresses(links: &[Link]) -> Result<Vec<Address>, Box<dyn std::error::Error>> {
+    let (locals, broadcasts) = fib_local_routes(&read_file(FIB_TRIE_FILE, true)?);
+    let subnets: Vec<_> = proc_routes(AF_INET)?
+        .into_iter()
+        .filter(|route| route.gateway.is_none() && route.flags & RTF_REJECT == 0)
+        .filter(|route| route.prefix_len < 32)
+        .collect();
+    let loopback = links
+        .iter()
+        .find(|link| link.flags & libc::IFF_LOOPBACK as u32 != 0);
+
+    let mut addresses = Vec::new();
+    for &(ip, _) in locals.iter().filter(|(_, prefix_len)| *prefix_len == 32) {
+        let subnet = subnets
+            .iter()
+            .filter(|route| contains(route.destination, route.prefix_len, ip.into()))
+            .max_by_key(|route| route.prefix_len);
+        let owner = match subnet {
+            Some(route) => links
+                .iter()
+                .find(|link| link.name == route.interface)
+                .map(|link| (link, route.prefix_len, RT_SCOPE_UNIVERSE)),
+            None => locals
+                .iter()
+                .find(|(network, prefix_len)| {
+                    *prefix_len < 32 && contains((*network).into(), *prefix_len, ip.into())
+                })
+                .and_then(|(_, prefix_len)| Some((loopback?, *prefix_len, RT_SCOPE_HOST))),
+        };
+        let (link, prefix_len, scope) = match owner {
+            Some(owner) => owner,
+            None => continue,
+        };
+
+        let host_mask = u32::MAX.checked_shr(prefix_len as u32).unwrap_or(0);
+        let broadcast = Ipv4Addr::from(u32::from(ip) | host_mask);
+
+        addresses.push(Address {
+            family: AF_INET,
+            prefix_len,
+            flags: 0,
+            scope,
+            index: link.index,
+            address: Some(ip.into()),
+            local: Some(ip.into()),
+            broadcast: (prefix_len < 31 && broadcasts.contains(&broadcast))
+                .then_some(broadcast.into()),
+            label: None,
+        });
+    }
+
+    Ok(addresses)
+}
+
+/// The addresses of a family (AF_UNSPEC for all of them) held by some of the links.
+fn addresses(links: &[Link], family: u8) -> Result<Vec<Address>, Box<dyn std::error::Error>> {
+    let mut addresses = Vec::new();
+
+    if family == AF_UNSPEC || family == AF_INET {
+        addresses.extend(ipv4_addresses(links)?);
+    }
+    if family == AF_UNSPEC || family == AF_INET6 {
+        addresses.extend(
+            ipv6_addresses()?
+                .into_iter()
+                .filter(|address| links.iter().any(|link| link.index == address.index)),
+        );
+    }
+
+    Ok(addresses)
+}
+
+pub fn procfs_interfaces(
+    display: InterfaceDisplay,
+    filter: &InterfaceFilter,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    Ok(procfs_interfaces_by_index(display, filter)?
+        .into_values()
+        .collect())
+}
+
+pub fn procfs_interfaces_by_index(
+    display: InterfaceDisplay,
+    filter: &InterfaceFilter,
+) -> Result<IfacesByIndex, Box<dyn std::error::Error>> {
+    filter.check_supported(true, false, true)?;
+
+    let links = links()?;
+    let addresses = match filter.has_address {
+        Some(AF_PACKET) | None => Vec::new(),
+        Some(family) => addresses(&links, family)?,
+    };
+
+    Ok(filter_links(links.iter(), &addresses, filter)?
+        .into_iter()
+        .map(|link| (link.index, display_name(link, display)))
+        .collect())
+}
+
+/// The link an interface reference designates, with its addresses.
+fn lookup_addresses(
+    iface: &InterfaceRef,
+) -> Result<(Link, Vec<Address>), Box<dyn std::error::Error>> {
+    match find_link(iface)? {
+        Some(link) => {
+            let addresses = addresses(std::slice::from_ref(&link), AF_UNSPEC)?;
+            Ok((link, addresses))
+        }
+        None => Err(Box::new(iface.not_found())),
+    }
+}
+
+pub fn procfs_ifaddresses(
+    iface: &InterfaceRef,
+) -> Result<api::InterfaceAddresses, Box<dyn std::error::Error>> {
+    let (link, addresses) = lookup_addresses(iface)?;
+
+    Ok(link_addresses(&link, addresses.iter()))
+}
+
+pub fn procfs_interface(
+    iface: &InterfaceRef,
+    display: InterfaceDisplay,
+) -> Result<api::Interface, Box<dyn std::error::Error>> {
+    let (link, addresses) = lookup_addresses(iface)?;
+
+    Ok(link_interface(&link, addresses.iter(), display))
+}
+
+pub fn procfs_snapshot(
+    display: InterfaceDisplay,
+) -> Result<Vec<api::Interface>, Box<dyn std::error::Error>> {
+    let links = links()?;
+    let addresses = addresses(&links, AF_UNSPEC)?;
+
+    Ok(links_snapshot(&links, &addresses, display))
+}
+
+pub fn procfs_interface_is_up(iface: &InterfaceRef) -> Result<bool, Box<dyn std::error::Error>> {
+    match find_link(iface)? {
+        Some(link) => Ok(link.is_running()),
+        None => Err(Box::new(iface.not_found())),
+    }
+}
+
+pub fn procfs_if_nametoindex(if_name: &str) -> Result<u32, Box<dyn std::error::Error>> {
+    let iface = InterfaceRef::Name(if_name.to_string());
+
+    match find_link(&iface)? {
+        Some(link) => Ok(link.index),
+        None => Err(Box::new(iface.not_found())),
+    }
+}
+
+pub fn procfs_if_indextoname(
+    if_index: u32,
+    display: InterfaceDisplay,
+) -> Result<String, Box<dyn std::error::Error>> {
+    match find_link(&InterfaceRef::Index(if_index))? {
+        Some(link) => Ok(display_name(&link, display)),
+        None => Err(Box::new(InterfaceRef::Index(if_index).not_found())),
+    }
+}
+
+/// Parse a line of /proc/net/arp: "address hardware-type flags hardware-address mask
+/// name". The flags are the ATF_* ones, in hexadecimal; the index is filled in later.
+fn parse_arp_line(line: &str) -> Option<(Neighbour, String)> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let flags = hex_u32(fields.get(2)?.trim_start_matches("0x"))?;
+
+    let neighbour = Neighbour {
+        family: AF_INET,
+        index: 0,
+        // The table only tells whether an entry is complete or permanent
+        state: match flags {
+            _ if flags & ATF_PERM != 0 => NUD_PERMANENT,
+            _ if flags & ATF_COM != 0 => NUD_REACHABLE,
+            _ => NUD_INCOMPLETE,
+        },
+        flags: match flags & ATF_PUBL {
+            0 => 0,
+            _ => NTF_PROXY,
+        },
+        destination: Some(fields.first()?.parse().ok()?),
+        lladdr: match flags & ATF_COM {
+            0 => None,
+            _ => Some(hw_address(fields.get(3).map(|lladdr| lladdr.to_string()))?),
+        },
+    };
+
+    Some((neighbour, fields.get(5)?.to_string()))
+}
+
+/// The entries of the ARP table, in the given family (AF_UNSPEC for all of them); the
+/// NDP one is not in /proc.
+pub fn procfs_neighbours(family: u8) -> Result<Vec<api::Neighbour>, Box<dyn std::error::Error>> {
+    if family != AF_UNSPEC && family != AF_INET {
+        return Ok(Vec::new());
+    }
+
+    let content = read_file(ARP_FILE, true)?;
+    let links = links()?;
+    let names = link_names(&links);
+
+    Ok(parse_lines(ARP_FILE, &content, true, parse_arp_line)?
+        .into_iter()
+        .map(|(mut entry, name)| {
+            entry.index = links
+                .iter()
+                .find(|link| link.name == name)
+                .map_or(0, |link| link.index);
+            neighbour(&entry, &names)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::procfs::parse_lines;
+
+    const IF_INET6: &str = "\
+fe8000000000000000fc00fffe000001 04 40 20 80     eth0
+fd110000000000000000000000000001 08 40 00 82       va
+00000000000000000000000000000001 01 80 10 80       lo
+";
+
+    #[test]
+    fn parse_if_inet6() {
+        let addresses = parse_lines(IF_INET6_FILE, IF_INET6, false, parse_if_inet6_line).unwrap();
+
+        let summary: Vec<_> = addresses
+            .iter()
+            .map(|address| {
+                (
+                    address.local_ip().unwrap().to_string(),
+                    address.index,
+                    address.prefix_len,
+                    address.scope,
+                    address.flags,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("fe80::fc:ff:fe00:1".to_string(), 4, 64, RT_SCOPE_LINK, 0x80),
+                ("fd11::1".to_string(), 8, 64, RT_SCOPE_UNIVERSE, 0x82),
+                ("::1".to_string(), 1, 128, RT_SCOPE_HOST, 0x80),
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_truncated_if_inet6_lines() {
+        assert!(parse_if_inet6_line("fe8000000000000000fc00fffe000001 04 40 20").is_none());
+        assert!(parse_if_inet6_line("fe8000000000000000fc00fffe0000 04 40 20 80 eth0").is_none());
+    }
+}
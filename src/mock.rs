@@ -0,0 +1,716 @@
+//! A synthetic host, for the tests of the programs using netifaces.
+//!
+//! The host is described from Python (see `netifaces.testing`) and turned into the
+//! typed values every backend answers with, normalized the way the kernel reports a
+//! real host, e.g. with the flags, MTU and broadcast addresses it would give. They are
+//! then formatted by the same code as the answers of the other backends, so they look
+//! exactly like the ones of a real host. Nothing of the system is read while a host is
+//! loaded, and the host is the same on every platform.
+
+use crate::api::{self, HardwareAddress, LinkAddress};
+use crate::common::{
+    parse_zoned_ip, InterfaceDisplay, InterfaceFacts, InterfaceFilter, InterfaceRef, IpNetwork,
+};
+use crate::types::{IfacesByIndex, AF_PACKET, AF_UNSPEC, RTN_UNICAST, RT_TABLE_MAIN};
+use crate::NetifacesError;
+use pyo3::FromPyObject;
+use std::cmp::Reverse;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::RwLock;
+
+// The values of Linux, which the host reports on every platform
+const IFF_UP: u32 = 0x1;
+const IFF_BROADCAST: u32 = 0x2;
+const IFF_LOOPBACK: u32 = 0x8;
+const IFF_POINTOPOINT: u32 = 0x10;
+const IFF_RUNNING: u32 = 0x40;
+const IFF_NOARP: u32 = 0x80;
+const IFF_MULTICAST: u32 = 0x1000;
+const IFF_LOWER_UP: u32 = 0x10000;
+const RTPROT_BOOT: u8 = 3;
+const RT_SCOPE_UNIVERSE: u8 = 0;
+const RT_SCOPE_LINK: u8 = 253;
+const IFA_F_PERMANENT: u32 = 0x80;
+const DEFAULT_MTU: u32 = 1500;
+const LOOPBACK_MTU: u32 = 65536;
+
+/// An address of an interface of the host, as normalized by `netifaces.testing`.
+#[derive(FromPyObject)]
+pub struct AddressDescription {
+    /// The address and its prefix length, e.g. "192.0.2.10/24"
+    #[pyo3(item)]
+    address: String,
+    #[pyo3(item)]
+    broadcast: Option<String>,
+    /// The other end of a point-to-point link
+    #[pyo3(item)]
+    peer: Option<String>,
+    /// The IFA_F_* flags; IFA_F_PERMANENT if None
+    #[pyo3(item)]
+    flags: Option<u32>,
+    /// The label of an IPv4 address, which makes it the address of an alias
+    #[pyo3(item)]
+    label: Option<String>,
+}
+
+/// An interface of the host.
+#[derive(FromPyObject)]
+pub struct InterfaceDescription {
+    #[pyo3(item)]
+    name: String,
+    #[pyo3(item)]
+    index: u32,
+    /// Whether the interface is up and running, when its flags are not given
+    #[pyo3(item)]
+    up: bool,
+    #[pyo3(item)]
+    loopback: bool,
+    /// The IFF_* flags; made from `up`, `loopback` and the addresses if None
+    #[pyo3(item)]
+    flags: Option<u32>,
+    #[pyo3(item)]
+    mtu: Option<u32>,
+    /// The hardware address, hexadecimal bytes separated by colons
+    #[pyo3(item)]
+    mac: Option<String>,
+    #[pyo3(item)]
+    description: Option<String>,
+    #[pyo3(item)]
+    kind: Option<String>,
+    /// The name of the interface this one is enslaved to
+    #[pyo3(item)]
+    master: Option<String>,
+    #[pyo3(item)]
+    physical: bool,
+    #[pyo3(item)]
+    addresses: Vec<AddressDescription>,
+}
+
+/// A path of a multipath route.
+#[derive(FromPyObject)]
+pub struct NextHopDescription {
+    #[pyo3(item)]
+    gateway: Option<String>,
+    #[pyo3(item)]
+    interface: Option<String>,
+    #[pyo3(item)]
+    weight: u32,
+}
+
+/// A route of the host.
+#[derive(FromPyObject)]
+pub struct RouteDescription {
+    /// The destination network, e.g. "0.0.0.0/0" for a default route
+    #[pyo3(item)]
+    destination: String,
+    #[pyo3(item)]
+    gateway: Option<String>,
+    #[pyo3(item)]
+    interface: Option<String>,
+    #[pyo3(item)]
+    source: Option<String>,
+    #[pyo3(item)]
+    metric: Option<u32>,
+    #[pyo3(item)]
+    table: u32,
+    /// The paths of a multipath route, which then has no gateway nor interface
+    #[pyo3(item)]
+    nexthops: Vec<NextHopDescription>,
+}
+
+/// A synthetic host.
+#[derive(FromPyObject)]
+pub struct HostDescription {
+    #[pyo3(item)]
+    interfaces: Vec<InterfaceDescription>,
+    #[pyo3(item)]
+    routes: Vec<RouteDescription>,
+}
+
+/// An address of an interface of the host.
+struct HostAddress {
+    address: api::Address,
+    /// The label of an IPv4 address: the name of its interface, or of an alias
+    label: Option<String>,
+}
+
+/// An interface of the host.
+struct HostInterface {
+    name: String,
+    index: u32,
+    flags: u32,
+    mtu: u32,
+    link_address: Option<LinkAddress>,
+    description: Option<String>,
+    kind: Option<String>,
+    master: Option<String>,
+    physical: bool,
+    addresses: Vec<HostAddress>,
+}
+
+/// The host, sorted by index.
+struct Host {
+    interfaces: Vec<HostInterface>,
+    routes: Vec<api::Route>,
+}
+
+/// The loaded host; None when the real one is used.
+static HOST: RwLock<Option<Host>> = RwLock::new(None);
+
+fn invalid(what: &str, value: &str) -> NetifacesError {
+    NetifacesError::Other(format!("Invalid {what} {value}"))
+}
+
+fn parse_ip(value: &str) -> Result<IpAddr, NetifacesError> {
+    value.parse().map_err(|_| invalid("address", value))
+}
+
+fn parse_mac(value: &str) -> Result<Vec<u8>, NetifacesError> {
+    value
+        .split(':')
+        .map(|byte| u8::from_str_radix(byte, 16).map_err(|_| invalid("hardware address", value)))
+        .collect()
+}
+
+/// The flags of a link whose flags were not given: the ones of an Ethernet or loopback
+/// link, or of a point-to-point link if one of its addresses has a peer.
+fn default_flags(interface: &InterfaceDescription) -> u32 {
+    let point_to_point = interface
+        .addresses
+        .iter()
+        .any(|address| address.peer.is_some());
+
+    let mut flags = match (interface.loopback, point_to_point) {
+        (true, _) => IFF_LOOPBACK,
+        (false, true) => IFF_POINTOPOINT | IFF_NOARP | IFF_MULTICAST,
+        (false, false) => IFF_BROADCAST | IFF_MULTICAST,
+    };
+    if interface.up {
+        flags |= IFF_UP | IFF_RUNNING | IFF_LOWER_UP;
+    }
+
+    flags
+}
+
+/// Where the secondary address of a link or of one of its addresses goes: like
+/// `getifaddrs`, the peer of point-to-point links, the broadcast address of the others.
+fn set_secondary<T>(
+    flags: u32,
+    secondary: Option<T>,
+    broadcast: &mut Option<T>,
+    peer: &mut Option<T>,
+) {
+    if flags & IFF_POINTOPOINT != 0 {
+        *peer = secondary;
+    } else if flags & IFF_BROADCAST != 0 {
+        *broadcast = secondary;
+    }
+}
+
+fn link_address(flags: u32, mac: Option<&str>) -> Result<Option<LinkAddress>, NetifacesError> {
+    let address = match mac {
+        Some(mac) => parse_mac(mac)?,
+        None => return Ok(None),
+    };
+    // Ethernet links broadcast to ff:ff:ff:ff:ff:ff, the others report zeroes
+    let broadcast_byte = match flags & IFF_BROADCAST {
+        0 => 0x00,
+        _ => 0xff,
+    };
+    let secondary = HardwareAddress(vec![broadcast_byte; address.len()]);
+
+    let mut link_address = LinkAddress {
+        address: HardwareAddress(address),
+        broadcast: None,
+        peer: None,
+    };
+    set_secondary(
+        flags,
+        Some(secondary),
+        &mut link_address.broadcast,
+        &mut link_address.peer,
+    );
+
+    Ok(Some(link_address))
+}
+
+/// An address as the kernel reports it. Its IFA_LOCAL holds the address of IPv4 and
+/// point-to-point addresses, and IFA_ADDRESS their peer, which stands for the broadcast
+/// address when none is given.
+fn address(
+    interface: &InterfaceDescription,
+    flags: u32,
+    description: &AddressDescription,
+) -> Result<HostAddress, NetifacesError> {
+    let network = IpNetwork::parse(&description.address)?;
+    let ip = network.addr;
+    let peer = description.peer.as_deref().map(parse_ip).transpose()?;
+    let local = match (ip, peer) {
+        (IpAddr::V6(_), None) => None,
+        _ => Some(ip),
+    };
+    let broadcast = description.broadcast.as_deref().map(parse_ip).transpose()?;
+
+    let mut address = api::Address {
+        flags: description.flags.unwrap_or(IFA_F_PERMANENT),
+        ..api::Address::new(ip, Some(network.prefix_len))
+    }
+    .on_interface(interface.index);
+    set_secondary(
+        flags,
+        broadcast.or(local.and(Some(peer.unwrap_or(ip)))),
+        &mut address.broadcast,
+        &mut address.peer,
+    );
+
+    Ok(HostAddress {
+        address,
+        label: match ip {
+            IpAddr::V4(_) => Some(
+                description
+                    .label
+                    .clone()
+                    .unwrap_or_else(|| interface.name.clone()),
+            ),
+            IpAddr::V6(_) => None,
+        },
+    })
+}
+
+fn interface(description: &InterfaceDescription) -> Result<HostInterface, NetifacesError> {
+    let flags = description
+        .flags
+        .unwrap_or_else(|| default_flags(description));
+
+    Ok(HostInterface {
+        name: description.name.clone(),
+        index: description.index,
+        flags,
+        mtu: description.mtu.unwrap_or(match description.loopback {
+            true => LOOPBACK_MTU,
+            false => DEFAULT_MTU,
+        }),
+        link_address: link_address(flags, description.mac.as_deref())?,
+        description: description.description.clone(),
+        kind: description.kind.clone(),
+        master: description.master.clone(),
+        physical: description.physical,
+        addresses: description
+            .addresses
+            .iter()
+            .map(|address_description| address(description, flags, address_description))
+            .collect::<Result<_, NetifacesError>>()?,
+    })
+}
+
+fn link_index(interfaces: &[HostInterface], name: &str) -> Result<u32, NetifacesError> {
+    interfaces
+        .iter()
+        .find(|interface| interface.name == name)
+        .map(|interface| interface.index)
+        .ok_or_else(|| NetifacesError::Other(format!("Unknown interface {name}")))
+}
+
+/// The path of a route going out through the named interface, if any.
+fn next_hop(
+    interfaces: &[HostInterface],
+    gateway: Option<&str>,
+    interface: Option<&str>,
+    weight: u32,
+) -> Result<api::NextHop, NetifacesError> {
+    Ok(api::NextHop {
+        gateway: gateway.map(parse_ip).transpose()?,
+        interface: interface.map(str::to_string),
+        index: interface
+            .map(|name| link_index(interfaces, name))
+            .transpose()?,
+        weight,
+        flags: 0,
+    })
+}
+
+fn route(
+    interfaces: &[HostInterface],
+    description: &RouteDescription,
+) -> Result<api::Route, NetifacesError> {
+    let mut destination = IpNetwork::parse(&description.destination)?;
+    if destination.prefix_len == 0 {
+        destination.addr = match destination.addr {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        };
+    }
+    let path = next_hop(
+        interfaces,
+        description.gateway.as_deref(),
+        description.interface.as_deref(),
+        1,
+    )?;
+
+    let nexthops = match description.nexthops.is_empty() {
+        true => vec![path.clone()],
+        false => description
+            .nexthops
+            .iter()
+            .map(|hop| {
+                next_hop(
+                    interfaces,
+                    hop.gateway.as_deref(),
+                    hop.interface.as_deref(),
+                    hop.weight,
+                )
+            })
+            .collect::<Result<_, NetifacesError>>()?,
+    };
+    let gateways = nexthops.iter().any(|hop| hop.gateway.is_some());
+
+    Ok(api::Route {
+        destination,
+        gateway: path.gateway,
+        interface: path.interface,
+        index: path.index,
+        source: description.source.as_deref().map(parse_ip).transpose()?,
+        metric: description.metric,
+        table: description.table,
+        protocol: RTPROT_BOOT,
+        scope: match gateways {
+            true => RT_SCOPE_UNIVERSE,
+            false => RT_SCOPE_LINK,
+        },
+        route_type: RTN_UNICAST,
+        nexthops,
+        nexthop_id: None,
+    })
+}
+
+/// Answer the queries of the mock backend with this host.
+pub fn load(description: &HostDescription) -> Result<(), NetifacesError> {
+    let mut interfaces = description
+        .interfaces
+        .iter()
+        .map(interface)
+        .collect::<Result<Vec<HostInterface>, NetifacesError>>()?;
+    for interface in &interfaces {
+        if let Some(master) = &interface.master {
+            link_index(&interfaces, master)?;
+        }
+    }
+
+    let routes = description
+        .routes
+        .iter()
+        .map(|description| route(&interfaces, description))
+        .collect::<Result<Vec<api::Route>, NetifacesError>>()?;
+
+    interfaces.sort_by_key(|interface| interface.index);
+
+    *HOST.write().unwrap() = Some(Host { interfaces, routes });
+
+    Ok(())
+}
+
+/// Go back to the real host.
+pub fn unload() {
+    *HOST.write().unwrap() = None;
+}
+
+/// Run a query on the loaded host.
+fn with_host<T>(
+    query: impl FnOnce(&Host) -> Result<T, Box<dyn std::error::Error>>,
+) -> Result<T, Box<dyn std::error::Error>> {
+    match HOST.read().unwrap().as_ref() {
+        Some(host) => query(host),
+        None => Err(Box::new(NetifacesError::Other(
+            "No host is loaded in the mock backend".to_string(),
+        ))),
+    }
+}
+
+impl HostInterface {
+    fn is_running(&self) -> bool {
+        self.flags & IFF_RUNNING != 0
+    }
+
+    fn display_name(&self, display: InterfaceDisplay) -> String {
+        match display {
+            InterfaceDisplay::HumanReadable => {
+                self.description.as_ref().unwrap_or(&self.name).clone()
+            }
+            InterfaceDisplay::MachineReadable | InterfaceDisplay::AltName => self.name.clone(),
+            InterfaceDisplay::Index => self.index.to_string(),
+        }
+    }
+
+    fn addresses(&self) -> impl Iterator<Item = &api::Address> {
+        self.addresses.iter().map(|address| &address.address)
+    }
+
+    fn snapshot(&self, display: InterfaceDisplay) -> api::Interface {
+        api::Interface {
+            name: self.display_name(display),
+            index: self.index,
+            flags: self.flags,
+            up: self.is_running(),
+            mtu: Some(self.mtu),
+            link_address: self.link_address.clone(),
+            addresses: self.addresses().cloned().collect(),
+        }
+    }
+}
+
+impl Host {
+    /// The interface a reference designates, like netlink looks it up: by name or
+    /// index, then by the names it is displayed with.
+    fn find(&self, iface: &InterfaceRef) -> Result<&HostInterface, NetifacesError> {
+        let exact = self.interfaces.iter().find(|interface| match iface {
+            InterfaceRef::Index(index) => interface.index == *index,
+            InterfaceRef::Name(name) => interface.name == *name,
+        });
+        let name = match (exact, iface) {
+            (Some(interface), _) => return Ok(interface),
+            (None, InterfaceRef::Name(name)) => name,
+            (None, InterfaceRef::Index(_)) => return Err(iface.not_found()),
+        };
+
+        if let Some(interface) = self
+            .interfaces
+            .iter()
+            .find(|interface| interface.index.to_string() == *name)
+        {
+            return Ok(interface);
+        }
+
+        let mut described = self
+            .interfaces
+            .iter()
+            .filter(|interface| interface.description.as_deref() == Some(name));
+        match (described.next(), described.next()) {
+            (Some(_), Some(_)) => Err(NetifacesError::AmbiguousInterface(name.to_string())),
+            (Some(interface), None) => Ok(interface),
+            (None, _) => Err(iface.not_found()),
+        }
+    }
+
+    /// The addresses of an interface, or of an IPv4 alias (e.g. `eth0:1`): the ones
+    /// bearing its label, without the link layer address.
+    fn ifaddresses(&self, iface: &InterfaceRef) -> Result<api::InterfaceAddresses, NetifacesError> {
+        let alias = match iface {
+            InterfaceRef::Name(name) => name
+                .split_once(':')
+                .filter(|_| {
+                    self.interfaces
+                        .iter()
+                        .all(|interface| interface.name != *name)
+                })
+                .and_then(|(base, _)| {
+                    self.interfaces
+                        .iter()
+                        .find(|interface| interface.name == base)
+                })
+                .map(|interface| (name, interface)),
+            InterfaceRef::Index(_) => None,
+        };
+
+        if let Some((name, interface)) = alias {
+            let addresses: Vec<api::Address> = interface
+                .addresses
+                .iter()
+                .filter(|address| address.label.as_ref() == Some(name))
+                .map(|address| address.address.clone())
+                .collect();
+
+            if !addresses.is_empty() {
+                return Ok(api::InterfaceAddresses {
+                    link_address: None,
+                    addresses,
+                });
+            }
+        }
+
+        let interface = self.find(iface)?;
+        Ok(api::InterfaceAddresses {
+            link_address: interface.link_address.clone(),
+            addresses: interface.addresses().cloned().collect(),
+        })
+    }
+}
+
+pub fn mock_interfaces(
+    display: InterfaceDisplay,
+    filter: &InterfaceFilter,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    Ok(mock_interfaces_by_index(display, filter)?
+        .into_values()
+        .collect())
+}
+
+pub fn mock_interfaces_by_index(
+    display: InterfaceDisplay,
+    filter: &InterfaceFilter,
+) -> Result<IfacesByIndex, Box<dyn std::error::Error>> {
+    let matcher = filter.matcher()?;
+
+    with_host(|host| {
+        Ok(host
+            .interfaces
+            .iter()
+            .filter(|interface| {
+                let mut families: Vec<u8> = interface
+                    .addresses()
+                    .map(|address| address.family())
+                    .collect();
+                if interface.link_address.is_some() {
+                    families.push(AF_PACKET);
+                }
+
+                matcher.matches(&InterfaceFacts {
+                    name: &interface.name,
+                    up: interface.is_running(),
+                    loopback: interface.flags & IFF_LOOPBACK != 0,
+                    physical: interface.physical,
+                    kind: interface.kind.as_deref(),
+                    master: interface.master.as_deref(),
+                    families: &families,
+                })
+            })
+            .map(|interface| (interface.index, interface.display_name(display)))
+            .collect())
+    })
+}
+
+pub fn mock_if_nametoindex(if_name: &str) -> Result<u32, Box<dyn std::error::Error>> {
+    with_host(|host| Ok(host.find(&InterfaceRef::Name(if_name.to_string()))?.index))
+}
+
+pub fn mock_if_indextoname(
+    if_index: u32,
+    display: InterfaceDisplay,
+) -> Result<String, Box<dyn std::error::Error>> {
+    with_host(|host| {
+        Ok(host
+            .find(&InterfaceRef::Index(if_index))?
+            .display_name(display))
+    })
+}
+
+pub fn mock_ifaddresses(
+    iface: &InterfaceRef,
+) -> Result<api::InterfaceAddresses, Box<dyn std::error::Error>> {
+    with_host(|host| Ok(host.ifaddresses(iface)?))
+}
+
+pub fn mock_snapshot(
+    display: InterfaceDisplay,
+) -> Result<Vec<api::Interface>, Box<dyn std::error::Error>> {
+    with_host(|host| {
+        Ok(host
+            .interfaces
+            .iter()
+            .map(|interface| interface.snapshot(display))
+            .collect())
+    })
+}
+
+pub fn mock_interface(
+    iface: &InterfaceRef,
+    display: InterfaceDisplay,
+) -> Result<api::Interface, Box<dyn std::error::Error>> {
+    with_host(|host| Ok(host.find(iface)?.snapshot(display)))
+}
+
+pub fn mock_interface_is_up(iface: &InterfaceRef) -> Result<bool, Box<dyn std::error::Error>> {
+    with_host(|host| Ok(host.find(iface)?.is_running()))
+}
+
+pub fn mock_routes(family: u8) -> Result<Vec<api::Route>, Box<dyn std::error::Error>> {
+    with_host(|host| {
+        Ok(host
+            .routes
+            .iter()
+            .filter(|route| family == AF_UNSPEC || route.family() == family)
+            .cloned()
+            .collect())
+    })
+}
+
+/// The route the host would use for a packet to `destination`: the most specific
+/// route of the main table holding it, then the one with the lowest metric, reported
+/// with its first path through a running interface. The host has no routing rules, so
+/// the firewall mark changes nothing.
+pub fn mock_route_get(
+    destination: &str,
+    source: Option<&str>,
+    oif: Option<&InterfaceRef>,
+    mark: Option<u32>,
+) -> Result<Option<api::Route>, Box<dyn std::error::Error>> {
+    let _ = mark;
+
+    with_host(|host| {
+        let (destination, zone) = parse_zoned_ip(destination)?;
+        let source = source.map(parse_ip).transpose()?;
+        if source.is_some_and(|source| api::family_of(&source) != api::family_of(&destination)) {
+            return Err(Box::new(NetifacesError::Other(
+                "The source and destination addresses are not of the same family".to_string(),
+            )));
+        }
+        let oif = match (oif, zone) {
+            (Some(iface), _) => Some(host.find(iface)?.index),
+            (None, Some(zone)) => Some(host.find(&InterfaceRef::Name(zone.to_string()))?.index),
+            (None, None) => None,
+        };
+
+        let target = IpNetwork {
+            addr: destination,
+            prefix_len: match destination {
+                IpAddr::V4(_) => 32,
+                IpAddr::V6(_) => 128,
+            },
+        };
+        let mut routes: Vec<&api::Route> = host
+            .routes
+            .iter()
+            .filter(|route| route.table == RT_TABLE_MAIN && route.route_type == RTN_UNICAST)
+            .filter(|route| route.destination.overlaps(&target))
+            .collect();
+        // IPv4 routes without a metric have a metric of 0
+        routes.sort_by_key(|route| {
+            (
+                Reverse(route.destination.prefix_len),
+                route.metric.unwrap_or(0),
+            )
+        });
+
+        for route in routes {
+            for hop in route.nexthops.iter().filter(|hop| hop.is_alive()) {
+                let interface = match hop.index {
+                    Some(index) if oif.is_none_or(|oif| oif == index) => {
+                        host.find(&InterfaceRef::Index(index))?
+                    }
+                    _ => continue,
+                };
+                if !interface.is_running() {
+                    continue;
+                }
+
+                let addresses: Vec<api::Address> = interface
+                    .addresses()
+                    .filter(|address| address.family() == api::family_of(&destination))
+                    .cloned()
+                    .collect();
+                let source = source
+                    .or(route.source)
+                    .or_else(|| api::source_for(&addresses, hop.gateway.or(Some(destination))));
+
+                return Ok(Some(api::Route {
+                    gateway: hop.gateway,
+                    interface: hop.interface.clone(),
+                    index: hop.index,
+                    source,
+                    ..route.clone()
+                }));
+            }
+        }
+
+        Ok(None)
+    })
+}
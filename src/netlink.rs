@@ -0,0 +1,1480 @@
+//! A small rtnetlink client.
+//!
+//! On Linux, the kernel exposes links, addresses and routes over a NETLINK_ROUTE socket.
+//! This module only implements what netifaces needs: dumping those objects, decoding
+//! them into plain structs and listening for change notifications.
+
+use crate::common::InterfaceRef;
+#[cfg(any(feature = "python", test))]
+use crate::types::{RTNH_F_DEAD, RTNH_F_LINKDOWN};
+pub use crate::types::{RTN_UNICAST, RT_TABLE_MAIN};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+#[cfg(feature = "python")]
+use std::time::Duration;
+
+// Message types
+pub const NLMSG_NOOP: u16 = 1;
+pub const NLMSG_ERROR: u16 = 2;
+pub const NLMSG_DONE: u16 = 3;
+pub const RTM_NEWLINK: u16 = 16;
+#[cfg(feature = "python")]
+pub const RTM_DELLINK: u16 = 17;
+pub const RTM_GETLINK: u16 = 18;
+pub const RTM_NEWADDR: u16 = 20;
+#[cfg(feature = "python")]
+pub const RTM_DELADDR: u16 = 21;
+pub const RTM_GETADDR: u16 = 22;
+pub const RTM_NEWROUTE: u16 = 24;
+#[cfg(feature = "python")]
+pub const RTM_DELROUTE: u16 = 25;
+pub const RTM_GETROUTE: u16 = 26;
+pub const RTM_NEWNEIGH: u16 = 28;
+pub const RTM_GETNEIGH: u16 = 30;
+pub const RTM_NEWNEXTHOP: u16 = 104;
+#[cfg(feature = "python")]
+pub const RTM_DELNEXTHOP: u16 = 105;
+pub const RTM_GETNEXTHOP: u16 = 106;
+
+// Message flags
+pub const NLM_F_REQUEST: u16 = 0x01;
+pub const NLM_F_ACK: u16 = 0x04;
+pub const NLM_F_DUMP_INTR: u16 = 0x10;
+pub const NLM_F_DUMP: u16 = 0x300;
+
+// Multicast groups
+#[cfg(feature = "python")]
+pub const RTNLGRP_LINK: u32 = 1;
+#[cfg(feature = "python")]
+pub const RTNLGRP_IPV4_IFADDR: u32 = 5;
+#[cfg(feature = "python")]
+pub const RTNLGRP_IPV4_ROUTE: u32 = 7;
+#[cfg(feature = "python")]
+pub const RTNLGRP_IPV6_IFADDR: u32 = 9;
+#[cfg(feature = "python")]
+pub const RTNLGRP_IPV6_ROUTE: u32 = 11;
+#[cfg(feature = "python")]
+pub const RTNLGRP_NEXTHOP: u32 = 32;
+
+// Link attributes
+pub const IFLA_ADDRESS: u16 = 1;
+pub const IFLA_BROADCAST: u16 = 2;
+pub const IFLA_IFNAME: u16 = 3;
+pub const IFLA_MTU: u16 = 4;
+pub const IFLA_MASTER: u16 = 10;
+pub const IFLA_OPERSTATE: u16 = 16;
+pub const IFLA_LINKINFO: u16 = 18;
+pub const IFLA_IFALIAS: u16 = 20;
+pub const IFLA_STATS64: u16 = 23;
+pub const IFLA_PROP_LIST: u16 = 52;
+pub const IFLA_ALT_IFNAME: u16 = 53;
+pub const IFLA_PARENT_DEV_NAME: u16 = 56;
+pub const IFLA_INFO_KIND: u16 = 1;
+
+/// The size of the buffer holding an alternative name, including its final NUL
+const ALTIFNAMSIZ: usize = 128;
+
+// Address attributes and flags
+pub const IFA_ADDRESS: u16 = 1;
+pub const IFA_LOCAL: u16 = 2;
+pub const IFA_LABEL: u16 = 3;
+pub const IFA_BROADCAST: u16 = 4;
+pub const IFA_FLAGS: u16 = 8;
+#[cfg(any(feature = "python", test))]
+pub const IFA_F_DADFAILED: u32 = 0x08;
+#[cfg(any(feature = "python", test))]
+pub const IFA_F_TENTATIVE: u32 = 0x40;
+
+// Route attributes and values
+pub const RTA_DST: u16 = 1;
+pub const RTA_SRC: u16 = 2;
+pub const RTA_OIF: u16 = 4;
+pub const RTA_GATEWAY: u16 = 5;
+pub const RTA_PRIORITY: u16 = 6;
+pub const RTA_PREFSRC: u16 = 7;
+pub const RTA_MULTIPATH: u16 = 9;
+pub const RTA_TABLE: u16 = 15;
+pub const RTA_MARK: u16 = 16;
+pub const RTA_VIA: u16 = 18;
+pub const RTA_NH_ID: u16 = 30;
+pub const RTM_F_LOOKUP_TABLE: u32 = 0x1000;
+pub const RTM_F_FIB_MATCH: u32 = 0x2000;
+pub const RT_SCOPE_UNIVERSE: u8 = 0;
+pub const RT_SCOPE_SITE: u8 = 200;
+pub const RT_SCOPE_LINK: u8 = 253;
+pub const RT_SCOPE_HOST: u8 = 254;
+pub const RT_TABLE_LOCAL: u32 = 255;
+
+// Nexthop object attributes
+pub const NHA_ID: u16 = 1;
+pub const NHA_GROUP: u16 = 2;
+pub const NHA_BLACKHOLE: u16 = 4;
+pub const NHA_OIF: u16 = 5;
+pub const NHA_GATEWAY: u16 = 6;
+
+// Neighbour attributes
+pub const NDA_DST: u16 = 1;
+pub const NDA_LLADDR: u16 = 2;
+pub const NUD_INCOMPLETE: u16 = 0x01;
+pub const NUD_REACHABLE: u16 = 0x02;
+pub const NUD_PERMANENT: u16 = 0x80;
+pub const NTF_PROXY: u8 = 0x08;
+
+const NLMSG_HDRLEN: usize = 16;
+const NLA_HDRLEN: usize = 4;
+const NLA_TYPE_MASK: u16 = 0x3fff;
+const IFINFOMSG_LEN: usize = 16;
+const IFADDRMSG_LEN: usize = 8;
+const RTMSG_LEN: usize = 12;
+const NDMSG_LEN: usize = 12;
+const NHMSG_LEN: usize = 8;
+const RTNEXTHOP_LEN: usize = 8;
+const NEXTHOP_GRP_LEN: usize = 8;
+
+/// The initial size of the receive buffer, which holds any datagram of a dump. It grows
+/// if a larger one comes.
+const RECV_BUF_LEN: usize = 64 * 1024;
+
+/// How many times a dump is attempted before giving up on getting a consistent one.
+const DUMP_ATTEMPTS: usize = 5;
+
+/// Socket option asking the kernel to validate dump requests strictly, and to honour
+/// the filters they carry (Linux 4.20 and later).
+const NETLINK_GET_STRICT_CHK: libc::c_int = 12;
+
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let slice = bytes.get(offset..offset + 2)?;
+    Some(u16::from_ne_bytes(slice.try_into().ok()?))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let slice = bytes.get(offset..offset + 4)?;
+    Some(u32::from_ne_bytes(slice.try_into().ok()?))
+}
+
+fn read_i32(bytes: &[u8], offset: usize) -> Option<i32> {
+    read_u32(bytes, offset).map(|value| value as i32)
+}
+
+/// Decode an attribute holding a NUL terminated string.
+fn read_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Decode an RTA_VIA attribute, a gateway whose family differs from the route's.
+fn read_via(bytes: &[u8]) -> Option<IpAddr> {
+    read_ip(bytes.get(2..)?)
+}
+
+/// Encode an IPv4 or IPv6 address as an attribute payload.
+fn ip_bytes(ip: &IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets().to_vec(),
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+    }
+}
+
+/// Decode an attribute holding an IPv4 or IPv6 address.
+fn read_ip(bytes: &[u8]) -> Option<IpAddr> {
+    match bytes.len() {
+        4 => Some(IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?))),
+        16 => Some(IpAddr::V6(Ipv6Addr::from(
+            <[u8; 16]>::try_from(bytes).ok()?,
+        ))),
+        _ => None,
+    }
+}
+
+/// Iterator over the `rtattr`/`nlattr` TLVs of a message payload.
+pub struct Attributes<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Attributes<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Attributes { buf }
+    }
+}
+
+impl<'a> Iterator for Attributes<'a> {
+    type Item = (u16, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = read_u16(self.buf, 0)? as usize;
+        let kind = read_u16(self.buf, 2)? & NLA_TYPE_MASK;
+
+        if len < NLA_HDRLEN || len > self.buf.len() {
+            return None;
+        }
+
+        let payload = &self.buf[NLA_HDRLEN..len];
+        self.buf = &self.buf[align(len).min(self.buf.len())..];
+
+        Some((kind, payload))
+    }
+}
+
+/// Serialize a single attribute, padding included, at the end of `buf`.
+pub fn push_attribute(buf: &mut Vec<u8>, kind: u16, payload: &[u8]) {
+    let len = NLA_HDRLEN + payload.len();
+    buf.extend_from_slice(&(len as u16).to_ne_bytes());
+    buf.extend_from_slice(&kind.to_ne_bytes());
+    buf.extend_from_slice(payload);
+    buf.resize(align(buf.len()), 0);
+}
+
+/// A raw netlink message, as returned by the kernel.
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub kind: u16,
+    pub payload: Vec<u8>,
+}
+
+/// Iterator over the netlink messages packed into one datagram.
+struct Messages<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Iterator for Messages<'a> {
+    /// (type, flags, sequence number, payload)
+    type Item = (u16, u16, u32, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = read_u32(self.buf, 0)? as usize;
+        let kind = read_u16(self.buf, 4)?;
+        let flags = read_u16(self.buf, 6)?;
+        let seq = read_u32(self.buf, 8)?;
+
+        if len < NLMSG_HDRLEN || len > self.buf.len() {
+            return None;
+        }
+
+        let payload = &self.buf[NLMSG_HDRLEN..len];
+        self.buf = &self.buf[align(len).min(self.buf.len())..];
+
+        Some((kind, flags, seq, payload))
+    }
+}
+
+/// A NETLINK_ROUTE socket.
+pub struct NetlinkSocket {
+    fd: OwnedFd,
+    seq: u32,
+    buf: Vec<u8>,
+    /// Whether the kernel filters dumps for us
+    strict: bool,
+}
+
+impl NetlinkSocket {
+    pub fn new() -> io::Result<Self> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_ROUTE,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        unsafe {
+            let mut addr: libc::sockaddr_nl = std::mem::zeroed();
+            addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+
+            let ret = libc::bind(
+                fd.as_raw_fd(),
+                &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            );
+            if ret < 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        // Older kernels do not know the option; dumps are then filtered here instead
+        let enable: libc::c_int = 1;
+        let strict = unsafe {
+            libc::setsockopt(
+                fd.as_raw_fd(),
+                libc::SOL_NETLINK,
+                NETLINK_GET_STRICT_CHK,
+                &enable as *const libc::c_int as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            ) == 0
+        };
+
+        Ok(NetlinkSocket {
+            fd,
+            seq: 0,
+            buf: vec![0; RECV_BUF_LEN],
+            strict,
+        })
+    }
+
+    /// Join the given RTNLGRP_* multicast groups, after which the kernel
+    /// sends a notification on this socket for every change in them.
+    #[cfg(feature = "python")]
+    pub fn subscribe(&self, groups: &[u32]) -> io::Result<()> {
+        for group in groups {
+            let ret = unsafe {
+                libc::setsockopt(
+                    self.fd.as_raw_fd(),
+                    libc::SOL_NETLINK,
+                    libc::NETLINK_ADD_MEMBERSHIP,
+                    group as *const u32 as *const libc::c_void,
+                    std::mem::size_of::<u32>() as libc::socklen_t,
+                )
+            };
+            if ret < 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn send(&mut self, kind: u16, flags: u16, payload: &[u8]) -> io::Result<u32> {
+        self.seq = self.seq.wrapping_add(1);
+
+        let len = NLMSG_HDRLEN + payload.len();
+        let mut msg = Vec::with_capacity(len);
+        msg.extend_from_slice(&(len as u32).to_ne_bytes());
+        msg.extend_from_slice(&kind.to_ne_bytes());
+        msg.extend_from_slice(&(flags | NLM_F_REQUEST).to_ne_bytes());
+        msg.extend_from_slice(&self.seq.to_ne_bytes());
+        msg.extend_from_slice(&0_u32.to_ne_bytes());
+        msg.extend_from_slice(payload);
+
+        let ret = unsafe {
+            let mut kernel: libc::sockaddr_nl = std::mem::zeroed();
+            kernel.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+
+            libc::sendto(
+                self.fd.as_raw_fd(),
+                msg.as_ptr() as *const libc::c_void,
+                msg.len(),
+                0,
+                &kernel as *const libc::sockaddr_nl as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(self.seq)
+    }
+
+    /// Receive the next datagram into the buffer, and return its length.
+    ///
+    /// The datagram is peeked at first: with MSG_TRUNC, the kernel reports its full
+    /// length, and the buffer grows if it is too small to hold it whole, rather than
+    /// losing the end of it.
+    fn recv(&mut self, flags: libc::c_int) -> io::Result<usize> {
+        let len = self.recv_into_buf(flags | libc::MSG_PEEK | libc::MSG_TRUNC)?;
+        if len > self.buf.len() {
+            self.buf.resize(len, 0);
+        }
+
+        let len = self.recv_into_buf(flags | libc::MSG_TRUNC)?;
+        match len > self.buf.len() {
+            true => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "truncated netlink datagram",
+            )),
+            false => Ok(len),
+        }
+    }
+
+    fn recv_into_buf(&mut self, flags: libc::c_int) -> io::Result<usize> {
+        loop {
+            let ret = unsafe {
+                libc::recv(
+                    self.fd.as_raw_fd(),
+                    self.buf.as_mut_ptr() as *mut libc::c_void,
+                    self.buf.len(),
+                    flags,
+                )
+            };
+            if ret >= 0 {
+                return Ok(ret as usize);
+            }
+
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+
+    /// Send a request to the kernel and collect its replies.
+    ///
+    /// Dump requests (`NLM_F_DUMP`) return every object the kernel reported; other
+    /// requests are acknowledged, and return the reply if the request has one.
+    ///
+    /// If the objects changed while they were being dumped, the kernel flags the dump
+    /// as inconsistent and it is started over, so the result is always one coherent view.
+    pub fn request(&mut self, kind: u16, flags: u16, payload: &[u8]) -> io::Result<Vec<Message>> {
+        for _ in 0..DUMP_ATTEMPTS {
+            let (replies, consistent) = self.request_once(kind, flags, payload)?;
+            if consistent {
+                return Ok(replies);
+            }
+        }
+
+        Err(io::Error::other(
+            "netlink dump kept being interrupted by concurrent changes",
+        ))
+    }
+
+    fn request_once(
+        &mut self,
+        kind: u16,
+        flags: u16,
+        payload: &[u8],
+    ) -> io::Result<(Vec<Message>, bool)> {
+        let flags = if flags & NLM_F_DUMP == 0 {
+            flags | NLM_F_ACK
+        } else {
+            flags
+        };
+        let seq = self.send(kind, flags, payload)?;
+        let mut replies = Vec::new();
+        let mut consistent = true;
+
+        loop {
+            let len = self.recv(0)?;
+
+            for (kind, flags, msg_seq, payload) in (Messages {
+                buf: &self.buf[..len],
+            }) {
+                if msg_seq != seq {
+                    continue;
+                }
+
+                consistent &= flags & NLM_F_DUMP_INTR == 0;
+
+                match kind {
+                    NLMSG_NOOP => continue,
+                    NLMSG_DONE => return Ok((replies, consistent)),
+                    NLMSG_ERROR => {
+                        return match read_i32(payload, 0) {
+                            Some(0) => Ok((replies, consistent)),
+                            Some(code) => Err(io::Error::from_raw_os_error(-code)),
+                            None => Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                "truncated netlink error message",
+                            )),
+                        };
+                    }
+                    _ => replies.push(Message {
+                        kind,
+                        payload: payload.to_vec(),
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Wait until a message is available on the socket.
+    ///
+    /// Returns false if the timeout expired, or if a signal interrupted the wait.
+    #[cfg(feature = "python")]
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        let mut pollfd = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout_ms = match timeout {
+            // Round up so that a short timeout does not turn into a busy loop
+            Some(t) => t
+                .as_nanos()
+                .div_ceil(1_000_000)
+                .min(libc::c_int::MAX as u128) as libc::c_int,
+            None => -1,
+        };
+
+        let ret = unsafe { libc::poll(&mut pollfd, 1, timeout_ms) };
+        if ret < 0 {
+            let err = io::Error::last_os_error();
+            return match err.kind() {
+                io::ErrorKind::Interrupted => Ok(false),
+                _ => Err(err),
+            };
+        }
+
+        Ok(ret > 0)
+    }
+
+    /// Read every message currently queued on the socket, without blocking.
+    ///
+    /// Fails with ENOBUFS if the kernel had to drop notifications because they were
+    /// not read fast enough.
+    #[cfg(feature = "python")]
+    pub fn receive(&mut self) -> io::Result<Vec<Message>> {
+        let mut messages = Vec::new();
+
+        loop {
+            let len = match self.recv(libc::MSG_DONTWAIT) {
+                Ok(len) => len,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(messages),
+                Err(e) => return Err(e),
+            };
+
+            for (kind, _, _, payload) in (Messages {
+                buf: &self.buf[..len],
+            }) {
+                messages.push(Message {
+                    kind,
+                    payload: payload.to_vec(),
+                });
+            }
+        }
+    }
+
+    /// Read and discard every message currently queued on the socket.
+    #[cfg(feature = "python")]
+    pub fn drain(&mut self) -> io::Result<()> {
+        loop {
+            match self.recv(libc::MSG_DONTWAIT) {
+                Ok(_) => continue,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                // The kernel dropped notifications because we were not reading fast
+                // enough. Since the queue is discarded anyway, this does not matter.
+                Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Dump all the links (network interfaces) of the system.
+    pub fn links(&mut self) -> io::Result<Vec<Link>> {
+        let header = [0_u8; IFINFOMSG_LEN];
+        let replies = self.request(RTM_GETLINK, NLM_F_DUMP, &header)?;
+
+        Ok(replies
+            .iter()
+            .filter(|msg| msg.kind == RTM_NEWLINK)
+            .filter_map(|msg| Link::parse(&msg.payload))
+            .collect())
+    }
+
+    /// Get a single link by its name, if it exists. The kernel also looks the name
+    /// up among the alternative names of the links.
+    pub fn link_by_name(&mut self, name: &str) -> io::Result<Option<Link>> {
+        // The kernel would reject these as invalid rather than report them missing
+        if name.is_empty() || name.len() >= ALTIFNAMSIZ {
+            return Ok(None);
+        }
+
+        let mut payload = vec![0_u8; IFINFOMSG_LEN];
+        let mut c_name = name.as_bytes().to_vec();
+        c_name.push(0);
+        // Only alternative names can be that long, and IFLA_IFNAME would be rejected
+        match name.len() < libc::IFNAMSIZ {
+            true => push_attribute(&mut payload, IFLA_IFNAME, &c_name),
+            false => push_attribute(&mut payload, IFLA_ALT_IFNAME, &c_name),
+        }
+
+        self.get_link(&payload)
+    }
+
+    /// Get a single link by its index, if it exists.
+    pub fn link_by_index(&mut self, index: u32) -> io::Result<Option<Link>> {
+        // ifi_index is a signed int, 0 would be rejected as invalid
+        if index == 0 || index > i32::MAX as u32 {
+            return Ok(None);
+        }
+
+        let mut payload = vec![0_u8; IFINFOMSG_LEN];
+        payload[4..8].copy_from_slice(&index.to_ne_bytes());
+
+        self.get_link(&payload)
+    }
+
+    /// Get a single link, by name or by index.
+    pub fn link(&mut self, iface: &InterfaceRef) -> io::Result<Option<Link>> {
+        match iface {
+            InterfaceRef::Name(name) => self.link_by_name(name),
+            InterfaceRef::Index(index) => self.link_by_index(*index),
+        }
+    }
+
+    fn get_link(&mut self, payload: &[u8]) -> io::Result<Option<Link>> {
+        match self.request(RTM_GETLINK, 0, payload) {
+            Ok(replies) => Ok(replies
+                .iter()
+                .filter(|msg| msg.kind == RTM_NEWLINK)
+                .find_map(|msg| Link::parse(&msg.payload))),
+            Err(e) if e.raw_os_error() == Some(libc::ENODEV) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Dump all the addresses of the given family (AF_UNSPEC for all of them).
+    pub fn addresses(&mut self, family: u8) -> io::Result<Vec<Address>> {
+        self.dump_addresses(family, 0)
+    }
+
+    /// Dump the addresses of the given family that are assigned to one link.
+    pub fn addresses_on(&mut self, index: u32, family: u8) -> io::Result<Vec<Address>> {
+        let mut addresses = self.dump_addresses(family, index)?;
+        addresses.retain(|address| address.index == index);
+        Ok(addresses)
+    }
+
+    /// With strict checking, the kernel only dumps the addresses of the link whose
+    /// index is in the header; 0 means all of them.
+    fn dump_addresses(&mut self, family: u8, index: u32) -> io::Result<Vec<Address>> {
+        let mut header = [0_u8; IFADDRMSG_LEN];
+        header[0] = family;
+        if self.strict {
+            header[4..8].copy_from_slice(&index.to_ne_bytes());
+        }
+        let replies = self.request(RTM_GETADDR, NLM_F_DUMP, &header)?;
+
+        Ok(replies
+            .iter()
+            .filter(|msg| msg.kind == RTM_NEWADDR)
+            .filter_map(|msg| Address::parse(&msg.payload))
+            .collect())
+    }
+
+    /// Dump all the routes of the given family (AF_UNSPEC for all of them), from every table.
+    pub fn routes(&mut self, family: u8) -> io::Result<Vec<Route>> {
+        let mut routes = self.dump_routes(family, None)?;
+        self.resolve_nexthops(&mut routes)?;
+        Ok(routes)
+    }
+
+    /// Dump the routes of the given family, from every table, that go out through one link,
+    /// through any of their nexthops.
+    pub fn routes_via(&mut self, oif: u32, family: u8) -> io::Result<Vec<Route>> {
+        let mut routes = self.dump_routes(family, Some(oif))?;
+        self.resolve_nexthops(&mut routes)?;
+        routes.retain(|route| route.nexthops.iter().any(|hop| hop.oif == Some(oif)));
+        Ok(routes)
+    }
+
+    /// Replace the nexthops of the routes using a nexthop object with the ones of that
+    /// object, or of the members of that group.
+    ///
+    /// The kernel also describes them with RTA_OIF and RTA_MULTIPATH, unless the
+    /// net.ipv4.nexthop_compat_mode sysctl is off, but the weights are only found in
+    /// the objects.
+    pub fn resolve_nexthops(&mut self, routes: &mut [Route]) -> io::Result<()> {
+        if routes.iter().all(|route| route.nexthop_id.is_none()) {
+            return Ok(());
+        }
+
+        let objects = self.nexthops()?;
+        let by_id = |id: u32| objects.iter().find(|object| object.id == id);
+
+        for route in routes.iter_mut() {
+            let Some(object) = route.nexthop_id.and_then(by_id) else {
+                continue;
+            };
+
+            route.nexthops = match (object.blackhole, object.group.is_empty()) {
+                (true, _) => Vec::new(),
+                (false, true) => vec![object.hop(1)],
+                (false, false) => object
+                    .group
+                    .iter()
+                    .filter_map(|(id, weight)| by_id(*id).map(|member| member.hop(*weight)))
+                    .collect(),
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Dump the nexthop objects, as created by "ip nexthop add" (Linux 5.3 and later).
+    /// Older kernels have none.
+    pub fn nexthops(&mut self) -> io::Result<Vec<NexthopObject>> {
+        let header = [0_u8; NHMSG_LEN];
+
+        match self.request(RTM_GETNEXTHOP, NLM_F_DUMP, &header) {
+            Ok(replies) => Ok(replies
+                .iter()
+                .filter(|msg| msg.kind == RTM_NEWNEXTHOP)
+                .filter_map(|msg| NexthopObject::parse(&msg.payload))
+                .collect()),
+            Err(e) if e.raw_os_error() == Some(libc::EOPNOTSUPP) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// With strict checking, the kernel only dumps the routes matching the RTA_OIF
+    /// attribute of the request.
+    fn dump_routes(&mut self, family: u8, oif: Option<u32>) -> io::Result<Vec<Route>> {
+        let mut payload = vec![0_u8; RTMSG_LEN];
+        payload[0] = family;
+        if let (true, Some(oif)) = (self.strict, oif) {
+            push_attribute(&mut payload, RTA_OIF, &oif.to_ne_bytes());
+        }
+        let replies = self.request(RTM_GETROUTE, NLM_F_DUMP, &payload)?;
+
+        Ok(replies
+            .iter()
+            .filter(|msg| msg.kind == RTM_NEWROUTE)
+            .filter_map(|msg| Route::parse(&msg.payload))
+            .collect())
+    }
+
+    /// Ask the kernel which route it would use for a packet, as "ip route get" does. The
+    /// route is resolved for the destination (oif, gateway and preferred source), or with
+    /// `fib_match`, reported as it is in its table. None if there is no route.
+    pub fn route_get(&mut self, query: &RouteQuery, fib_match: bool) -> io::Result<Option<Route>> {
+        let full_len = |ip: &IpAddr| match ip {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        // IPv6 always reports the table, and its strict checking rejects RTM_F_LOOKUP_TABLE
+        let flags = match (query.destination, fib_match) {
+            (IpAddr::V4(_), true) => RTM_F_LOOKUP_TABLE | RTM_F_FIB_MATCH,
+            (IpAddr::V4(_), false) => RTM_F_LOOKUP_TABLE,
+            (IpAddr::V6(_), true) => RTM_F_FIB_MATCH,
+            (IpAddr::V6(_), false) => 0,
+        };
+
+        let mut payload = vec![0_u8; RTMSG_LEN];
+        payload[0] = match query.destination {
+            IpAddr::V4(_) => libc::AF_INET as u8,
+            IpAddr::V6(_) => libc::AF_INET6 as u8,
+        };
+        payload[1] = full_len(&query.destination);
+        payload[8..12].copy_from_slice(&flags.to_ne_bytes());
+        push_attribute(&mut payload, RTA_DST, &ip_bytes(&query.destination));
+        if let Some(source) = &query.source {
+            payload[2] = full_len(source);
+            push_attribute(&mut payload, RTA_SRC, &ip_bytes(source));
+        }
+        if let Some(oif) = query.oif {
+            push_attribute(&mut payload, RTA_OIF, &oif.to_ne_bytes());
+        }
+        if let Some(mark) = query.mark {
+            push_attribute(&mut payload, RTA_MARK, &mark.to_ne_bytes());
+        }
+
+        match self.request(RTM_GETROUTE, 0, &payload) {
+            Ok(replies) => Ok(replies
+                .iter()
+                .filter(|msg| msg.kind == RTM_NEWROUTE)
+                .find_map(|msg| Route::parse(&msg.payload))),
+            Err(e)
+                if e.raw_os_error()
+                    .is_some_and(|errno| NO_ROUTE_ERRORS.contains(&errno)) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Dump the neighbour (ARP and NDP) tables of the given family (AF_UNSPEC for all of them).
+    pub fn neighbours(&mut self, family: u8) -> io::Result<Vec<Neighbour>> {
+        let mut header = [0_u8; NDMSG_LEN];
+        header[0] = family;
+        let replies = self.request(RTM_GETNEIGH, NLM_F_DUMP, &header)?;
+
+        Ok(replies
+            .iter()
+            .filter(|msg| msg.kind == RTM_NEWNEIGH)
+            .filter_map(|msg| Neighbour::parse(&msg.payload))
+            .collect())
+    }
+}
+
+impl AsRawFd for NetlinkSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+/// What a route lookup is about: a packet to `destination`, with the other fields
+/// narrowing down which route applies.
+#[derive(Debug, Clone)]
+pub struct RouteQuery {
+    pub destination: IpAddr,
+    pub source: Option<IpAddr>,
+    /// The index of the output interface
+    pub oif: Option<u32>,
+    /// The firewall mark of the packet, as used by policy routing rules
+    pub mark: Option<u32>,
+}
+
+/// The errors a route lookup fails with when no route matches the destination, or when
+/// an "unreachable" route does.
+const NO_ROUTE_ERRORS: [i32; 2] = [libc::ENETUNREACH, libc::EHOSTUNREACH];
+
+/// A network interface, as described by RTM_NEWLINK.
+#[derive(Debug, Clone)]
+pub struct Link {
+    pub index: u32,
+    /// The IFF_* flags
+    pub flags: u32,
+    pub name: String,
+    pub mtu: Option<u32>,
+    pub operstate: Option<u8>,
+    pub address: Option<Vec<u8>>,
+    pub broadcast: Option<Vec<u8>>,
+    /// The counters of `struct rtnl_link_stats64`, in order
+    pub stats: Option<Vec<u64>>,
+    /// The kind of virtual link, e.g. "veth" or "bridge"; hardware links have none
+    pub kind: Option<String>,
+    /// The index of the link this one is enslaved to, e.g. its bridge or bond
+    pub master: Option<u32>,
+    /// The name of the device backing the link, on Linux 5.16 and later
+    pub parent_device: Option<String>,
+    /// The description of the link, as set by "ip link set ... alias"
+    pub ifalias: Option<String>,
+    /// The alternative names of the link, as added by "ip link property add ... altname"
+    pub altnames: Vec<String>,
+}
+
+impl Link {
+    pub fn parse(payload: &[u8]) -> Option<Link> {
+        let mut link = Link {
+            index: read_u32(payload, 4)?,
+            flags: read_u32(payload, 8)?,
+            name: String::new(),
+            mtu: None,
+            operstate: None,
+            address: None,
+            broadcast: None,
+            stats: None,
+            kind: None,
+            master: None,
+            parent_device: None,
+            ifalias: None,
+            altnames: Vec::new(),
+        };
+
+        for (kind, value) in Attributes::new(payload.get(IFINFOMSG_LEN..)?) {
+            match kind {
+                IFLA_IFNAME => link.name = read_string(value),
+                IFLA_MTU => link.mtu = read_u32(value, 0),
+                IFLA_OPERSTATE => link.operstate = value.first().copied(),
+                IFLA_ADDRESS => link.address = Some(value.to_vec()),
+                IFLA_BROADCAST => link.broadcast = Some(value.to_vec()),
+                IFLA_STATS64 => {
+                    let counters = value
+                        .chunks_exact(8)
+                        .map(|chunk| u64::from_ne_bytes(chunk.try_into().unwrap()))
+                        .collect();
+                    link.stats = Some(counters);
+                }
+                IFLA_MASTER => link.master = read_u32(value, 0),
+                IFLA_LINKINFO => {
+                    link.kind = Attributes::new(value)
+                        .find(|(kind, _)| *kind == IFLA_INFO_KIND)
+                        .map(|(_, kind)| read_string(kind));
+                }
+                IFLA_PARENT_DEV_NAME => link.parent_device = Some(read_string(value)),
+                IFLA_IFALIAS => link.ifalias = Some(read_string(value)),
+                IFLA_PROP_LIST => {
+                    link.altnames = Attributes::new(value)
+                        .filter(|(kind, _)| *kind == IFLA_ALT_IFNAME)
+                        .map(|(_, name)| read_string(name))
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+
+        Some(link)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.flags & libc::IFF_RUNNING as u32 != 0
+    }
+
+    /// Whether the link is administratively up, i.e. "ip link set ... up".
+    #[cfg(feature = "python")]
+    pub fn is_up(&self) -> bool {
+        self.flags & libc::IFF_UP as u32 != 0
+    }
+
+    /// Whether the link goes by this name, as its name or one of its alternative names.
+    #[cfg(any(feature = "python", test))]
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name == name || self.altnames.iter().any(|altname| altname == name)
+    }
+}
+
+/// An interface address, as described by RTM_NEWADDR.
+#[derive(Debug, Clone)]
+pub struct Address {
+    pub family: u8,
+    pub prefix_len: u8,
+    /// The IFA_F_* flags
+    pub flags: u32,
+    /// The RT_SCOPE_* scope, which only the waits of the Python module look at
+    #[cfg_attr(not(feature = "python"), allow(dead_code))]
+    pub scope: u8,
+    pub index: u32,
+    /// IFA_ADDRESS; this is the peer address on point-to-point links
+    pub address: Option<IpAddr>,
+    /// IFA_LOCAL; always present for IPv4, only on point-to-point links for IPv6
+    pub local: Option<IpAddr>,
+    pub broadcast: Option<IpAddr>,
+    pub label: Option<String>,
+}
+
+impl Address {
+    pub fn parse(payload: &[u8]) -> Option<Address> {
+        let mut address = Address {
+            family: *payload.first()?,
+            prefix_len: *payload.get(1)?,
+            flags: *payload.get(2)? as u32,
+            scope: *payload.get(3)?,
+            index: read_u32(payload, 4)?,
+            address: None,
+            local: None,
+            broadcast: None,
+            label: None,
+        };
+
+        for (kind, value) in Attributes::new(payload.get(IFADDRMSG_LEN..)?) {
+            match kind {
+                IFA_ADDRESS => address.address = read_ip(value),
+                IFA_LOCAL => address.local = read_ip(value),
+                IFA_BROADCAST => address.broadcast = read_ip(value),
+                IFA_LABEL => address.label = Some(read_string(value)),
+                // The 8 bits of ifa_flags are not enough for all the flags; when
+                // present, this attribute holds the full set.
+                IFA_FLAGS => address.flags = read_u32(value, 0).unwrap_or(address.flags),
+                _ => {}
+            }
+        }
+
+        Some(address)
+    }
+
+    /// The address of the interface itself (as opposed to the peer's).
+    pub fn local_ip(&self) -> Option<IpAddr> {
+        self.local.or(self.address)
+    }
+
+    /// Whether the address is done with duplicate address detection and
+    /// can be used as a source address.
+    #[cfg(any(feature = "python", test))]
+    pub fn is_usable(&self) -> bool {
+        self.flags & (IFA_F_TENTATIVE | IFA_F_DADFAILED) == 0
+    }
+
+    #[cfg(any(feature = "python", test))]
+    pub fn is_global(&self) -> bool {
+        self.scope == RT_SCOPE_UNIVERSE
+    }
+}
+
+/// A route, as described by RTM_NEWROUTE.
+#[derive(Debug, Clone)]
+pub struct Route {
+    pub family: u8,
+    pub dst_len: u8,
+    pub table: u32,
+    pub protocol: u8,
+    pub scope: u8,
+    /// The RTN_* route type
+    pub kind: u8,
+    pub flags: u32,
+    pub destination: Option<IpAddr>,
+    pub gateway: Option<IpAddr>,
+    pub oif: Option<u32>,
+    pub prefsrc: Option<IpAddr>,
+    pub priority: Option<u32>,
+    /// The paths of the route. Multipath routes have several and no gateway or oif of
+    /// their own; other routes have a single one, made of their gateway and oif.
+    pub nexthops: Vec<NextHop>,
+    /// The nexthop object (or group) the route uses, if any
+    pub nexthop_id: Option<u32>,
+}
+
+impl Route {
+    pub fn parse(payload: &[u8]) -> Option<Route> {
+        let mut route = Route {
+            family: *payload.first()?,
+            dst_len: *payload.get(1)?,
+            table: *payload.get(4)? as u32,
+            protocol: *payload.get(5)?,
+            scope: *payload.get(6)?,
+            kind: *payload.get(7)?,
+            flags: read_u32(payload, 8)?,
+            destination: None,
+            gateway: None,
+            oif: None,
+            prefsrc: None,
+            priority: None,
+            nexthops: Vec::new(),
+            nexthop_id: None,
+        };
+
+        for (kind, value) in Attributes::new(payload.get(RTMSG_LEN..)?) {
+            match kind {
+                RTA_DST => route.destination = read_ip(value),
+                RTA_GATEWAY => route.gateway = read_ip(value),
+                RTA_VIA => route.gateway = read_via(value),
+                RTA_OIF => route.oif = read_u32(value, 0),
+                RTA_PREFSRC => route.prefsrc = read_ip(value),
+                RTA_PRIORITY => route.priority = read_u32(value, 0),
+                // rtm_table only has room for the first 255 tables
+                RTA_TABLE => route.table = read_u32(value, 0).unwrap_or(route.table),
+                RTA_MULTIPATH => route.nexthops = NextHop::parse_multipath(value),
+                RTA_NH_ID => route.nexthop_id = read_u32(value, 0),
+                _ => {}
+            }
+        }
+
+        // The RTNH_F_* flags of a single path are reported in the low byte of rtm_flags
+        if route.nexthops.is_empty() && (route.gateway.is_some() || route.oif.is_some()) {
+            route.nexthops.push(NextHop {
+                gateway: route.gateway,
+                oif: route.oif,
+                weight: 1,
+                flags: route.flags & 0xff,
+            });
+        }
+
+        Some(route)
+    }
+
+    #[cfg(any(feature = "python", test))]
+    pub fn is_default(&self) -> bool {
+        self.dst_len == 0
+    }
+}
+
+/// One of the paths of a route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NextHop {
+    pub gateway: Option<IpAddr>,
+    pub oif: Option<u32>,
+    /// The share of the traffic going through this path, relative to the other paths
+    pub weight: u32,
+    /// The RTNH_F_* flags
+    pub flags: u32,
+}
+
+impl NextHop {
+    /// Decode the `struct rtnexthop` entries of an RTA_MULTIPATH attribute, each
+    /// followed by its own attributes.
+    fn parse_multipath(mut buf: &[u8]) -> Vec<NextHop> {
+        let mut hops = Vec::new();
+
+        while let Some(len) = read_u16(buf, 0).map(usize::from) {
+            if len < RTNEXTHOP_LEN || len > buf.len() {
+                break;
+            }
+
+            let oif = read_u32(buf, 4).filter(|oif| *oif != 0);
+            let gateway =
+                Attributes::new(&buf[RTNEXTHOP_LEN..len]).find_map(|(kind, value)| match kind {
+                    RTA_GATEWAY => read_ip(value),
+                    RTA_VIA => read_via(value),
+                    _ => None,
+                });
+            hops.push(NextHop {
+                gateway,
+                oif,
+                // rtnh_hops holds the weight minus one
+                weight: buf[3] as u32 + 1,
+                flags: buf[2] as u32,
+            });
+
+            buf = &buf[align(len).min(buf.len())..];
+        }
+
+        hops
+    }
+
+    /// Whether traffic can currently go through this path.
+    #[cfg(any(feature = "python", test))]
+    pub fn is_alive(&self) -> bool {
+        self.flags & (RTNH_F_DEAD | RTNH_F_LINKDOWN) == 0
+    }
+}
+
+/// A nexthop object or group, as described by RTM_NEWNEXTHOP.
+#[derive(Debug, Clone)]
+pub struct NexthopObject {
+    pub id: u32,
+    /// The RTNH_F_* flags
+    pub flags: u32,
+    pub gateway: Option<IpAddr>,
+    pub oif: Option<u32>,
+    pub blackhole: bool,
+    /// For a group, the ids of its members with their weights
+    pub group: Vec<(u32, u32)>,
+}
+
+impl NexthopObject {
+    pub fn parse(payload: &[u8]) -> Option<NexthopObject> {
+        let mut object = NexthopObject {
+            id: 0,
+            flags: read_u32(payload, 4)?,
+            gateway: None,
+            oif: None,
+            blackhole: false,
+            group: Vec::new(),
+        };
+
+        for (kind, value) in Attributes::new(payload.get(NHMSG_LEN..)?) {
+            match kind {
+                NHA_ID => object.id = read_u32(value, 0)?,
+                NHA_GATEWAY => object.gateway = read_ip(value),
+                NHA_OIF => object.oif = read_u32(value, 0),
+                NHA_BLACKHOLE => object.blackhole = true,
+                // struct nexthop_grp: the id, then the weight minus one, split in a low
+                // and (on recent kernels) a high byte
+                NHA_GROUP => {
+                    object.group = value
+                        .chunks_exact(NEXTHOP_GRP_LEN)
+                        .filter_map(|member| {
+                            let weight = u32::from_le_bytes([member[4], member[5], 0, 0]);
+                            Some((read_u32(member, 0)?, weight + 1))
+                        })
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+
+        Some(object)
+    }
+
+    /// The path of a route through this object, with the given weight.
+    fn hop(&self, weight: u32) -> NextHop {
+        NextHop {
+            gateway: self.gateway,
+            oif: self.oif,
+            weight,
+            flags: self.flags,
+        }
+    }
+}
+
+/// An entry of the neighbour tables, as described by RTM_NEWNEIGH.
+#[derive(Debug, Clone)]
+pub struct Neighbour {
+    pub family: u8,
+    pub index: u32,
+    /// The NUD_* state
+    pub state: u16,
+    /// The NTF_* flags
+    pub flags: u8,
+    pub destination: Option<IpAddr>,
+    pub lladdr: Option<Vec<u8>>,
+}
+
+impl Neighbour {
+    pub fn parse(payload: &[u8]) -> Option<Neighbour> {
+        let mut neighbour = Neighbour {
+            family: *payload.first()?,
+            index: read_u32(payload, 4)?,
+            state: read_u16(payload, 8)?,
+            flags: *payload.get(10)?,
+            destination: None,
+            lladdr: None,
+        };
+
+        for (kind, value) in Attributes::new(payload.get(NDMSG_LEN..)?) {
+            match kind {
+                NDA_DST => neighbour.destination = read_ip(value),
+                NDA_LLADDR => neighbour.lladdr = Some(value.to_vec()),
+                _ => {}
+            }
+        }
+
+        Some(neighbour)
+    }
+}
+
+// The fixtures were captured on x86_64, and the kernel writes the messages in native
+// byte order
+#[cfg(all(test, target_endian = "little"))]
+mod tests {
+    use super::*;
+
+    /// RTM_NEWLINK for a veth enslaved to link 3, with an alias and an alternative
+    /// name. IFLA_LINKINFO and IFLA_PROP_LIST carry NLA_F_NESTED.
+    #[rustfmt::skip]
+    const VETH_LINK: &[u8] = &[
+        // ifinfomsg
+        0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x43, 0x10, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        // IFLA_IFNAME
+        0x0a, 0x00, 0x03, 0x00, b'v', b'e', b't', b'h', b'0', 0x00, 0x00, 0x00,
+        // IFLA_MTU
+        0x08, 0x00, 0x04, 0x00, 0xdc, 0x05, 0x00, 0x00,
+        // IFLA_MASTER
+        0x08, 0x00, 0x0a, 0x00, 0x03, 0x00, 0x00, 0x00,
+        // IFLA_OPERSTATE
+        0x05, 0x00, 0x10, 0x00, 0x06, 0x00, 0x00, 0x00,
+        // IFLA_LINKINFO, holding IFLA_INFO_KIND
+        0x10, 0x00, 0x12, 0x80,
+        0x09, 0x00, 0x01, 0x00, b'v', b'e', b't', b'h', 0x00, 0x00, 0x00, 0x00,
+        // IFLA_IFALIAS
+        0x0b, 0x00, 0x14, 0x00, b'u', b'p', b'l', b'i', b'n', b'k', 0x00, 0x00,
+        // IFLA_PROP_LIST, holding IFLA_ALT_IFNAME
+        0x10, 0x00, 0x34, 0x80,
+        0x0b, 0x00, 0x35, 0x00, b'e', b'n', b'p', b'0', b's', b'3', 0x00, 0x00,
+        // IFLA_ADDRESS
+        0x0a, 0x00, 0x01, 0x00, 0x52, 0x54, 0x00, 0x12, 0x34, 0x56, 0x00, 0x00,
+    ];
+
+    /// RTM_NEWADDR for 192.0.2.10/24 on link 2, labelled as an alias, whose flags do
+    /// not fit in ifa_flags.
+    #[rustfmt::skip]
+    const ALIAS_ADDRESS: &[u8] = &[
+        // ifaddrmsg
+        0x02, 0x18, 0x80, 0x00, 0x02, 0x00, 0x00, 0x00,
+        // IFA_ADDRESS
+        0x08, 0x00, 0x01, 0x00, 0xc0, 0x00, 0x02, 0x0a,
+        // IFA_LOCAL
+        0x08, 0x00, 0x02, 0x00, 0xc0, 0x00, 0x02, 0x0a,
+        // IFA_BROADCAST
+        0x08, 0x00, 0x04, 0x00, 0xc0, 0x00, 0x02, 0xff,
+        // IFA_LABEL
+        0x0c, 0x00, 0x03, 0x00, b'v', b'e', b't', b'h', b'0', b':', b'1', 0x00,
+        // IFA_FLAGS
+        0x08, 0x00, 0x08, 0x00, 0x80, 0x02, 0x00, 0x00,
+    ];
+
+    /// RTM_NEWADDR for the tentative fe80::1/64 on link 2.
+    #[rustfmt::skip]
+    const TENTATIVE_ADDRESS: &[u8] = &[
+        // ifaddrmsg
+        0x0a, 0x40, 0x40, 0xfd, 0x02, 0x00, 0x00, 0x00,
+        // IFA_ADDRESS
+        0x14, 0x00, 0x01, 0x00,
+        0xfe, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+    ];
+
+    /// RTM_NEWROUTE for "default via 192.0.2.1 dev veth0 metric 100".
+    #[rustfmt::skip]
+    const DEFAULT_ROUTE: &[u8] = &[
+        // rtmsg
+        0x02, 0x00, 0x00, 0x00, 0xfe, 0x04, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        // RTA_TABLE
+        0x08, 0x00, 0x0f, 0x00, 0xfe, 0x00, 0x00, 0x00,
+        // RTA_PRIORITY
+        0x08, 0x00, 0x06, 0x00, 0x64, 0x00, 0x00, 0x00,
+        // RTA_GATEWAY
+        0x08, 0x00, 0x05, 0x00, 0xc0, 0x00, 0x02, 0x01,
+        // RTA_OIF
+        0x08, 0x00, 0x04, 0x00, 0x02, 0x00, 0x00, 0x00,
+    ];
+
+    /// RTM_NEWROUTE for "198.51.100.0/24 table 1000 nexthop via 192.0.2.1 dev veth0
+    /// nexthop via 203.0.113.1 dev veth1 weight 3", whose second path is dead.
+    #[rustfmt::skip]
+    const MULTIPATH_ROUTE: &[u8] = &[
+        // rtmsg
+        0x02, 0x18, 0x00, 0x00, 0xfc, 0x04, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        // RTA_TABLE
+        0x08, 0x00, 0x0f, 0x00, 0xe8, 0x03, 0x00, 0x00,
+        // RTA_DST
+        0x08, 0x00, 0x01, 0x00, 0xc6, 0x33, 0x64, 0x00,
+        // RTA_MULTIPATH, holding two rtnexthop with their RTA_GATEWAY
+        0x24, 0x00, 0x09, 0x00,
+        0x10, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x05, 0x00, 0xc0, 0x00, 0x02, 0x01,
+        0x10, 0x00, 0x01, 0x02, 0x03, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x05, 0x00, 0xcb, 0x00, 0x71, 0x01,
+    ];
+
+    /// The same route in the main table, whose second rtnexthop claims more bytes than
+    /// RTA_MULTIPATH holds.
+    #[rustfmt::skip]
+    const TRUNCATED_MULTIPATH_ROUTE: &[u8] = &[
+        // rtmsg
+        0x02, 0x18, 0x00, 0x00, 0xfe, 0x04, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        // RTA_DST
+        0x08, 0x00, 0x01, 0x00, 0xc6, 0x33, 0x64, 0x00,
+        // RTA_MULTIPATH
+        0x20, 0x00, 0x09, 0x00,
+        0x10, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x05, 0x00, 0xc0, 0x00, 0x02, 0x01,
+        0x10, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x05, 0x00,
+    ];
+
+    fn ip(text: &str) -> Option<IpAddr> {
+        Some(text.parse().unwrap())
+    }
+
+    #[test]
+    fn parse_link() {
+        let link = Link::parse(VETH_LINK).unwrap();
+
+        assert_eq!(link.index, 2);
+        assert_eq!(link.flags, 0x11043);
+        assert_eq!(link.name, "veth0");
+        assert_eq!(link.mtu, Some(1500));
+        assert_eq!(link.master, Some(3));
+        assert_eq!(link.operstate, Some(6));
+        assert_eq!(link.kind.as_deref(), Some("veth"));
+        assert_eq!(link.ifalias.as_deref(), Some("uplink"));
+        assert_eq!(link.altnames, ["enp0s3"]);
+        assert_eq!(
+            link.address.as_deref(),
+            Some(&[0x52, 0x54, 0x00, 0x12, 0x34, 0x56][..])
+        );
+        assert!(link.is_running());
+        assert!(link.has_name("enp0s3"));
+    }
+
+    #[test]
+    fn parse_truncated_link() {
+        // The attributes that fit are kept, the one that is cut short is dropped
+        let link = Link::parse(&VETH_LINK[..VETH_LINK.len() - 6]).unwrap();
+        assert_eq!(link.name, "veth0");
+        assert_eq!(link.altnames, ["enp0s3"]);
+        assert_eq!(link.address, None);
+
+        // Without a full ifinfomsg, there is no link
+        assert!(Link::parse(&VETH_LINK[..12]).is_none());
+    }
+
+    #[test]
+    fn parse_address() {
+        let address = Address::parse(ALIAS_ADDRESS).unwrap();
+
+        assert_eq!(address.family, libc::AF_INET as u8);
+        assert_eq!(address.prefix_len, 24);
+        assert_eq!(address.index, 2);
+        assert_eq!(address.local_ip(), ip("192.0.2.10"));
+        assert_eq!(address.broadcast, ip("192.0.2.255"));
+        assert_eq!(address.label.as_deref(), Some("veth0:1"));
+        assert_eq!(address.flags, 0x280);
+        assert!(address.is_usable());
+        assert!(address.is_global());
+
+        let address = Address::parse(TENTATIVE_ADDRESS).unwrap();
+        assert_eq!(address.local, None);
+        assert_eq!(address.local_ip(), ip("fe80::1"));
+        assert_eq!(address.scope, RT_SCOPE_LINK);
+        assert!(!address.is_usable());
+    }
+
+    #[test]
+    fn parse_truncated_address() {
+        let address = Address::parse(&TENTATIVE_ADDRESS[..TENTATIVE_ADDRESS.len() - 1]).unwrap();
+        assert_eq!(address.address, None);
+    }
+
+    #[test]
+    fn parse_route() {
+        let route = Route::parse(DEFAULT_ROUTE).unwrap();
+
+        assert!(route.is_default());
+        assert_eq!(route.table, RT_TABLE_MAIN);
+        assert_eq!(route.kind, RTN_UNICAST);
+        assert_eq!(route.priority, Some(100));
+        assert_eq!(route.gateway, ip("192.0.2.1"));
+        assert_eq!(
+            route.nexthops,
+            [NextHop {
+                gateway: ip("192.0.2.1"),
+                oif: Some(2),
+                weight: 1,
+                flags: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_multipath_route() {
+        let route = Route::parse(MULTIPATH_ROUTE).unwrap();
+
+        assert_eq!(route.destination, ip("198.51.100.0"));
+        assert_eq!(route.dst_len, 24);
+        // rtm_table only holds RT_TABLE_UNSPEC
+        assert_eq!(route.table, 1000);
+        assert_eq!((route.gateway, route.oif), (None, None));
+        assert_eq!(
+            route.nexthops,
+            [
+                NextHop {
+                    gateway: ip("192.0.2.1"),
+                    oif: Some(2),
+                    weight: 1,
+                    flags: 0,
+                },
+                NextHop {
+                    gateway: ip("203.0.113.1"),
+                    oif: Some(3),
+                    weight: 3,
+                    flags: RTNH_F_DEAD,
+                },
+            ]
+        );
+        assert!(route.nexthops[0].is_alive());
+        assert!(!route.nexthops[1].is_alive());
+    }
+
+    #[test]
+    fn parse_truncated_multipath_route() {
+        let route = Route::parse(TRUNCATED_MULTIPATH_ROUTE).unwrap();
+        assert_eq!(route.nexthops.len(), 1);
+        assert_eq!(route.nexthops[0].oif, Some(2));
+    }
+
+    #[test]
+    fn split_datagram() {
+        let mut datagram = Vec::new();
+        for (kind, payload) in [(RTM_NEWROUTE, DEFAULT_ROUTE), (NLMSG_DONE, &[0; 4][..])] {
+            datagram.extend_from_slice(&((NLMSG_HDRLEN + payload.len()) as u32).to_ne_bytes());
+            datagram.extend_from_slice(&kind.to_ne_bytes());
+            datagram.extend_from_slice(&NLM_F_DUMP_INTR.to_ne_bytes());
+            datagram.extend_from_slice(&7_u32.to_ne_bytes());
+            datagram.extend_from_slice(&0_u32.to_ne_bytes());
+            datagram.extend_from_slice(payload);
+        }
+
+        let messages: Vec<_> = Messages { buf: &datagram }.collect();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0],
+            (RTM_NEWROUTE, NLM_F_DUMP_INTR, 7, DEFAULT_ROUTE)
+        );
+        assert_eq!(messages[1].0, NLMSG_DONE);
+
+        // A message longer than what is left of the datagram ends it
+        let messages = Messages {
+            buf: &datagram[..datagram.len() - 1],
+        };
+        assert_eq!(messages.count(), 1);
+    }
+
+    #[test]
+    fn receive_datagrams_larger_than_the_buffer() {
+        let expected = NetlinkSocket::new().unwrap().links().unwrap();
+
+        let mut socket = NetlinkSocket::new().unwrap();
+        socket.buf = vec![0; NLMSG_HDRLEN];
+        let links = socket.links().unwrap();
+
+        assert!(socket.buf.len() > NLMSG_HDRLEN);
+        let names = |links: &[Link]| {
+            links
+                .iter()
+                .map(|link| link.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&links), names(&expected));
+    }
+}
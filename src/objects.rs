@@ -0,0 +1,480 @@
+//! The typed results: interfaces, their addresses and routes, as Python objects.
+//!
+//! The dicts returned by `ifaddresses`, `snapshot` and `routes` are what the `as_dict`
+//! methods of these objects return, so that both APIs always agree.
+
+use crate::api;
+use crate::types::{
+    AddrPairs, IfAddrs, ADDR_ADDR, AF_PACKET, BROADCAST_ADDR, MASK_ADDR, PEER_ADDR,
+};
+use pyo3::prelude::*;
+use std::fmt::Display;
+use std::net::IpAddr;
+
+/// Shape key and value pairs like an entry of the result of `ifaddresses`.
+fn pairs<T: Display>(
+    address: &T,
+    netmask: Option<&T>,
+    broadcast: Option<&T>,
+    peer: Option<&T>,
+) -> AddrPairs {
+    let mut pairs = AddrPairs::from([(ADDR_ADDR.to_string(), address.to_string())]);
+
+    for (key, value) in [
+        (MASK_ADDR, netmask),
+        (BROADCAST_ADDR, broadcast),
+        (PEER_ADDR, peer),
+    ] {
+        if let Some(value) = value {
+            pairs.insert(key.to_string(), value.to_string());
+        }
+    }
+
+    pairs
+}
+
+/// An IP address as an entry of the result of `ifaddresses`.
+pub fn address_pairs(address: &api::Address) -> AddrPairs {
+    pairs(
+        &address.ip,
+        address.netmask.as_ref(),
+        address.broadcast.as_ref(),
+        address.peer.as_ref(),
+    )
+}
+
+/// A link layer address as an entry of the result of `ifaddresses`.
+pub fn link_address_pairs(link_address: &api::LinkAddress) -> AddrPairs {
+    pairs(
+        &link_address.address,
+        None,
+        link_address.broadcast.as_ref(),
+        link_address.peer.as_ref(),
+    )
+}
+
+/// Shape the addresses of an interface like the result of `ifaddresses`.
+pub fn if_addrs(link_address: Option<&api::LinkAddress>, addresses: &[api::Address]) -> IfAddrs {
+    let mut if_addrs = IfAddrs::new();
+
+    if let Some(link_address) = link_address {
+        if_addrs.insert(AF_PACKET.into(), vec![link_address_pairs(link_address)]);
+    }
+    for address in addresses {
+        if_addrs
+            .entry(address.family().into())
+            .or_default()
+            .push(address_pairs(address));
+    }
+
+    if_addrs
+}
+
+/// Call one of the factories of the `ipaddress` module.
+fn ipaddress(py: Python<'_>, factory: &str, text: &str) -> PyResult<PyObject> {
+    Ok(py
+        .import("ipaddress")?
+        .getattr(factory)?
+        .call1((text,))?
+        .unbind())
+}
+
+/// An `ipaddress` object, or None.
+fn ip_object(py: Python<'_>, ip: Option<&IpAddr>) -> PyResult<PyObject> {
+    match ip {
+        Some(ip) => ipaddress(py, "ip_address", &ip.to_string()),
+        None => Ok(py.None()),
+    }
+}
+
+/// How Python would show an optional string.
+fn optional_repr(value: Option<&impl Display>) -> String {
+    match value {
+        Some(value) => format!("'{value}'"),
+        None => "None".to_string(),
+    }
+}
+
+#[derive(Debug, Clone)]
+enum AddressKind {
+    Ip(api::Address),
+    Link(api::LinkAddress),
+}
+
+/// An address held by an interface.
+#[pyclass(module = "netifaces")]
+#[derive(Debug, Clone)]
+pub struct Address {
+    address: AddressKind,
+}
+
+impl From<api::Address> for Address {
+    fn from(address: api::Address) -> Self {
+        Address {
+            address: AddressKind::Ip(address),
+        }
+    }
+}
+
+impl From<api::LinkAddress> for Address {
+    fn from(link_address: api::LinkAddress) -> Self {
+        Address {
+            address: AddressKind::Link(link_address),
+        }
+    }
+}
+
+#[pymethods]
+impl Address {
+    #[getter]
+    fn family(&self) -> i32 {
+        match &self.address {
+            AddressKind::Ip(address) => address.family().into(),
+            AddressKind::Link(_) => AF_PACKET.into(),
+        }
+    }
+
+    /// An `ipaddress.IPv4Address` or `IPv6Address`; a string for hardware addresses
+    #[getter]
+    fn ip(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match &self.address {
+            AddressKind::Ip(address) => ip_object(py, Some(&address.ip)),
+            AddressKind::Link(link_address) => Ok(link_address
+                .address
+                .to_string()
+                .into_pyobject(py)?
+                .into_any()
+                .unbind()),
+        }
+    }
+
+    #[getter]
+    fn prefixlen(&self) -> Option<u8> {
+        match &self.address {
+            AddressKind::Ip(address) => address.prefix_len,
+            AddressKind::Link(_) => None,
+        }
+    }
+
+    #[getter]
+    fn netmask(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match &self.address {
+            AddressKind::Ip(address) => ip_object(py, address.netmask.as_ref()),
+            AddressKind::Link(_) => Ok(py.None()),
+        }
+    }
+
+    #[getter]
+    fn broadcast(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match &self.address {
+            AddressKind::Ip(address) => ip_object(py, address.broadcast.as_ref()),
+            AddressKind::Link(link_address) => Ok(link_address
+                .broadcast
+                .as_ref()
+                .map(ToString::to_string)
+                .into_pyobject(py)?
+                .unbind()),
+        }
+    }
+
+    #[getter]
+    fn peer(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match &self.address {
+            AddressKind::Ip(address) => ip_object(py, address.peer.as_ref()),
+            AddressKind::Link(link_address) => Ok(link_address
+                .peer
+                .as_ref()
+                .map(ToString::to_string)
+                .into_pyobject(py)?
+                .unbind()),
+        }
+    }
+
+    /// The IFA_F_* flags; always 0 outside of Linux
+    #[getter]
+    fn flags(&self) -> u32 {
+        match &self.address {
+            AddressKind::Ip(address) => address.flags,
+            AddressKind::Link(_) => 0,
+        }
+    }
+
+    /// The address along with its network, as an `ipaddress.IPv4Interface` or
+    /// `IPv6Interface`; None if it is not an IP address or its prefix is unknown
+    #[getter]
+    fn ip_interface(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match &self.address {
+            AddressKind::Ip(address) => match address.network() {
+                Some(network) => ipaddress(py, "ip_interface", &network.to_string()),
+                None => Ok(py.None()),
+            },
+            AddressKind::Link(_) => Ok(py.None()),
+        }
+    }
+
+    /// The address as an entry of the result of `ifaddresses`
+    fn as_dict(&self) -> AddrPairs {
+        match &self.address {
+            AddressKind::Ip(address) => address_pairs(address),
+            AddressKind::Link(link_address) => link_address_pairs(link_address),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.address {
+            AddressKind::Ip(address) => match address.network() {
+                Some(network) => format!("Address(family={}, ip='{network}')", self.family()),
+                None => format!("Address(family={}, ip='{}')", self.family(), address.ip),
+            },
+            AddressKind::Link(link_address) => format!(
+                "Address(family={}, ip='{}')",
+                self.family(),
+                link_address.address
+            ),
+        }
+    }
+}
+
+/// An interface, along with all its addresses.
+#[pyclass(module = "netifaces")]
+#[derive(Debug, Clone)]
+pub struct Interface {
+    interface: api::Interface,
+}
+
+impl From<api::Interface> for Interface {
+    fn from(interface: api::Interface) -> Self {
+        Interface { interface }
+    }
+}
+
+#[pymethods]
+impl Interface {
+    #[getter]
+    fn name(&self) -> &str {
+        &self.interface.name
+    }
+
+    #[getter]
+    fn index(&self) -> u32 {
+        self.interface.index
+    }
+
+    /// The IFF_* flags; always 0 on Windows
+    #[getter]
+    fn flags(&self) -> u32 {
+        self.interface.flags
+    }
+
+    /// Same meaning as `interface_is_up`
+    #[getter]
+    fn up(&self) -> bool {
+        self.interface.up
+    }
+
+    #[getter]
+    fn mtu(&self) -> Option<u32> {
+        self.interface.mtu
+    }
+
+    /// The hardware address, if the interface has one
+    #[getter]
+    fn mac(&self) -> Option<String> {
+        self.interface
+            .link_address
+            .as_ref()
+            .map(|link_address| link_address.address.to_string())
+    }
+
+    /// Every address of the interface, its hardware address first
+    #[getter]
+    fn addresses(&self) -> Vec<Address> {
+        let link_address = self
+            .interface
+            .link_address
+            .iter()
+            .cloned()
+            .map(Address::from);
+        let addresses = self.interface.addresses.iter().cloned().map(Address::from);
+
+        link_address.chain(addresses).collect()
+    }
+
+    /// The interface as an entry of the result of `snapshot`
+    fn as_dict(&self) -> api::Interface {
+        self.interface.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Interface(name='{}', index={})",
+            self.interface.name, self.interface.index
+        )
+    }
+}
+
+/// One of the paths of a route.
+#[pyclass(module = "netifaces")]
+#[derive(Debug, Clone)]
+pub struct NextHop {
+    hop: api::NextHop,
+}
+
+#[pymethods]
+impl NextHop {
+    #[getter]
+    fn gateway(&self, py: Python<'_>) -> PyResult<PyObject> {
+        ip_object(py, self.hop.gateway.as_ref())
+    }
+
+    #[getter]
+    fn interface(&self) -> Option<String> {
+        self.hop.interface.clone()
+    }
+
+    #[getter]
+    fn index(&self) -> Option<u32> {
+        self.hop.index
+    }
+
+    #[getter]
+    fn weight(&self) -> u32 {
+        self.hop.weight
+    }
+
+    /// The RTNH_F_* flags
+    #[getter]
+    fn flags(&self) -> u32 {
+        self.hop.flags
+    }
+
+    /// The path as an entry of the "nexthops" of the result of `routes`
+    fn as_dict(&self) -> api::NextHop {
+        self.hop.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "NextHop(gateway={}, interface={}, weight={})",
+            optional_repr(self.hop.gateway.as_ref()),
+            optional_repr(self.hop.interface.as_ref()),
+            self.hop.weight
+        )
+    }
+}
+
+/// A route of one of the routing tables.
+#[pyclass(module = "netifaces")]
+#[derive(Debug, Clone)]
+pub struct Route {
+    route: api::Route,
+}
+
+impl From<api::Route> for Route {
+    fn from(route: api::Route) -> Self {
+        Route { route }
+    }
+}
+
+#[pymethods]
+impl Route {
+    #[getter]
+    fn family(&self) -> u8 {
+        self.route.family()
+    }
+
+    /// The destination network, as an `ipaddress.IPv4Network` or `IPv6Network`; the
+    /// default routes have 0.0.0.0/0 or ::/0
+    #[getter]
+    fn destination(&self, py: Python<'_>) -> PyResult<PyObject> {
+        ipaddress(py, "ip_network", &self.route.destination.to_string())
+    }
+
+    #[getter]
+    fn prefixlen(&self) -> u8 {
+        self.route.destination.prefix_len
+    }
+
+    #[getter]
+    fn gateway(&self, py: Python<'_>) -> PyResult<PyObject> {
+        ip_object(py, self.route.gateway.as_ref())
+    }
+
+    #[getter]
+    fn interface(&self) -> Option<String> {
+        self.route.interface.clone()
+    }
+
+    #[getter]
+    fn index(&self) -> Option<u32> {
+        self.route.index
+    }
+
+    /// The preferred source address
+    #[getter]
+    fn source(&self, py: Python<'_>) -> PyResult<PyObject> {
+        ip_object(py, self.route.source.as_ref())
+    }
+
+    #[getter]
+    fn metric(&self) -> Option<u32> {
+        self.route.metric
+    }
+
+    #[getter]
+    fn table(&self) -> u32 {
+        self.route.table
+    }
+
+    /// The RTPROT_* protocol which installed the route
+    #[getter]
+    fn protocol(&self) -> u8 {
+        self.route.protocol
+    }
+
+    /// The RT_SCOPE_* scope
+    #[getter]
+    fn scope(&self) -> u8 {
+        self.route.scope
+    }
+
+    /// The RTN_* route type
+    #[getter(r#type)]
+    fn route_type(&self) -> u8 {
+        self.route.route_type
+    }
+
+    #[getter]
+    fn nexthops(&self) -> Vec<NextHop> {
+        self.route
+            .nexthops
+            .iter()
+            .map(|hop| NextHop { hop: hop.clone() })
+            .collect()
+    }
+
+    /// The nexthop object (or group) the route uses, if any
+    #[getter]
+    fn nhid(&self) -> Option<u32> {
+        self.route.nexthop_id
+    }
+
+    /// The route as an entry of the result of `routes`
+    fn as_dict(&self) -> api::Route {
+        self.route.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        let destination = match self.route.is_default() {
+            true => "default".to_string(),
+            false => self.route.destination.to_string(),
+        };
+
+        format!(
+            "Route(destination='{destination}', gateway={}, interface={}, table={})",
+            optional_repr(self.route.gateway.as_ref()),
+            optional_repr(self.route.interface.as_ref()),
+            self.route.table
+        )
+    }
+}
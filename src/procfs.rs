@@ -0,0 +1,407 @@
+//! The routing tables, as exposed in /proc/net/route and /proc/net/ipv6_route.
+//!
+//! These files predate netlink and lack most of what it reports (tables, multipath
+//! routes, preferred sources), but they are readable wherever /proc is mounted, even
+//! when netlink sockets are forbidden or iproute2 is not installed.
+
+use crate::api;
+use crate::common::IpNetwork;
+use crate::linux_procfs::link_indexes;
+use crate::netlink::{
+    RTN_UNICAST, RT_SCOPE_HOST, RT_SCOPE_LINK, RT_SCOPE_UNIVERSE, RT_TABLE_LOCAL, RT_TABLE_MAIN,
+};
+#[cfg(feature = "python")]
+use crate::types::ProcRouteEntry;
+use crate::types::{AF_INET, AF_INET6, AF_UNSPEC};
+use crate::NetifacesError;
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+const IPV4_ROUTE_FILE: &str = "/proc/net/route";
+const IPV6_ROUTE_FILE: &str = "/proc/net/ipv6_route";
+
+pub const RTF_REJECT: u32 = 0x0200;
+const RTF_LOCAL: u32 = 0x8000_0000;
+const RTN_LOCAL: u8 = 2;
+const RTN_MULTICAST: u8 = 5;
+const RTN_UNREACHABLE: u8 = 7;
+const RTPROT_KERNEL: u8 = 2;
+const RTPROT_BOOT: u8 = 3;
+
+/// A route, as described by a line of one of the route files. Only the Python module
+/// reports all of its columns.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "python"), allow(dead_code))]
+pub struct ProcRoute {
+    pub family: u8,
+    pub interface: String,
+    pub destination: IpAddr,
+    pub prefix_len: u8,
+    /// The source network of an IPv6 source-specific route
+    pub source: Option<(IpAddr, u8)>,
+    pub gateway: Option<IpAddr>,
+    /// The RTF_* flags
+    pub flags: u32,
+    pub refcnt: u32,
+    pub use_count: u32,
+    pub metric: u32,
+    /// The MTU, TCP window and initial round trip time clamps; IPv4 only
+    pub mtu: Option<u32>,
+    pub window: Option<u32>,
+    pub irtt: Option<u32>,
+}
+
+/// A field of the route files, which are in hexadecimal unless said otherwise.
+pub fn field<T>(fields: &[&str], index: usize, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    parse(fields.get(index)?)
+}
+
+pub fn hex_u32(text: &str) -> Option<u32> {
+    u32::from_str_radix(text, 16).ok()
+}
+
+pub fn hex_u8(text: &str) -> Option<u8> {
+    u8::from_str_radix(text, 16).ok()
+}
+
+fn decimal_u32(text: &str) -> Option<u32> {
+    text.parse().ok()
+}
+
+/// An IPv4 address of /proc/net/route: the 4 bytes of the address, in memory order,
+/// read as a native endian integer.
+fn hex_ipv4(text: &str) -> Option<IpAddr> {
+    Some(IpAddr::V4(Ipv4Addr::from(hex_u32(text)?.to_ne_bytes())))
+}
+
+/// An IPv6 address of /proc/net/ipv6_route: its 16 bytes, in order.
+pub fn hex_ipv6(text: &str) -> Option<IpAddr> {
+    if text.len() != 32 {
+        return None;
+    }
+    Some(IpAddr::V6(Ipv6Addr::from(
+        u128::from_str_radix(text, 16).ok()?,
+    )))
+}
+
+/// The unspecified address stands for "none" in the route files.
+fn specified(ip: IpAddr) -> Option<IpAddr> {
+    (!ip.is_unspecified()).then_some(ip)
+}
+
+/// Parse a line of /proc/net/route: "Iface Destination Gateway Flags RefCnt Use
+/// Metric Mask MTU Window IRTT".
+fn parse_ipv4_line(line: &str) -> Option<ProcRoute> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let mask = field(&fields, 7, hex_u32)?;
+
+    Some(ProcRoute {
+        family: AF_INET,
+        interface: fields.first()?.to_string(),
+        destination: field(&fields, 1, hex_ipv4)?,
+        prefix_len: u32::from_be_bytes(mask.to_ne_bytes()).leading_ones() as u8,
+        source: None,
+        gateway: specified(field(&fields, 2, hex_ipv4)?),
+        flags: field(&fields, 3, hex_u32)?,
+        refcnt: field(&fields, 4, decimal_u32)?,
+        use_count: field(&fields, 5, decimal_u32)?,
+        metric: field(&fields, 6, decimal_u32)?,
+        mtu: Some(field(&fields, 8, decimal_u32)?),
+        window: Some(field(&fields, 9, decimal_u32)?),
+        irtt: Some(field(&fields, 10, decimal_u32)?),
+    })
+}
+
+/// Parse a line of /proc/net/ipv6_route, which has no header: "destination prefixlen
+/// source prefixlen gateway metric refcnt use flags interface".
+fn parse_ipv6_line(line: &str) -> Option<ProcRoute> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let source = field(&fields, 2, hex_ipv6)?;
+    let source_len = field(&fields, 3, hex_u8)?;
+
+    Some(ProcRoute {
+        family: AF_INET6,
+        interface: fields.get(9)?.to_string(),
+        destination: field(&fields, 0, hex_ipv6)?,
+        prefix_len: field(&fields, 1, hex_u8)?,
+        source: (source_len != 0).then_some((source, source_len)),
+        gateway: specified(field(&fields, 4, hex_ipv6)?),
+        metric: field(&fields, 5, hex_u32)?,
+        refcnt: field(&fields, 6, hex_u32)?,
+        use_count: field(&fields, 7, hex_u32)?,
+        flags: field(&fields, 8, hex_u32)?,
+        mtu: None,
+        window: None,
+        irtt: None,
+    })
+}
+
+/// Parse the content of a file of /proc, made of a line per item, given the parser of
+/// its lines.
+pub fn parse_lines<T>(
+    path: &str,
+    content: &str,
+    skip_header: bool,
+    parse_line: fn(&str) -> Option<T>,
+) -> Result<Vec<T>, NetifacesError> {
+    content
+        .lines()
+        .enumerate()
+        .skip(skip_header as usize)
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            parse_line(line).ok_or_else(|| {
+                NetifacesError::Other(format!(
+                    "Cannot understand line {} of {path}: {line}",
+                    number + 1
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Read a file of /proc or /sys. A missing optional file reads as empty, e.g. the IPv6
+/// ones, which are missing when IPv6 is disabled.
+pub fn read_file(path: &str, optional: bool) -> Result<String, Box<dyn std::error::Error>> {
+    match std::fs::read_to_string(Path::new(path)) {
+        Ok(content) => Ok(content),
+        Err(e) if optional && e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(read_error(path, e)),
+    }
+}
+
+/// The error of a file that cannot be read, keeping its errno.
+pub fn read_error(path: &str, e: io::Error) -> Box<dyn std::error::Error> {
+    let message = format!("Cannot read {path}: {e}");
+
+    Box::new(match e.raw_os_error() {
+        Some(errno) => NetifacesError::System { message, errno },
+        None => NetifacesError::Other(message),
+    })
+}
+
+/// The routes of the given family (AF_UNSPEC for both) listed in the route files.
+/// /proc/net/route only has the main table, while /proc/net/ipv6_route has all of them.
+pub fn proc_routes(family: u8) -> Result<Vec<ProcRoute>, Box<dyn std::error::Error>> {
+    let mut routes = Vec::new();
+
+    if family == AF_UNSPEC || family == AF_INET {
+        let content = read_file(IPV4_ROUTE_FILE, false)?;
+        routes.extend(parse_lines(
+            IPV4_ROUTE_FILE,
+            &content,
+            true,
+            parse_ipv4_line,
+        )?);
+    }
+    if family == AF_UNSPEC || family == AF_INET6 {
+        let content = read_file(IPV6_ROUTE_FILE, true)?;
+        routes.extend(parse_lines(
+            IPV6_ROUTE_FILE,
+            &content,
+            false,
+            parse_ipv6_line,
+        )?);
+    }
+
+    Ok(routes)
+}
+
+#[cfg(feature = "python")]
+pub fn proc_route_entry(route: &ProcRoute) -> ProcRouteEntry {
+    ProcRouteEntry {
+        family: route.family,
+        destination: match route.prefix_len {
+            0 => None,
+            _ => Some(route.destination.to_string()),
+        },
+        prefix_len: route.prefix_len,
+        gateway: route.gateway.map(|ip| ip.to_string()),
+        interface: route.interface.clone(),
+        source: route.source.map(|(ip, _)| ip.to_string()),
+        source_prefix_len: route.source.map(|(_, len)| len),
+        flags: route.flags,
+        refcnt: route.refcnt,
+        use_count: route.use_count,
+        metric: route.metric,
+        mtu: route.mtu,
+        window: route.window,
+        irtt: route.irtt,
+    }
+}
+
+/// The routes listed in the route files, in the given family (AF_UNSPEC for both).
+#[cfg(feature = "python")]
+pub fn procfs_routes(family: u8) -> Result<Vec<ProcRouteEntry>, Box<dyn std::error::Error>> {
+    Ok(proc_routes(family)?.iter().map(proc_route_entry).collect())
+}
+
+/// The routes of the route files in the form netlink gives them, for the procfs backend.
+/// The unreachable route on lo ending /proc/net/ipv6_route is the entry the kernel
+/// answers with when no route matches, which netlink does not list.
+pub fn procfs_ip_routes(family: u8) -> Result<Vec<api::Route>, Box<dyn std::error::Error>> {
+    let indexes = link_indexes();
+
+    Ok(proc_routes(family)?
+        .into_iter()
+        .filter(|route| !(route.flags & RTF_REJECT != 0 && route.metric == u32::MAX))
+        .map(|route| ip_route(route, &indexes))
+        .collect())
+}
+
+/// A route of the route files in the form netlink gives it. The files do not tell the
+/// tables, protocols nor scopes of the routes: the local routes and the IPv6 multicast
+/// ones, which /proc/net/ipv6_route lists along with the others, are told apart and put
+/// in the local table, like the kernel does. Every other route is reported as one added
+/// by hand to the main table, even when it comes from another table, which the files
+/// cannot tell.
+fn ip_route(route: ProcRoute, indexes: &HashMap<String, u32>) -> api::Route {
+    let (table, protocol, scope, route_type) = if route.flags & RTF_LOCAL != 0 {
+        (RT_TABLE_LOCAL, RTPROT_KERNEL, RT_SCOPE_HOST, RTN_LOCAL)
+    } else if route.family == AF_INET6 && route.destination.is_multicast() {
+        (
+            RT_TABLE_LOCAL,
+            RTPROT_KERNEL,
+            RT_SCOPE_UNIVERSE,
+            RTN_MULTICAST,
+        )
+    } else {
+        let scope = match route.gateway {
+            Some(_) => RT_SCOPE_UNIVERSE,
+            None => RT_SCOPE_LINK,
+        };
+        let route_type = match route.flags & RTF_REJECT {
+            0 => RTN_UNICAST,
+            _ => RTN_UNREACHABLE,
+        };
+        (RT_TABLE_MAIN, RTPROT_BOOT, scope, route_type)
+    };
+
+    api::Route {
+        destination: IpNetwork {
+            addr: route.destination,
+            prefix_len: route.prefix_len,
+        },
+        gateway: route.gateway,
+        interface: Some(route.interface.clone()),
+        index: indexes.get(&route.interface).copied(),
+        source: None,
+        metric: Some(route.metric),
+        table,
+        protocol,
+        scope,
+        route_type,
+        nexthops: vec![api::NextHop {
+            gateway: route.gateway,
+            index: indexes.get(&route.interface).copied(),
+            interface: Some(route.interface),
+            weight: 1,
+            flags: 0,
+        }],
+        nexthop_id: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// /proc/net/route, whose addresses are in memory order: the fixture only reads
+    /// right on little endian hosts.
+    const IPV4_ROUTES: &str = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t00000000\t010200C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+eth0\t000200C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t1400\t0\t0
+";
+
+    const IPV6_ROUTES: &str = "\
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 fd000000000000000000000000000001 00000400 00000002 00000000 00000003     eth0
+20010db8000000000000000000000000 40 20010db8000100000000000000000000 30 00000000000000000000000000000000 00000100 00000001 00000000 00000001     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 00000000000000000000000000000000 ffffffff 00000001 00000000 00200200       lo
+";
+
+    /// The local and multicast routes the kernel adds to the local table for an address
+    /// of eth0.
+    const IPV6_LOCAL_ROUTES: &str = "\
+fd000000000000000000000000000002 80 00000000000000000000000000000000 00 00000000000000000000000000000000 00000000 00000003 00000000 80200001     eth0
+ff000000000000000000000000000000 08 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000003 00000000 00000001     eth0
+";
+
+    fn ip(text: &str) -> IpAddr {
+        text.parse().unwrap()
+    }
+
+    #[cfg(target_endian = "little")]
+    #[test]
+    fn parse_ipv4_routes() {
+        let routes = parse_lines(IPV4_ROUTE_FILE, IPV4_ROUTES, true, parse_ipv4_line).unwrap();
+
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].interface, "eth0");
+        assert_eq!(routes[0].destination, ip("0.0.0.0"));
+        assert_eq!(routes[0].prefix_len, 0);
+        assert_eq!(routes[0].gateway, Some(ip("192.0.2.1")));
+        assert_eq!(routes[0].flags, 0x3);
+        assert_eq!(routes[0].metric, 100);
+        assert_eq!(routes[1].destination, ip("192.0.2.0"));
+        assert_eq!(routes[1].prefix_len, 24);
+        assert_eq!(routes[1].gateway, None);
+        assert_eq!(routes[1].mtu, Some(1400));
+    }
+
+    #[test]
+    fn parse_ipv6_routes() {
+        let routes = parse_lines(IPV6_ROUTE_FILE, IPV6_ROUTES, false, parse_ipv6_line).unwrap();
+
+        assert_eq!(routes.len(), 3);
+        assert_eq!(routes[0].gateway, Some(ip("fd00::1")));
+        assert_eq!(routes[0].metric, 1024);
+        assert_eq!(routes[0].refcnt, 2);
+        assert_eq!(routes[1].destination, ip("2001:db8::"));
+        assert_eq!(routes[1].prefix_len, 64);
+        assert_eq!(routes[1].source, Some((ip("2001:db8:1::"), 48)));
+        // The "unreachable" route the kernel keeps on lo
+        assert_eq!(routes[2].interface, "lo");
+        assert_eq!(routes[2].metric, u32::MAX);
+        assert_ne!(routes[2].flags & RTF_REJECT, 0);
+    }
+
+    #[test]
+    fn parse_ipv6_local_routes() {
+        let indexes = HashMap::from([("eth0".to_string(), 2)]);
+        let routes: Vec<api::Route> =
+            parse_lines(IPV6_ROUTE_FILE, IPV6_ROUTES, false, parse_ipv6_line)
+                .unwrap()
+                .into_iter()
+                .chain(
+                    parse_lines(IPV6_ROUTE_FILE, IPV6_LOCAL_ROUTES, false, parse_ipv6_line)
+                        .unwrap(),
+                )
+                .map(|route| ip_route(route, &indexes))
+                .collect();
+
+        assert_eq!(routes[0].table, RT_TABLE_MAIN);
+        assert_eq!(routes[0].route_type, RTN_UNICAST);
+        assert_eq!(routes[0].index, Some(2));
+        assert_eq!(routes[2].route_type, RTN_UNREACHABLE);
+        assert_eq!(routes[3].destination.addr, ip("fd00::2"));
+        assert_eq!(routes[3].table, RT_TABLE_LOCAL);
+        assert_eq!(routes[3].route_type, RTN_LOCAL);
+        assert_eq!(routes[3].scope, RT_SCOPE_HOST);
+        assert_eq!(routes[4].destination.addr, ip("ff00::"));
+        assert_eq!(routes[4].destination.prefix_len, 8);
+        assert_eq!(routes[4].table, RT_TABLE_LOCAL);
+        assert_eq!(routes[4].route_type, RTN_MULTICAST);
+    }
+
+    #[test]
+    fn reject_truncated_lines() {
+        let truncated = &IPV6_ROUTES[..IPV6_ROUTES.find("     eth0").unwrap()];
+        let error = parse_lines(IPV6_ROUTE_FILE, truncated, false, parse_ipv6_line).unwrap_err();
+        assert!(error.to_string().contains("line 1 of /proc/net/ipv6_route"));
+
+        assert!(parse_ipv4_line("eth0\t00000000\t010200C0\t0003\t0\t0\t100").is_none());
+    }
+}
@@ -0,0 +1,825 @@
+//! The Python extension module, a thin layer over the backends and the types of [api].
+//!
+//! Queries run without the GIL; their results are converted to Python objects once it
+//! is taken back.
+
+use crate::api;
+use crate::backend;
+use crate::common::{InterfaceDisplay, InterfaceFilter, InterfaceRef, WaitCondition};
+#[cfg(not(target_os = "linux"))]
+use crate::error::unsupported;
+use crate::error::NetifacesError;
+use crate::mock;
+use crate::objects;
+use crate::types;
+#[cfg(target_os = "linux")]
+use crate::wait::{WaitTarget, Waiter};
+#[cfg(target_os = "linux")]
+use crate::{cache, compat, procfs, session};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use std::collections::HashMap;
+use std::net::IpAddr;
+#[cfg(target_os = "linux")]
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+impl TryFrom<i32> for InterfaceDisplay {
+    type Error = PyErr;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(InterfaceDisplay::HumanReadable),
+            1 => Ok(InterfaceDisplay::MachineReadable),
+            2 => Ok(InterfaceDisplay::AltName),
+            3 => Ok(InterfaceDisplay::Index),
+            _ => Err(PyTypeError::new_err(format!(
+                "Invalid interface display {value}"
+            ))),
+        }
+    }
+}
+
+impl TryFrom<i32> for WaitCondition {
+    type Error = PyErr;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(WaitCondition::Present),
+            1 => Ok(WaitCondition::Up),
+            2 => Ok(WaitCondition::HasAddress),
+            _ => Err(PyTypeError::new_err(format!(
+                "Invalid wait condition {value}"
+            ))),
+        }
+    }
+}
+
+/// Run a query on the current backend (see [backend::query]) without holding the GIL,
+/// so that the other Python threads keep running while it waits on the kernel. Its
+/// errors are raised as the ones of the backend which failed, which is the fallback one
+/// when it took over.
+fn without_gil<T, F>(py: Python<'_>, query: F) -> PyResult<T>
+where
+    T: Send,
+    F: Fn(&'static dyn backend::Backend) -> Result<T, Box<dyn std::error::Error>> + Send,
+{
+    py.allow_threads(|| backend::query_naming_backend(query))
+        .map_err(|(e, backend)| e.into_py_err(Some(backend)))
+}
+
+/// Like [without_gil], for the queries which always go to the given backend.
+fn on_backend<T, E, F>(py: Python<'_>, backend: &str, query: F) -> PyResult<T>
+where
+    T: Send,
+    E: Into<Box<dyn std::error::Error>>,
+    F: FnOnce() -> Result<T, E> + Send,
+{
+    py.allow_threads(|| query().map_err(|e| NetifacesError::from_boxed(e.into())))
+        .map_err(|e| e.into_py_err(Some(backend)))
+}
+
+#[pyfunction]
+fn _ip_to_string(ip: u32) -> String {
+    crate::ip_to_string(ip)
+}
+
+#[pyfunction]
+fn _interfaces(
+    py: Python<'_>,
+    interface_display: i32,
+    filter: InterfaceFilter,
+) -> PyResult<Vec<String>> {
+    let interface_display = InterfaceDisplay::try_from(interface_display)?;
+
+    without_gil(py, |backend| backend.interfaces(interface_display, &filter))
+}
+
+#[pyfunction]
+fn _interfaces_by_index(
+    py: Python<'_>,
+    interface_display: i32,
+    filter: InterfaceFilter,
+) -> PyResult<types::IfacesByIndex> {
+    let interface_display = InterfaceDisplay::try_from(interface_display)?;
+
+    without_gil(py, |backend| {
+        backend.interfaces_by_index(interface_display, &filter)
+    })
+}
+
+#[pyfunction]
+fn _if_nametoindex(py: Python<'_>, if_name: &str) -> PyResult<u32> {
+    without_gil(py, |backend| backend.if_nametoindex(if_name))
+}
+
+#[pyfunction]
+fn _if_indextoname(py: Python<'_>, if_index: u32, interface_display: i32) -> PyResult<String> {
+    let interface_display = InterfaceDisplay::try_from(interface_display)?;
+
+    without_gil(py, |backend| {
+        backend.if_indextoname(if_index, interface_display)
+    })
+}
+
+#[pyfunction]
+fn _ifaddresses(py: Python<'_>, iface: InterfaceRef) -> PyResult<types::IfAddrs> {
+    without_gil(py, |backend| backend.ifaddresses(&iface)).map(if_addrs)
+}
+
+#[pyfunction]
+fn _packed_addresses(py: Python<'_>, iface: InterfaceRef) -> PyResult<PackedAddrs> {
+    without_gil(py, |backend| backend.ifaddresses(&iface)).map(packed_addrs)
+}
+
+#[pyfunction]
+fn _interface_is_up(py: Python<'_>, iface: InterfaceRef) -> PyResult<bool> {
+    without_gil(py, |backend| backend.interface_is_up(&iface))
+}
+
+/// An address of an interface, in binary form: the bytes of the address as they are on
+/// the wire (4 or 16 for IP addresses, usually 6 for hardware addresses).
+struct PackedEntry {
+    address: Vec<u8>,
+    prefix_len: Option<u8>,
+    broadcast: Option<Vec<u8>>,
+    peer: Option<Vec<u8>>,
+}
+
+/// The addresses of an interface in binary form, by family.
+type PackedAddrs = HashMap<i32, Vec<PackedEntry>>;
+
+fn packed_addrs(addresses: api::InterfaceAddresses) -> PackedAddrs {
+    let mut packed = PackedAddrs::new();
+
+    if let Some(link_address) = addresses.link_address {
+        packed
+            .entry(types::AF_PACKET.into())
+            .or_default()
+            .push(PackedEntry {
+                address: link_address.address.0,
+                prefix_len: None,
+                broadcast: link_address.broadcast.map(|broadcast| broadcast.0),
+                peer: link_address.peer.map(|peer| peer.0),
+            });
+    }
+    for address in addresses.addresses {
+        packed
+            .entry(address.family().into())
+            .or_default()
+            .push(PackedEntry {
+                address: types::ip_bytes(&address.ip),
+                prefix_len: address.prefix_len,
+                broadcast: address.broadcast.as_ref().map(types::ip_bytes),
+                peer: address.peer.as_ref().map(types::ip_bytes),
+            });
+    }
+
+    packed
+}
+
+/// The addresses of an interface, shaped like the result of `ifaddresses`.
+fn if_addrs(addresses: api::InterfaceAddresses) -> types::IfAddrs {
+    objects::if_addrs(addresses.link_address.as_ref(), &addresses.addresses)
+}
+
+fn ip_string(ip: Option<IpAddr>) -> Option<String> {
+    ip.map(|ip| ip.to_string())
+}
+
+impl<'py> IntoPyObject<'py> for PackedEntry {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Self::Output> {
+        let dict = PyDict::new(py);
+        dict.set_item(types::ADDR_ADDR, PyBytes::new(py, &self.address))?;
+        if let Some(prefix_len) = self.prefix_len {
+            dict.set_item("prefixlen", prefix_len)?;
+        }
+        if let Some(broadcast) = self.broadcast {
+            dict.set_item(types::BROADCAST_ADDR, PyBytes::new(py, &broadcast))?;
+        }
+        if let Some(peer) = self.peer {
+            dict.set_item(types::PEER_ADDR, PyBytes::new(py, &peer))?;
+        }
+        Ok(dict)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for api::Interface {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Self::Output> {
+        let dict = PyDict::new(py);
+        dict.set_item("name", &self.name)?;
+        dict.set_item("index", self.index)?;
+        dict.set_item("flags", self.flags)?;
+        dict.set_item("up", self.up)?;
+        dict.set_item("mtu", self.mtu)?;
+        dict.set_item(
+            "addresses",
+            objects::if_addrs(self.link_address.as_ref(), &self.addresses),
+        )?;
+        Ok(dict)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for api::Route {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Self::Output> {
+        let destination = match self.is_default() {
+            true => None,
+            false => Some(self.destination.addr.to_string()),
+        };
+
+        let dict = PyDict::new(py);
+        dict.set_item("family", self.family())?;
+        dict.set_item("destination", destination)?;
+        dict.set_item("prefixlen", self.destination.prefix_len)?;
+        dict.set_item("gateway", ip_string(self.gateway))?;
+        dict.set_item("interface", self.interface)?;
+        dict.set_item("index", self.index)?;
+        dict.set_item("source", ip_string(self.source))?;
+        dict.set_item("metric", self.metric)?;
+        dict.set_item("table", self.table)?;
+        dict.set_item("protocol", self.protocol)?;
+        dict.set_item("scope", self.scope)?;
+        dict.set_item("type", self.route_type)?;
+        dict.set_item("nexthops", self.nexthops)?;
+        dict.set_item("nhid", self.nexthop_id)?;
+        Ok(dict)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for types::ProcRouteEntry {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Self::Output> {
+        let dict = PyDict::new(py);
+        dict.set_item("family", self.family)?;
+        dict.set_item("destination", self.destination)?;
+        dict.set_item("prefixlen", self.prefix_len)?;
+        dict.set_item("gateway", self.gateway)?;
+        dict.set_item("interface", self.interface)?;
+        dict.set_item("source", self.source)?;
+        dict.set_item("source_prefixlen", self.source_prefix_len)?;
+        dict.set_item("flags", self.flags)?;
+        dict.set_item("refcnt", self.refcnt)?;
+        dict.set_item("use", self.use_count)?;
+        dict.set_item("metric", self.metric)?;
+        dict.set_item("mtu", self.mtu)?;
+        dict.set_item("window", self.window)?;
+        dict.set_item("irtt", self.irtt)?;
+        Ok(dict)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for api::NextHop {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Self::Output> {
+        let dict = PyDict::new(py);
+        dict.set_item("gateway", ip_string(self.gateway))?;
+        dict.set_item("interface", self.interface)?;
+        dict.set_item("index", self.index)?;
+        dict.set_item("weight", self.weight)?;
+        dict.set_item("flags", self.flags)?;
+        Ok(dict)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for api::AddressOwner {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Self::Output> {
+        let dict = PyDict::new(py);
+        dict.set_item("interface", self.interface)?;
+        dict.set_item("index", self.index)?;
+        dict.set_item("family", self.address.family())?;
+        dict.set_item("prefixlen", self.address.prefix_len)?;
+        dict.set_item("address", objects::address_pairs(&self.address))?;
+        Ok(dict)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for api::Neighbour {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Self::Output> {
+        let dict = PyDict::new(py);
+        dict.set_item("family", self.family)?;
+        dict.set_item("addr", ip_string(self.ip))?;
+        dict.set_item(
+            "lladdr",
+            self.hardware_address.map(|address| address.to_string()),
+        )?;
+        dict.set_item("interface", self.interface)?;
+        dict.set_item("index", self.index)?;
+        dict.set_item("state", self.state)?;
+        dict.set_item("flags", self.flags)?;
+        Ok(dict)
+    }
+}
+
+/// A netlink socket kept open across queries, for callers that query the system
+/// at a high frequency. Threads sharing a session take turns using its socket.
+#[cfg(target_os = "linux")]
+#[pyclass]
+struct _NetlinkSession {
+    session: Mutex<session::Session>,
+}
+
+#[cfg(target_os = "linux")]
+impl _NetlinkSession {
+    /// The session, for the calling thread to use. A thread panicking while using it
+    /// leaves at worst unread replies, which the next query skips.
+    fn session(&self) -> MutexGuard<'_, session::Session> {
+        self.session.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[pymethods]
+impl _NetlinkSession {
+    #[new]
+    fn new(py: Python<'_>) -> PyResult<Self> {
+        let session = on_backend(py, "netlink", session::Session::new)?;
+
+        Ok(_NetlinkSession {
+            session: Mutex::new(session),
+        })
+    }
+
+    fn interfaces(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        on_backend(py, "netlink", || self.session().interfaces())
+    }
+
+    fn ifaddresses(&self, py: Python<'_>, iface: InterfaceRef) -> PyResult<types::IfAddrs> {
+        on_backend(py, "netlink", || self.session().ifaddresses(&iface)).map(if_addrs)
+    }
+
+    fn interface_is_up(&self, py: Python<'_>, iface: InterfaceRef) -> PyResult<bool> {
+        on_backend(py, "netlink", || self.session().interface_is_up(&iface))
+    }
+
+    fn routes(&self, py: Python<'_>, family: u8) -> PyResult<Vec<api::Route>> {
+        on_backend(py, "netlink", || self.session().routes(family))
+    }
+
+    fn interface_routes(
+        &self,
+        py: Python<'_>,
+        iface: InterfaceRef,
+        family: u8,
+    ) -> PyResult<Vec<api::Route>> {
+        on_backend(py, "netlink", || {
+            self.session().interface_routes(&iface, family)
+        })
+    }
+
+    fn neighbours(&self, py: Python<'_>, family: u8) -> PyResult<Vec<api::Neighbour>> {
+        on_backend(py, "netlink", || self.session().neighbours(family))
+    }
+
+    fn stats(&self, py: Python<'_>) -> PyResult<HashMap<String, types::IfStats>> {
+        on_backend(py, "netlink", || self.session().stats())
+    }
+}
+
+#[pyfunction]
+fn _interface_routes(py: Python<'_>, iface: InterfaceRef, family: u8) -> PyResult<Vec<api::Route>> {
+    without_gil(py, |backend| backend.interface_routes(&iface, family))
+}
+
+#[pyfunction]
+fn _interface_for_address(py: Python<'_>, ip: &str) -> PyResult<Option<api::AddressOwner>> {
+    without_gil(py, |backend| backend.interface_for_address(ip))
+}
+
+#[pyfunction]
+fn _interfaces_in_network(py: Python<'_>, network: &str) -> PyResult<Vec<api::AddressOwner>> {
+    without_gil(py, |backend| backend.interfaces_in_network(network))
+}
+
+#[pyfunction]
+#[pyo3(signature = (destination, source, oif, mark))]
+fn _route_get(
+    py: Python<'_>,
+    destination: &str,
+    source: Option<&str>,
+    oif: Option<InterfaceRef>,
+    mark: Option<u32>,
+) -> PyResult<Option<api::Route>> {
+    without_gil(py, |backend| {
+        backend.route_get(destination, source, oif.as_ref(), mark)
+    })
+}
+
+#[pyfunction]
+fn _routes(py: Python<'_>, family: u8) -> PyResult<Vec<objects::Route>> {
+    let routes = without_gil(py, |backend| backend.routes(family))?;
+
+    Ok(routes.into_iter().map(objects::Route::from).collect())
+}
+
+#[pyfunction]
+fn _neighbours(py: Python<'_>, family: u8) -> PyResult<Vec<api::Neighbour>> {
+    without_gil(py, |backend| backend.neighbours(family))
+}
+
+#[pyfunction]
+fn _proc_routes(py: Python<'_>, family: u8) -> PyResult<Vec<types::ProcRouteEntry>> {
+    #[cfg(target_os = "linux")]
+    {
+        on_backend(py, "procfs", || procfs::procfs_routes(family))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (py, family);
+        Err(unsupported(
+            "The /proc route files are only supported on Linux",
+        ))
+    }
+}
+
+#[pyfunction]
+fn _compat_interfaces(py: Python<'_>) -> PyResult<Vec<String>> {
+    #[cfg(target_os = "linux")]
+    {
+        on_backend(py, "getifaddrs", compat::compat_interfaces)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = py;
+        Err(unsupported(
+            "The netifaces 1 compatibility mode is only supported on Linux",
+        ))
+    }
+}
+
+/// The addresses of an interface by family, each a list of (key, value) pairs
+type LegacyAddrs = Vec<(i32, Vec<Vec<(&'static str, String)>>)>;
+
+#[pyfunction]
+fn _compat_ifaddresses(py: Python<'_>, if_name: &str) -> PyResult<Option<LegacyAddrs>> {
+    #[cfg(target_os = "linux")]
+    {
+        on_backend(py, "getifaddrs", || compat::compat_ifaddresses(if_name))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (py, if_name);
+        Err(unsupported(
+            "The netifaces 1 compatibility mode is only supported on Linux",
+        ))
+    }
+}
+
+#[pyfunction]
+fn _default_routes(py: Python<'_>, family: u8) -> PyResult<Vec<api::Route>> {
+    without_gil(py, |backend| backend.default_routes(family))
+}
+
+#[pyfunction]
+fn _primary_interface(py: Python<'_>, family: u8) -> PyResult<Option<api::Route>> {
+    without_gil(py, |backend| backend.primary_interface(family))
+}
+
+#[pyfunction]
+fn _enable_cache(py: Python<'_>) -> PyResult<()> {
+    #[cfg(target_os = "linux")]
+    {
+        without_gil(py, |backend| backend.enable_cache())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = py;
+        Err(unsupported("The cache is only supported on Linux"))
+    }
+}
+
+#[pyfunction]
+fn _disable_cache(py: Python<'_>) {
+    #[cfg(target_os = "linux")]
+    py.allow_threads(cache::disable);
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = py;
+}
+
+// The _cached_* functions return None when the cache is disabled, or when another
+// backend than the netlink one feeding it is selected, in which case the caller falls
+// back to querying the system. Results come along with the generation of the cache
+// they were read from.
+
+#[cfg(target_os = "linux")]
+fn cache_answers() -> bool {
+    backend::backend() == "netlink"
+}
+
+#[pyfunction]
+fn _cache_generation(py: Python<'_>) -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    return py.allow_threads(cache::generation);
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = py;
+        None
+    }
+}
+
+#[pyfunction]
+fn _cached_interfaces(
+    py: Python<'_>,
+    interface_display: i32,
+    filter: InterfaceFilter,
+) -> PyResult<Option<(u64, Vec<String>)>> {
+    let interface_display = InterfaceDisplay::try_from(interface_display)?;
+
+    #[cfg(target_os = "linux")]
+    return match cache_answers() {
+        true => on_backend(py, "netlink", || {
+            cache::interfaces(interface_display, &filter).transpose()
+        }),
+        false => Ok(None),
+    };
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (py, interface_display, filter);
+        Ok(None)
+    }
+}
+
+#[pyfunction]
+fn _cached_interfaces_by_index(
+    py: Python<'_>,
+    interface_display: i32,
+    filter: InterfaceFilter,
+) -> PyResult<Option<(u64, types::IfacesByIndex)>> {
+    let interface_display = InterfaceDisplay::try_from(interface_display)?;
+
+    #[cfg(target_os = "linux")]
+    return match cache_answers() {
+        true => on_backend(py, "netlink", || {
+            cache::interfaces_by_index(interface_display, &filter).transpose()
+        }),
+        false => Ok(None),
+    };
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (py, interface_display, filter);
+        Ok(None)
+    }
+}
+
+#[pyfunction]
+fn _cached_ifaddresses(
+    py: Python<'_>,
+    iface: InterfaceRef,
+) -> PyResult<Option<(u64, types::IfAddrs)>> {
+    #[cfg(target_os = "linux")]
+    return match cache_answers() {
+        true => on_backend(py, "netlink", || cache::ifaddresses(&iface).transpose())
+            .map(|cached| cached.map(|(generation, addresses)| (generation, if_addrs(addresses)))),
+        false => Ok(None),
+    };
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (py, iface);
+        Ok(None)
+    }
+}
+
+#[pyfunction]
+fn _cached_routes(py: Python<'_>) -> Option<(u64, Vec<api::Route>)> {
+    #[cfg(target_os = "linux")]
+    return match cache_answers() {
+        true => py.allow_threads(cache::routes),
+        false => None,
+    };
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = py;
+        None
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (name))]
+fn _set_backend(name: Option<&str>) -> PyResult<()> {
+    Ok(backend::set_backend(name)?)
+}
+
+#[pyfunction]
+fn _backend() -> &'static str {
+    backend::backend()
+}
+
+#[pyfunction]
+fn _backends() -> Vec<&'static str> {
+    backend::backends()
+}
+
+/// Answer the queries with a synthetic host, described by `netifaces.testing`.
+#[pyfunction]
+fn _load_mock_host(host: &Bound<'_, PyAny>) -> PyResult<()> {
+    mock::load(&host.extract()?).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    backend::set_override(Some(&backend::Mock));
+
+    Ok(())
+}
+
+/// Go back to the real host and to the selected backend.
+#[pyfunction]
+fn _unload_mock_host() {
+    backend::set_override(None);
+    mock::unload();
+}
+
+#[pyfunction]
+fn _all_interfaces(py: Python<'_>, interface_display: i32) -> PyResult<Vec<objects::Interface>> {
+    let interface_display = InterfaceDisplay::try_from(interface_display)?;
+    let snapshot = without_gil(py, |backend| backend.snapshot(interface_display))?;
+
+    Ok(snapshot.into_iter().map(objects::Interface::from).collect())
+}
+
+#[pyfunction]
+fn _get_interface(
+    py: Python<'_>,
+    iface: InterfaceRef,
+    interface_display: i32,
+) -> PyResult<objects::Interface> {
+    let interface_display = InterfaceDisplay::try_from(interface_display)?;
+    let interface = without_gil(py, |backend| backend.interface(&iface, interface_display))?;
+
+    Ok(objects::Interface::from(interface))
+}
+
+/// How long a wait runs without the GIL before checking for pending signals
+/// (e.g. a KeyboardInterrupt).
+#[cfg(target_os = "linux")]
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// When a wait of `timeout` seconds ends; None if it never does, which is also what an
+/// infinite timeout, or one too long to be represented, means. A negative timeout only
+/// checks the current state, and NaN is rejected.
+fn wait_deadline(timeout: Option<f64>) -> PyResult<Option<Instant>> {
+    let timeout = match timeout {
+        Some(timeout) if timeout.is_nan() => {
+            return Err(PyValueError::new_err("The timeout cannot be NaN"))
+        }
+        Some(timeout) => timeout.max(0.0),
+        None => return Ok(None),
+    };
+
+    Ok(Duration::try_from_secs_f64(timeout)
+        .ok()
+        .and_then(|timeout| Instant::now().checked_add(timeout)))
+}
+
+/// Run a [Waiter] until it is done, without holding the GIL while it blocks.
+#[cfg(target_os = "linux")]
+fn run_waiter(py: Python, mut waiter: Waiter, deadline: Option<Instant>) -> PyResult<bool> {
+    loop {
+        let slice = match deadline {
+            Some(deadline) => deadline
+                .saturating_duration_since(Instant::now())
+                .min(SIGNAL_CHECK_INTERVAL),
+            None => SIGNAL_CHECK_INTERVAL,
+        };
+
+        let reached = on_backend(py, "netlink", || waiter.wait(Some(slice)))?;
+
+        if reached {
+            return Ok(true);
+        }
+
+        py.check_signals()?;
+
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Ok(false);
+        }
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (iface, condition, timeout))]
+fn _wait_for_interface(
+    py: Python,
+    iface: InterfaceRef,
+    condition: i32,
+    timeout: Option<f64>,
+) -> PyResult<bool> {
+    let condition = WaitCondition::try_from(condition)?;
+    let deadline = wait_deadline(timeout)?;
+
+    #[cfg(target_os = "linux")]
+    {
+        let target = WaitTarget::Interface(iface, condition);
+        let waiter = without_gil(py, |backend| backend.waiter(target.clone()))?;
+
+        run_waiter(py, waiter, deadline)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (py, iface, condition, deadline);
+        Err(unsupported(
+            "Waiting on interfaces is only supported on Linux",
+        ))
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (family, timeout))]
+fn _wait_for_network_online(py: Python, family: u8, timeout: Option<f64>) -> PyResult<bool> {
+    let deadline = wait_deadline(timeout)?;
+
+    #[cfg(target_os = "linux")]
+    {
+        let target = WaitTarget::NetworkOnline(family);
+        let waiter = without_gil(py, |backend| backend.waiter(target.clone()))?;
+
+        run_waiter(py, waiter, deadline)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (py, family, deadline);
+        Err(unsupported(
+            "Waiting on the network is only supported on Linux",
+        ))
+    }
+}
+
+#[pymodule(gil_used = false)]
+fn netifaces(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(_interfaces, m)?)?;
+    m.add_function(wrap_pyfunction!(_interfaces_by_index, m)?)?;
+    m.add_function(wrap_pyfunction!(_if_nametoindex, m)?)?;
+    m.add_function(wrap_pyfunction!(_if_indextoname, m)?)?;
+    m.add_function(wrap_pyfunction!(_ifaddresses, m)?)?;
+    m.add_function(wrap_pyfunction!(_packed_addresses, m)?)?;
+    m.add_function(wrap_pyfunction!(_ip_to_string, m)?)?;
+    m.add_function(wrap_pyfunction!(_interface_is_up, m)?)?;
+    m.add_function(wrap_pyfunction!(_interface_routes, m)?)?;
+    m.add_function(wrap_pyfunction!(_set_backend, m)?)?;
+    m.add_function(wrap_pyfunction!(_backend, m)?)?;
+    m.add_function(wrap_pyfunction!(_backends, m)?)?;
+    m.add_function(wrap_pyfunction!(_load_mock_host, m)?)?;
+    m.add_function(wrap_pyfunction!(_unload_mock_host, m)?)?;
+    m.add_function(wrap_pyfunction!(_all_interfaces, m)?)?;
+    m.add_function(wrap_pyfunction!(_get_interface, m)?)?;
+    m.add_function(wrap_pyfunction!(_interface_for_address, m)?)?;
+    m.add_function(wrap_pyfunction!(_interfaces_in_network, m)?)?;
+    m.add_function(wrap_pyfunction!(_route_get, m)?)?;
+    m.add_function(wrap_pyfunction!(_routes, m)?)?;
+    m.add_function(wrap_pyfunction!(_proc_routes, m)?)?;
+    m.add_function(wrap_pyfunction!(_neighbours, m)?)?;
+    m.add_function(wrap_pyfunction!(_compat_interfaces, m)?)?;
+    m.add_function(wrap_pyfunction!(_compat_ifaddresses, m)?)?;
+    m.add_function(wrap_pyfunction!(_default_routes, m)?)?;
+    m.add_function(wrap_pyfunction!(_primary_interface, m)?)?;
+    m.add_function(wrap_pyfunction!(_enable_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(_disable_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(_cache_generation, m)?)?;
+    m.add_function(wrap_pyfunction!(_cached_interfaces, m)?)?;
+    m.add_function(wrap_pyfunction!(_cached_interfaces_by_index, m)?)?;
+    m.add_function(wrap_pyfunction!(_cached_ifaddresses, m)?)?;
+    m.add_function(wrap_pyfunction!(_cached_routes, m)?)?;
+    m.add_function(wrap_pyfunction!(_wait_for_interface, m)?)?;
+    m.add_function(wrap_pyfunction!(_wait_for_network_online, m)?)?;
+    m.add_class::<objects::Interface>()?;
+    m.add_class::<objects::Address>()?;
+    m.add_class::<objects::Route>()?;
+    m.add_class::<objects::NextHop>()?;
+    #[cfg(target_os = "linux")]
+    m.add_class::<_NetlinkSession>()?;
+    Ok(())
+}
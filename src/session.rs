@@ -0,0 +1,237 @@
+//! A netlink session, which keeps its socket (and buffers) open across queries.
+//!
+//! Every other function of netifaces sets up and tears down its own resources, which
+//! dominates the cost of a query when it is repeated many times per second.
+
+use crate::api;
+use crate::common::InterfaceRef;
+use crate::linux_netlink::{
+    find_link, interface_addresses, ip_routes, link_names, link_stats, neighbour, route_names,
+    LinkSource,
+};
+use crate::netlink::{Address, Link, NetlinkSocket};
+use crate::types::IfStats;
+use std::collections::HashMap;
+use std::io;
+
+/// Whether an error means the socket itself got into a bad state, in which case
+/// a fresh one will do better.
+///
+/// Replies left over from an earlier request are told apart by their sequence number
+/// and skipped, they need no recovery. Neither does a dump that kept being interrupted
+/// by changes to the system, which a fresh socket would not avoid.
+fn is_recoverable(err: &io::Error) -> bool {
+    matches!(
+        err.raw_os_error(),
+        // The kernel dropped messages because the receive queue was full, or a
+        // previous dump on this socket never completed
+        Some(libc::ENOBUFS | libc::EBUSY)
+    )
+}
+
+pub struct Session {
+    sock: NetlinkSocket,
+}
+
+impl Session {
+    pub fn new() -> io::Result<Self> {
+        Ok(Session {
+            sock: NetlinkSocket::new()?,
+        })
+    }
+
+    /// Run a query on the session's socket. If the socket got into a bad state, it is
+    /// replaced by a new one and the query is run again.
+    fn query<T>(&mut self, query: impl Fn(&mut NetlinkSocket) -> io::Result<T>) -> io::Result<T> {
+        match query(&mut self.sock) {
+            Err(e) if is_recoverable(&e) => {
+                self.sock = NetlinkSocket::new()?;
+                query(&mut self.sock)
+            }
+            result => result,
+        }
+    }
+
+    /// The names of all the interfaces, sorted by index.
+    pub fn interfaces(&mut self) -> io::Result<Vec<String>> {
+        let mut links = self.query(|sock| sock.links())?;
+        links.sort_by_key(|link| link.index);
+
+        Ok(links.into_iter().map(|link| link.name).collect())
+    }
+
+    pub fn ifaddresses(
+        &mut self,
+        iface: &InterfaceRef,
+    ) -> Result<api::InterfaceAddresses, Box<dyn std::error::Error>> {
+        interface_addresses(self, iface)
+    }
+
+    pub fn interface_is_up(
+        &mut self,
+        iface: &InterfaceRef,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        match find_link(self, iface)? {
+            Some(link) => Ok(link.is_running()),
+            None => Err(Box::new(iface.not_found())),
+        }
+    }
+
+    /// All the routes of the given family (AF_UNSPEC for all of them), from every table.
+    pub fn routes(&mut self, family: u8) -> io::Result<Vec<api::Route>> {
+        let names = link_names(&self.query(|sock| sock.links())?);
+        let routes = self.query(|sock| sock.routes(family))?;
+
+        Ok(ip_routes(&routes, &names))
+    }
+
+    /// The routes of the given family, from every table, that go out through one interface.
+    pub fn interface_routes(
+        &mut self,
+        iface: &InterfaceRef,
+        family: u8,
+    ) -> Result<Vec<api::Route>, Box<dyn std::error::Error>> {
+        let link = match find_link(self, iface)? {
+            Some(link) => link,
+            None => return Err(Box::new(iface.not_found())),
+        };
+        let mut names = HashMap::from([(link.index, link.name.clone())]);
+        let routes = self.query(|sock| sock.routes_via(link.index, family))?;
+        // The other nexthops of multipath routes go out through other interfaces
+        let others = self.query(|sock| route_names(sock, &routes, &names))?;
+        names.extend(others);
+
+        Ok(ip_routes(&routes, &names))
+    }
+
+    /// The neighbour tables of the given family (AF_UNSPEC for all of them).
+    pub fn neighbours(&mut self, family: u8) -> io::Result<Vec<api::Neighbour>> {
+        let names = link_names(&self.query(|sock| sock.links())?);
+        let neighbours = self.query(|sock| sock.neighbours(family))?;
+
+        Ok(neighbours
+            .iter()
+            .map(|entry| neighbour(entry, &names))
+            .collect())
+    }
+
+    /// The counters of every interface, by interface name.
+    pub fn stats(&mut self) -> io::Result<HashMap<String, IfStats>> {
+        let links = self.query(|sock| sock.links())?;
+
+        Ok(links
+            .iter()
+            .filter_map(|link| Some((link.name.clone(), link_stats(link)?)))
+            .collect())
+    }
+}
+
+impl LinkSource for Session {
+    type Link = Link;
+    type Address = Address;
+
+    fn link(&mut self, iface: &InterfaceRef) -> Result<Option<Link>, Box<dyn std::error::Error>> {
+        Ok(self.query(|sock| sock.link(iface))?)
+    }
+
+    fn links(&mut self) -> Result<Vec<Link>, Box<dyn std::error::Error>> {
+        Ok(self.query(|sock| sock.links())?)
+    }
+
+    fn addresses_on(
+        &mut self,
+        link: &Link,
+        family: u8,
+    ) -> Result<Vec<Address>, Box<dyn std::error::Error>> {
+        Ok(self.query(|sock| sock.addresses_on(link.index, family))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::netlink::{NLM_F_DUMP, NLM_F_REQUEST, RTM_GETLINK, RTM_GETROUTE};
+    use std::os::fd::AsRawFd;
+
+    #[test]
+    fn recover_from_dropped_replies() {
+        let mut session = Session::new().unwrap();
+        let expected = session.interfaces().unwrap();
+
+        // Shrink the receive queue to its minimum, then ask for more replies than it
+        // holds without reading them: the kernel drops some and reports ENOBUFS
+        let fd = session.sock.as_raw_fd();
+        let size: libc::c_int = 0;
+        let mut request = Vec::new();
+        request.extend_from_slice(&32_u32.to_ne_bytes());
+        request.extend_from_slice(&RTM_GETLINK.to_ne_bytes());
+        request.extend_from_slice(&NLM_F_REQUEST.to_ne_bytes());
+        request.extend_from_slice(&[0; 8]);
+        // An ifinfomsg asking for the link of index 1, the loopback
+        request.extend_from_slice(&[0, 0, 0, 0, 1, 0, 0, 0]);
+        request.extend_from_slice(&[0; 8]);
+        unsafe {
+            libc::setsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_RCVBUF,
+                &size as *const libc::c_int as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            );
+            for _ in 0..64 {
+                libc::send(
+                    fd,
+                    request.as_ptr() as *const libc::c_void,
+                    request.len(),
+                    0,
+                );
+            }
+        }
+
+        assert_eq!(session.interfaces().unwrap(), expected);
+    }
+
+    #[test]
+    fn skip_the_replies_of_an_unfinished_dump() {
+        let mut session = Session::new().unwrap();
+        let lo = InterfaceRef::Index(1);
+        let expected = (
+            session.interfaces().unwrap(),
+            session.ifaddresses(&lo).unwrap(),
+        );
+
+        // Ask for a dump of the routes without reading any of its replies, as a caller
+        // giving up halfway through would. Sequence number 0 is never used by the session.
+        let fd = session.sock.as_raw_fd();
+        let mut request = Vec::new();
+        request.extend_from_slice(&28_u32.to_ne_bytes());
+        request.extend_from_slice(&RTM_GETROUTE.to_ne_bytes());
+        request.extend_from_slice(&(NLM_F_REQUEST | NLM_F_DUMP).to_ne_bytes());
+        request.extend_from_slice(&[0; 8]);
+        // An rtmsg asking for the routes of every family
+        request.extend_from_slice(&[0; 12]);
+        let send_request = || unsafe {
+            libc::send(
+                fd,
+                request.as_ptr() as *const libc::c_void,
+                request.len(),
+                0,
+            );
+        };
+
+        // A request answered with a single reply, then dumps
+        send_request();
+        assert!(session.interface_is_up(&lo).is_ok());
+        assert_eq!(session.ifaddresses(&lo).unwrap(), expected.1);
+        send_request();
+        assert_eq!(session.interfaces().unwrap(), expected.0);
+    }
+
+    #[test]
+    fn only_a_bad_socket_is_recoverable() {
+        assert!(is_recoverable(&io::Error::from_raw_os_error(libc::ENOBUFS)));
+        assert!(is_recoverable(&io::Error::from_raw_os_error(libc::EBUSY)));
+        assert!(!is_recoverable(&io::Error::from_raw_os_error(libc::EPERM)));
+        assert!(!is_recoverable(&io::Error::other("interrupted dump")));
+    }
+}
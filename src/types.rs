@@ -1,9 +1,66 @@
 #![allow(dead_code)]
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
 
 pub type AddrPairs = HashMap<String, String>;
 pub type IfAddrs = HashMap<i32, Vec<AddrPairs>>;
-pub type IfacesByIndex = HashMap<usize, String>;
+/// Sorted by index, so that the interfaces always come in the same order
+pub type IfacesByIndex = BTreeMap<u32, String>;
+
+/// The number of leading ones of a netmask, aligned on the most significant bit; None
+/// if there are ones after the first zero.
+fn contiguous_prefix(bits: u128) -> Option<u8> {
+    let prefix_len = bits.leading_ones();
+    (bits.checked_shl(prefix_len).unwrap_or(0) == 0).then_some(prefix_len as u8)
+}
+
+/// The bytes of an IP address, in network order.
+pub fn ip_bytes(ip: &IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets().to_vec(),
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+    }
+}
+
+/// The prefix length of a contiguous netmask of up to 16 bytes, in network order.
+pub fn mask_bytes_to_prefix(mask: &[u8]) -> Option<u8> {
+    if mask.is_empty() || mask.len() > 16 {
+        return None;
+    }
+
+    let bits = mask
+        .iter()
+        .fold(0u128, |bits, byte| (bits << 8) | u128::from(*byte));
+    contiguous_prefix(bits << (128 - 8 * mask.len()))
+}
+
+/// A route, as listed in /proc/net/route or /proc/net/ipv6_route.
+#[derive(Debug, Clone)]
+pub struct ProcRouteEntry {
+    pub family: u8,
+    /// The destination network; None for a default route
+    pub destination: Option<String>,
+    pub prefix_len: u8,
+    pub gateway: Option<String>,
+    pub interface: String,
+    /// The source network of an IPv6 source-specific route
+    pub source: Option<String>,
+    pub source_prefix_len: Option<u8>,
+    /// The RTF_* flags
+    pub flags: u32,
+    pub refcnt: u32,
+    pub use_count: u32,
+    pub metric: u32,
+    /// IPv4 only
+    pub mtu: Option<u32>,
+    /// IPv4 only
+    pub window: Option<u32>,
+    /// IPv4 only
+    pub irtt: Option<u32>,
+}
+
+/// The counters of an interface, by name.
+pub type IfStats = HashMap<String, u64>;
 
 pub const ADDR_ADDR: &str = "addr";
 pub const MASK_ADDR: &str = "mask";
@@ -59,3 +116,11 @@ pub const AF_SMC: u8 = 43;
 pub const AF_XDP: u8 = 44;
 pub const AF_MCTP: u8 = 45;
 pub const AF_MAX: u8 = 46;
+
+/// The routing table the routes go to unless told otherwise.
+pub const RT_TABLE_MAIN: u32 = 254;
+/// The type of the routes to a gateway or to a directly connected network.
+pub const RTN_UNICAST: u8 = 1;
+/// The flags of the paths of a route the kernel does not use.
+pub const RTNH_F_DEAD: u32 = 0x01;
+pub const RTNH_F_LINKDOWN: u32 = 0x10;
@@ -0,0 +1,171 @@
+//! Blocking helpers that wait for an interface, or the network as a whole, to come up.
+//!
+//! Nothing here sleeps and polls: a netlink socket subscribed to the link, address and
+//! route notifications wakes the waiter up, and the condition is only re-evaluated
+//! after the kernel reported a change.
+
+use crate::common::{InterfaceRef, WaitCondition};
+use crate::netlink::{
+    Address, Link, NetlinkSocket, Route, RTNLGRP_IPV4_IFADDR, RTNLGRP_IPV4_ROUTE,
+    RTNLGRP_IPV6_IFADDR, RTNLGRP_IPV6_ROUTE, RTNLGRP_LINK, RTN_UNICAST, RT_TABLE_LOCAL,
+};
+use crate::types::{AF_INET, AF_INET6, AF_UNSPEC};
+use crate::NetifacesError;
+use std::time::{Duration, Instant};
+
+/// What a [Waiter] is waiting for.
+#[derive(Clone)]
+pub enum WaitTarget {
+    /// The interface meeting the given condition
+    Interface(InterfaceRef, WaitCondition),
+    /// The host having a default route, through a running interface, and a usable
+    /// source address for it. `AF_UNSPEC` accepts either IPv4 or IPv6.
+    NetworkOnline(u8),
+}
+
+impl WaitTarget {
+    fn is_reached(&self, sock: &mut NetlinkSocket) -> Result<bool, Box<dyn std::error::Error>> {
+        match self {
+            WaitTarget::Interface(iface, condition) => interface_reached(sock, iface, condition),
+            WaitTarget::NetworkOnline(AF_UNSPEC) => {
+                Ok(family_online(sock, AF_INET)? || family_online(sock, AF_INET6)?)
+            }
+            WaitTarget::NetworkOnline(family) => family_online(sock, *family),
+        }
+    }
+}
+
+fn interface_reached(
+    sock: &mut NetlinkSocket,
+    iface: &InterfaceRef,
+    condition: &WaitCondition,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let link = match sock.link(iface)? {
+        Some(link) => link,
+        None => return Ok(false),
+    };
+
+    Ok(match condition {
+        WaitCondition::Present => true,
+        WaitCondition::Up => link.is_running(),
+        WaitCondition::HasAddress => sock
+            .addresses_on(link.index, AF_UNSPEC)?
+            .iter()
+            .any(|addr| addr.is_global() && addr.is_usable()),
+    })
+}
+
+fn family_online(sock: &mut NetlinkSocket, family: u8) -> Result<bool, Box<dyn std::error::Error>> {
+    let routes = sock.routes(family)?;
+    let mut defaults = routes
+        .iter()
+        .filter(|route| route.is_default() && route.kind == RTN_UNICAST)
+        .filter(|route| route.table != RT_TABLE_LOCAL)
+        .peekable();
+
+    if defaults.peek().is_none() {
+        return Ok(false);
+    }
+
+    let links = sock.links()?;
+    let addresses = sock.addresses(family)?;
+
+    // Any of the nexthops of a multipath route will do
+    let online = defaults.any(|route| {
+        route.nexthops.iter().any(|hop| {
+            hop.is_alive()
+                && hop
+                    .oif
+                    .is_some_and(|oif| source_available(&links, &addresses, route, oif))
+        })
+    });
+
+    Ok(online)
+}
+
+/// Whether the packets of a route going out through `oif` can get a source address.
+fn source_available(links: &[Link], addresses: &[Address], route: &Route, oif: u32) -> bool {
+    if !links
+        .iter()
+        .any(|link| link.index == oif && link.is_running())
+    {
+        return false;
+    }
+
+    // The kernel picks the route's preferred source if it has one (and it may
+    // live on another interface), otherwise a global address of the interface.
+    addresses.iter().any(|addr| {
+        addr.is_usable()
+            && match route.prefsrc {
+                Some(src) => addr.local_ip() == Some(src),
+                None => addr.index == oif && addr.is_global(),
+            }
+    })
+}
+
+/// Waits for a [WaitTarget] to be reached.
+///
+/// A waiter can be waited on several times; between two waits, the notifications
+/// are queued by the kernel so no change is missed.
+pub struct Waiter {
+    target: WaitTarget,
+    events: NetlinkSocket,
+    queries: NetlinkSocket,
+    stale: bool,
+}
+
+impl Waiter {
+    pub fn new(target: WaitTarget) -> Result<Self, Box<dyn std::error::Error>> {
+        if let WaitTarget::NetworkOnline(family) = target {
+            if ![AF_UNSPEC, AF_INET, AF_INET6].contains(&family) {
+                let err_msg = format!("Cannot wait for the network online in family {family}");
+                return Err(Box::new(NetifacesError::Other(err_msg)));
+            }
+        }
+
+        // Subscribe before the first check, so that a change happening between the
+        // check and the wait still wakes us up.
+        let events = NetlinkSocket::new()?;
+        events.subscribe(&[
+            RTNLGRP_LINK,
+            RTNLGRP_IPV4_IFADDR,
+            RTNLGRP_IPV6_IFADDR,
+            RTNLGRP_IPV4_ROUTE,
+            RTNLGRP_IPV6_ROUTE,
+        ])?;
+
+        Ok(Waiter {
+            target,
+            events,
+            queries: NetlinkSocket::new()?,
+            stale: true,
+        })
+    }
+
+    /// Block until the target is reached, or the timeout expires.
+    ///
+    /// Returns whether the target was reached. An interrupting signal ends the wait
+    /// early, so the caller gets a chance to handle it.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<bool, Box<dyn std::error::Error>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            if self.stale {
+                if self.target.is_reached(&mut self.queries)? {
+                    return Ok(true);
+                }
+                self.stale = false;
+            }
+
+            let remaining =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+
+            if self.events.wait_readable(remaining)? {
+                self.events.drain()?;
+                self.stale = true;
+            } else {
+                return Ok(false);
+            }
+        }
+    }
+}
@@ -1,9 +1,11 @@
 #![allow(dead_code)]
-use crate::common::InterfaceDisplay;
+use crate::api::{Address, HardwareAddress, Interface, InterfaceAddresses, LinkAddress};
+use crate::common::{
+    FilterMatcher, InterfaceDisplay, InterfaceFacts, InterfaceFilter, InterfaceRef,
+};
 use crate::types;
-use crate::types::{IfAddrs, ADDR_ADDR, AF_INET, AF_INET6, AF_PACKET};
+use crate::types::{AF_INET, AF_INET6, AF_PACKET};
 use crate::NetifacesError;
-use std::collections::HashMap;
 use std::error::Error;
 use std::net::IpAddr;
 
@@ -12,97 +14,140 @@ use windows::core::HSTRING;
 use windows::Win32::NetworkManagement::IpHelper::GetAdapterIndex;
 
 use get_adapters_addresses;
-use get_adapters_addresses::{Adapter, PhysicalAddress};
-
-fn ifaddresses_ip(
-    adapter: &Adapter,
-    if_addrs: &mut IfAddrs,
-) -> Result<(), Box<dyn std::error::Error>> {
-    for unicast_addr in adapter.unicast_addresses() {
-        // Create entries based on type
-        let entry = match unicast_addr {
-            IpAddr::V4(_) => if_addrs.entry(AF_INET.into()),
-            IpAddr::V6(_) => if_addrs.entry(AF_INET6.into()),
-        };
-        let addr_vec = entry.or_default();
-
-        let addr_string = format!("{}", unicast_addr);
-
-        // TODO currently get_adapters_addresses does not provide access to the
-        // prefix length :(, so we cannot report the netmask or broadcast address in the data on Windows.
-        // Per here: https://stackoverflow.com/a/64358443/7083698
-        // this information is available in the IP_ADAPTER_UNICAST_ADDRESS_LH.OnLinkPrefixLength
-        // struct field from Windows.
-
-        addr_vec.push(HashMap::from([(ADDR_ADDR.to_string(), addr_string)]));
-    }
+use get_adapters_addresses::Adapter;
+
+/// The addresses of an adapter.
+fn addresses_of(adapter: &Adapter) -> Result<InterfaceAddresses, Box<dyn std::error::Error>> {
+    // TODO currently get_adapters_addresses does not provide access to the
+    // prefix length :(, so we cannot report the netmask or broadcast address in the data on Windows.
+    // Per here: https://stackoverflow.com/a/64358443/7083698
+    // this information is available in the IP_ADAPTER_UNICAST_ADDRESS_LH.OnLinkPrefixLength
+    // struct field from Windows.
+    let addresses = adapter
+        .unicast_addresses()
+        .into_iter()
+        .map(|unicast_addr| {
+            Address::new(unicast_addr.to_owned(), None).on_interface(adapter_index(adapter))
+        })
+        .collect();
 
-    Ok(())
+    // The get_adapters_addresses PhysicalAddress type does not give access to its
+    // bytes, only to their hyphenated form
+    let link_address = match adapter.physical_address() {
+        Some(phys_addr) => Some(LinkAddress {
+            address: HardwareAddress(
+                format!("{}", phys_addr)
+                    .split('-')
+                    .map(|byte| u8::from_str_radix(byte, 16))
+                    .collect::<Result<Vec<u8>, _>>()?,
+            ),
+            broadcast: None,
+            peer: None,
+        }),
+        None => None,
+    };
+
+    Ok(InterfaceAddresses {
+        link_address,
+        addresses,
+    })
 }
 
-fn ifaddresses_mac(
-    adapter: &Adapter,
-    if_addrs: &mut IfAddrs,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let phys_addr_option: Option<PhysicalAddress> = adapter.physical_address();
-
-    // If a MAC address was found for the interface, add it to the if addrs
-    match phys_addr_option {
-        Some(phys_addr) => {
-            let entry = if_addrs.entry(AF_PACKET as i32);
-            let macs = entry.or_default();
-
-            // Annoyingly, the get_adapters_addresses PhysicalAddress type does not implement
-            // array access to the bytes, and its string conversion does not produce the exact
-            // format that we need to be compatible with netifaces.  So we have to do a bit
-            // of string munging.
-            let mac_str_hyphens = format!("{}", phys_addr);
-            let mac_str = mac_str_hyphens.replace("-", ":").to_lowercase();
-
-            let m = HashMap::from([(ADDR_ADDR.to_string(), mac_str)]);
-            macs.push(m);
-        }
-        None => {}
+/// The name an adapter is displayed with.
+fn display_name(adapter: &Adapter, display: InterfaceDisplay) -> String {
+    match display {
+        InterfaceDisplay::HumanReadable => adapter.description().into_string().unwrap(),
+        InterfaceDisplay::MachineReadable => adapter.adapter_name(),
+        InterfaceDisplay::AltName => adapter.friendly_name().into_string().unwrap(),
+        InterfaceDisplay::Index => adapter_index(adapter).to_string(),
     }
-
-    Ok(())
 }
-/// Find a given adapter in the list of adapters.
-/// Returns error if the interface is not found
+
+/// Find a given adapter in the list of adapters, by any name it can be displayed with or by
+/// its index. Returns error if the interface is not found
 fn find_adapter<'a>(
     adapters: &'a get_adapters_addresses::AdaptersAddresses,
-    if_name: &str,
+    iface: &InterfaceRef,
 ) -> Result<Adapter<'a>, Box<NetifacesError>> {
-    // first find the interface, matching either the description or the name
+    let if_name = match iface {
+        InterfaceRef::Name(if_name) => if_name,
+        InterfaceRef::Index(if_index) => {
+            return adapters
+                .into_iter()
+                .find(|adapter| adapter_index(adapter) == *if_index)
+                .ok_or_else(|| Box::new(iface.not_found()));
+        }
+    };
+
+    // first find the interface, matching any of the names it can be displayed with
+    let displays = [
+        InterfaceDisplay::HumanReadable,
+        InterfaceDisplay::MachineReadable,
+        InterfaceDisplay::AltName,
+        InterfaceDisplay::Index,
+    ];
     let mut search_results: Vec<Adapter> = adapters
         .into_iter()
         .filter(|adapter| {
-            adapter.description().into_string().unwrap() == if_name
-                || adapter.adapter_name() == if_name
+            displays
+                .iter()
+                .any(|display| display_name(adapter, *display) == *if_name)
         })
         .collect();
 
     if search_results.len() > 1 {
-        let err_msg = format!("More than a single interface with description/name '{if_name}'");
-        return Err(Box::new(NetifacesError(err_msg)));
+        return Err(Box::new(NetifacesError::AmbiguousInterface(
+            if_name.to_string(),
+        )));
     }
 
     match search_results.get(0) {
         // Note: Adapter is not copyable/cloneable, so we cannot call search_results.get()
         // here.  Instead we have to use swap_remove.
         Some(_) => Ok(search_results.swap_remove(0)),
-        None => {
-            let err_msg = format!("Cannot find any interface with description or name '{if_name}'");
-            Err(Box::new(NetifacesError(err_msg)))
-        }
+        None => Err(Box::new(iface.not_found())),
     }
 }
 
-/// Given an interface name, returns all the addresses associated with that interface. The result
-/// is shaped loosely in a map.
-pub fn windows_ifaddresses(if_name: &str) -> Result<IfAddrs, Box<dyn std::error::Error>> {
-    let mut if_addrs: IfAddrs = HashMap::new();
+/// Look up the index of an interface by its description or name.
+pub fn windows_if_nametoindex(if_name: &str) -> Result<u32, Box<dyn std::error::Error>> {
+    let adapter_addresses = get_adapters_addresses::AdaptersAddresses::try_new(
+        get_adapters_addresses::Family::Unspec,
+        *get_adapters_addresses::Flags::default()
+            .skip_multicast()
+            .skip_dns_server()
+            .skip_anycast()
+            .skip_unicast(),
+    )?;
+
+    let interface = find_adapter(&adapter_addresses, &InterfaceRef::Name(if_name.to_string()))?;
+
+    Ok(adapter_index(&interface))
+}
+
+/// Look up the name of an interface by its index, displayed as requested.
+pub fn windows_if_indextoname(
+    if_index: u32,
+    display: InterfaceDisplay,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let adapter_addresses = get_adapters_addresses::AdaptersAddresses::try_new(
+        get_adapters_addresses::Family::Unspec,
+        *get_adapters_addresses::Flags::default()
+            .skip_multicast()
+            .skip_dns_server()
+            .skip_anycast()
+            .skip_unicast(),
+    )?;
 
+    let interface = find_adapter(&adapter_addresses, &InterfaceRef::Index(if_index))?;
+
+    Ok(display_name(&interface, display))
+}
+
+/// Given an interface name, returns all the addresses associated with that interface.
+pub fn windows_ifaddresses(
+    iface: &InterfaceRef,
+) -> Result<InterfaceAddresses, Box<dyn std::error::Error>> {
     let adapter_addresses = get_adapters_addresses::AdaptersAddresses::try_new(
         get_adapters_addresses::Family::Unspec,
         // Turn off stuff that we don't need in this call
@@ -112,42 +157,84 @@ pub fn windows_ifaddresses(if_name: &str) -> Result<IfAddrs, Box<dyn std::error:
             .skip_dns_server(),
     )?;
 
-    let interface = find_adapter(&adapter_addresses, if_name)?;
-
-    ifaddresses_ip(&interface, &mut if_addrs)?;
-    ifaddresses_mac(&interface, &mut if_addrs)?;
+    let interface = find_adapter(&adapter_addresses, iface)?;
 
-    Ok(if_addrs)
+    addresses_of(&interface)
 }
 
-/// List all the network interfaces available on the system.
+/// List all the network interfaces available on the system, sorted by index.
 ///
 /// # Params
 /// - `display`: an [InterfaceDisplay] that controls what ID is returned from the call to
 ///              identify the interface.
-pub fn windows_interfaces(display: InterfaceDisplay) -> Result<Vec<String>, Box<dyn Error>> {
-    let adapter_addresses = get_adapters_addresses::AdaptersAddresses::try_new(
-        get_adapters_addresses::Family::Unspec,
-        *get_adapters_addresses::Flags::default()
-            .skip_multicast()
-            .skip_dns_server(),
-    )?;
+/// - `filter`: the [InterfaceFilter] the listed interfaces have to pass.
+pub fn windows_interfaces(
+    display: InterfaceDisplay,
+    filter: &InterfaceFilter,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(windows_interfaces_by_index(display, filter)?
+        .into_values()
+        .collect())
+}
 
-    let mut ifaces: Vec<String> = Vec::new();
-    for adapter in &adapter_addresses {
-        ifaces.push(match display {
-            InterfaceDisplay::HumanReadable => adapter.description().into_string().unwrap(),
-            InterfaceDisplay::MachineReadable => adapter.adapter_name(),
-        });
+/// Look up the index of an adapter.
+fn adapter_index(adapter: &Adapter) -> u32 {
+    // Sadly get_adapters_addresses does not implement a getter for the
+    // interface index, so we have to use the Win32 function to look up the adapter
+    // index by its name.
+    // I did create a feature request for this: https://gitlab.com/cratesio/get_adapters_addresses/-/issues/1
+    // so hopefully someday it will be added and we can remove this code.
+    let adapter_name = adapter.adapter_name();
+    let mut index: u32 = 0;
+    let full_adapter_name = format!("\\DEVICE\\TCPIP_{adapter_name}");
+    let full_adapter_name_hstring = &HSTRING::from(&full_adapter_name);
+    unsafe { GetAdapterIndex(full_adapter_name_hstring, &mut index) };
+
+    index
+}
+
+/// Whether an adapter passes a filter, given the name it is displayed with.
+fn adapter_matches(adapter: &Adapter, name: &str, matcher: &FilterMatcher) -> bool {
+    let addresses: Vec<IpAddr> = adapter
+        .unicast_addresses()
+        .into_iter()
+        .map(|address| address.to_owned())
+        .collect();
+
+    let mut families: Vec<u8> = addresses
+        .iter()
+        .map(|address| match address {
+            IpAddr::V4(_) => AF_INET,
+            IpAddr::V6(_) => AF_INET6,
+        })
+        .collect();
+    if adapter.physical_address().is_some() {
+        families.push(AF_PACKET);
     }
 
-    Ok(ifaces)
+    matcher.matches(&InterfaceFacts {
+        name,
+        up: matches!(
+            adapter.operational_status(),
+            get_adapters_addresses::OperStatus::Up
+        ),
+        // The loopback pseudo-interface is the only one holding loopback addresses only
+        loopback: !addresses.is_empty() && addresses.iter().all(|address| address.is_loopback()),
+        physical: false,
+        kind: None,
+        master: None,
+        families: &families,
+    })
 }
 
 /// List all the network interfaces available on the system by their indexes
 pub fn windows_interfaces_by_index(
     display: InterfaceDisplay,
+    filter: &InterfaceFilter,
 ) -> Result<types::IfacesByIndex, Box<dyn std::error::Error>> {
+    filter.check_supported(false, false, false)?;
+    let matcher = filter.matcher()?;
+
     let adapter_addresses = get_adapters_addresses::AdaptersAddresses::try_new(
         get_adapters_addresses::Family::Unspec,
         *get_adapters_addresses::Flags::default()
@@ -157,30 +244,18 @@ pub fn windows_interfaces_by_index(
 
     let mut ifaces_by_index = types::IfacesByIndex::new();
     for adapter in &adapter_addresses {
-        let value = match display {
-            InterfaceDisplay::HumanReadable => adapter.description().into_string().unwrap(),
-            InterfaceDisplay::MachineReadable => adapter.adapter_name(),
-        };
-
-        // Sadly get_adapters_addresses does not implement a getter for the
-        // interface index, so we have to use the Win32 function to look up the adapter
-        // index by its name.
-        // I did create a feature request for this: https://gitlab.com/cratesio/get_adapters_addresses/-/issues/1
-        // so hopefully someday it will be added and we can remove this code.
-        let adapter_name = adapter.adapter_name();
-        let mut index: u32 = 0;
-        let full_adapter_name = format!("\\DEVICE\\TCPIP_{adapter_name}");
-        let full_adapter_name_hstring = &HSTRING::from(&full_adapter_name);
-        unsafe { GetAdapterIndex(full_adapter_name_hstring, &mut index) };
-
-        ifaces_by_index.insert(index as usize, value);
+        let value = display_name(&adapter, display);
+
+        if adapter_matches(&adapter, &value, &matcher) {
+            ifaces_by_index.insert(adapter_index(&adapter), value);
+        }
     }
 
     Ok(ifaces_by_index)
 }
 
 /// Given an interface name, checks if the interface is up or not.
-pub fn windows_interface_is_up(if_name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+pub fn windows_interface_is_up(iface: &InterfaceRef) -> Result<bool, Box<dyn std::error::Error>> {
     let adapter_addresses = get_adapters_addresses::AdaptersAddresses::try_new(
         get_adapters_addresses::Family::Unspec,
         *get_adapters_addresses::Flags::default()
@@ -190,10 +265,44 @@ pub fn windows_interface_is_up(if_name: &str) -> Result<bool, Box<dyn std::error
             .skip_unicast(),
     )?;
 
-    let interface = find_adapter(&adapter_addresses, if_name)?;
+    let interface = find_adapter(&adapter_addresses, iface)?;
 
     match interface.operational_status() {
         get_adapters_addresses::OperStatus::Up => Ok(true),
         _ => Ok(false),
     }
 }
+
+/// Take a snapshot of every interface, from a single adapter listing.
+pub fn windows_snapshot(
+    display: InterfaceDisplay,
+) -> Result<Vec<Interface>, Box<dyn std::error::Error>> {
+    let adapter_addresses = get_adapters_addresses::AdaptersAddresses::try_new(
+        get_adapters_addresses::Family::Unspec,
+        *get_adapters_addresses::Flags::default()
+            .skip_multicast()
+            .skip_dns_server(),
+    )?;
+
+    let mut snapshot = Vec::new();
+    for adapter in &adapter_addresses {
+        let addresses = addresses_of(&adapter)?;
+
+        snapshot.push(Interface {
+            name: display_name(&adapter, display),
+            index: adapter_index(&adapter),
+            flags: 0,
+            up: matches!(
+                adapter.operational_status(),
+                get_adapters_addresses::OperStatus::Up
+            ),
+            mtu: None,
+            link_address: addresses.link_address,
+            addresses: addresses.addresses,
+        });
+    }
+
+    snapshot.sort_by_key(|iface| iface.index);
+
+    Ok(snapshot)
+}
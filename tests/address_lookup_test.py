@@ -0,0 +1,92 @@
+import ipaddress
+from typing import Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfaddress"
+
+
+@linux_only  # type: ignore[misc]
+def test_every_address_has_an_owner() -> None:
+    for index, name in netifaces.interfaces_by_index(netifaces.InterfaceDisplay.MachineReadable).items():
+        addresses = netifaces.ifaddresses(name)
+
+        for family in [netifaces.AF_INET, netifaces.AF_INET6]:
+            for entry in addresses.get(family, []):
+                ip = entry["addr"]
+                zone = f"%{name}" if ipaddress.ip_address(ip).is_link_local else ""
+
+                owner = netifaces.interface_for_address(ip + zone)
+                assert owner is not None
+                assert owner["interface"] == name
+                assert owner["index"] == index
+                assert owner["family"] == family
+                assert owner["address"] == entry
+
+
+@linux_only  # type: ignore[misc]
+def test_loopback() -> None:
+    owner = netifaces.interface_for_address("127.0.0.1")
+    assert owner is not None
+    assert owner["interface"] == "lo"
+    assert owner["prefixlen"] == 8
+
+    in_network = netifaces.interfaces_in_network("127.0.0.0/8")
+    assert [entry["interface"] for entry in in_network] == ["lo"]
+    # A network inside the subnet of an address, and a bare address, also match it
+    assert netifaces.interfaces_in_network("127.1.2.0/24") == in_network
+    assert netifaces.interfaces_in_network("127.9.9.9") == in_network
+
+
+@linux_only  # type: ignore[misc]
+def test_no_owner() -> None:
+    assert netifaces.interface_for_address("192.0.2.254") is None
+    assert netifaces.interfaces_in_network("198.51.100.0/24") == []
+
+
+@linux_only  # type: ignore[misc]
+def test_invalid_arguments() -> None:
+    for ip in ["not an address", "10.0.0.1/8", "fe80::1%does-not-exist0"]:
+        with pytest.raises(RuntimeError):
+            netifaces.interface_for_address(ip)
+
+    for network in ["10.0.0.0/33", "10.0.0.0/x", "::/129", "10.0.0"]:
+        with pytest.raises(RuntimeError):
+            netifaces.interfaces_in_network(network)
+
+
+@pytest.fixture
+def subnets() -> Iterator[str]:
+    commands = [
+        "link add nfa0 type veth peer name nfa0p",
+        "addr add 10.80.1.1/24 dev nfa0",
+        "addr add 10.80.2.1/24 dev nfa0",
+        "addr add 10.80.1.2/16 dev nfa0p",
+        "addr add fd80::1/64 dev nfa0 nodad",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield "nfa0"
+
+
+@root_only  # type: ignore[misc]
+def test_interfaces_in_network(subnets: str) -> None:
+    # The peer of a veth can get the lower index, so the order of the two is not known
+    def owners(network: str) -> list:
+        entries = in_namespace(NAMESPACE, f"netifaces.interfaces_in_network({network!r})")
+        assert [entry["index"] for entry in entries] == sorted(entry["index"] for entry in entries)
+        return sorted((entry["interface"], entry["address"]["addr"]) for entry in entries)
+
+    assert owners("10.80.1.0/24") == [(subnets, "10.80.1.1"), (f"{subnets}p", "10.80.1.2")]
+    assert owners("10.80.2.128/25") == [(subnets, "10.80.2.1"), (f"{subnets}p", "10.80.1.2")]
+    assert owners("10.80.0.0/16") == [(subnets, "10.80.1.1"), (subnets, "10.80.2.1"), (f"{subnets}p", "10.80.1.2")]
+    assert owners("10.81.0.0/16") == []
+    assert owners("fd80::/16") == [(subnets, "fd80::1")]
+
+    owner = in_namespace(NAMESPACE, "netifaces.interface_for_address('10.80.1.2')")
+    assert owner is not None
+    assert owner["interface"] == f"{subnets}p"
+    assert owner["prefixlen"] == 16
+    assert owner["address"]["mask"] == "255.255.0.0"
@@ -0,0 +1,176 @@
+import os
+import subprocess
+import sys
+from typing import Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfbackends"
+
+
+@pytest.fixture(autouse=True)
+def default_backend() -> Iterator[None]:
+    yield
+    netifaces.set_backend(None)
+
+
+def backend_in_subprocess(variable: str) -> str:
+    env = dict(os.environ, NETIFACES_BACKEND=variable)
+    script = "import netifaces; print(netifaces.get_backend())"
+
+    return subprocess.run([sys.executable, "-c", script], env=env, capture_output=True, text=True).stdout.strip()
+
+
+def test_default_backend() -> None:
+    assert netifaces.get_backend() == netifaces.available_backends()[0]
+
+
+@linux_only  # type: ignore[misc]
+def test_linux_backends() -> None:
+    assert netifaces.available_backends() == ["netlink", "procfs", "getifaddrs"]
+
+
+def test_unknown_backend() -> None:
+    with pytest.raises(ValueError, match="Unknown backend 'nfbackends'"):
+        netifaces.set_backend("nfbackends")
+
+    assert netifaces.get_backend() == netifaces.available_backends()[0]
+
+
+def test_set_backend() -> None:
+    for backend in netifaces.available_backends():
+        netifaces.set_backend(backend)
+        assert netifaces.get_backend() == backend
+
+    netifaces.set_backend(None)
+    assert netifaces.get_backend() == netifaces.available_backends()[0]
+
+
+@linux_only  # type: ignore[misc]
+def test_environment_variable() -> None:
+    assert backend_in_subprocess("getifaddrs") == "getifaddrs"
+    # Unknown backends are ignored
+    assert backend_in_subprocess("nfbackends") == "netlink"
+
+
+@linux_only  # type: ignore[misc]
+def test_netlink_and_getifaddrs_agree() -> None:
+    netifaces.set_backend("netlink")
+    netlink = (netifaces.interfaces_by_index(), netifaces.ifaddresses("lo")[netifaces.AF_INET])
+
+    netifaces.set_backend("getifaddrs")
+    getifaddrs = (netifaces.interfaces_by_index(), netifaces.ifaddresses("lo")[netifaces.AF_INET])
+
+    assert netlink == getifaddrs
+
+
+@pytest.fixture
+def aliased_link() -> Iterator[str]:
+    commands = [
+        "link add nfa0 type veth peer name nfa0p",
+        "addr add 10.78.0.1/24 dev nfa0",
+        "addr add 10.78.1.1/24 dev nfa0 label nfa0:1",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield "nfa0"
+
+
+# What each backend tells of the link and of its alias
+LOOK_ALIASES_UP = """
+def addresses(name):
+    return sorted(entry["addr"] for entry in netifaces.ifaddresses(name)[netifaces.AF_INET])
+results = []
+for backend in ["netlink", "getifaddrs"]:
+    netifaces.set_backend(backend)
+    snapshot = {interface["name"]: interface for interface in netifaces.snapshot()}
+    results.append(
+        [
+            addresses("nfa0"),
+            addresses("nfa0:1"),
+            sorted(entry["addr"] for entry in snapshot["nfa0"]["addresses"][netifaces.AF_INET]),
+            "nfa0:1" in snapshot,
+        ]
+    )
+"""
+
+
+@root_only  # type: ignore[misc]
+def test_backends_agree_on_aliases(aliased_link: str) -> None:
+    # The procfs backend cannot tell the labels of the addresses
+    results = in_namespace(NAMESPACE, "results", setup=LOOK_ALIASES_UP)
+
+    assert results == [[["10.78.0.1", "10.78.1.1"], ["10.78.1.1"], ["10.78.0.1", "10.78.1.1"], False]] * 2
+
+
+@linux_only  # type: ignore[misc]
+def test_errors_name_the_backend() -> None:
+    netifaces.set_backend("getifaddrs")
+
+    with pytest.raises(netifaces.InterfaceNotFoundError) as error:
+        netifaces.ifaddresses("nfbackends-none")
+    assert error.value.backend == "getifaddrs"
+
+    with pytest.raises(netifaces.InterfaceNotFoundError) as error:
+        netifaces.interface_for_address("fe80::1%nfbackends-none")
+    assert error.value.backend == "getifaddrs"
+
+
+@linux_only  # type: ignore[misc]
+def test_lookups_follow_the_selection() -> None:
+    netifaces.set_backend("netlink")
+    netlink = (
+        netifaces.interface_for_address("127.0.0.1"),
+        netifaces.interfaces_in_network("127.0.0.0/8"),
+        netifaces.interface_routes("lo", netifaces.InterfaceType.AF_INET),
+    )
+
+    netifaces.set_backend("procfs")
+    owner, owners, routes = (
+        netifaces.interface_for_address("127.0.0.1"),
+        netifaces.interfaces_in_network("127.0.0.0/8"),
+        netifaces.interface_routes("lo", netifaces.InterfaceType.AF_INET),
+    )
+
+    assert owner == netlink[0]
+    assert owners == netlink[1]
+    # Only the main table is in /proc/net/route
+    assert routes == [route for route in netlink[2] if route["table"] == 254]
+
+
+@linux_only  # type: ignore[misc]
+def test_procfs_cannot_do_what_only_netlink_does() -> None:
+    netifaces.set_backend("procfs")
+
+    for query in (
+        lambda: netifaces.route_get("127.0.0.1"),
+        lambda: netifaces.wait_for_interface("lo", timeout=0),
+        lambda: netifaces.wait_for_network_online(timeout=0),
+        lambda: netifaces.enable_cache(),
+    ):
+        with pytest.raises(netifaces.UnsupportedOperationError) as error:
+            query()
+        assert error.value.backend == "procfs"
+
+
+@linux_only  # type: ignore[misc]
+def test_procfs_routes() -> None:
+    netifaces.set_backend("netlink")
+    netlink = netifaces.gateways().get(netifaces.InterfaceType.AF_INET)
+
+    netifaces.set_backend("procfs")
+    procfs = netifaces.routes(netifaces.InterfaceType.AF_INET)
+
+    assert all(route["table"] == 254 and len(route["nexthops"]) == 1 for route in procfs)
+    assert netifaces.gateways().get(netifaces.InterfaceType.AF_INET) == netlink
+
+
+@linux_only  # type: ignore[misc]
+def test_getifaddrs_cannot_list_routes() -> None:
+    netifaces.set_backend("getifaddrs")
+
+    with pytest.raises(netifaces.UnsupportedOperationError) as error:
+        netifaces.routes()
+    assert error.value.backend == "getifaddrs"
@@ -12,8 +12,11 @@ def test_interfaces_returns_something() -> None:
 
 def test_interfaces_by_index_returns_same_interface_list() -> None:
     # Interface indices are difficult to verify, but we can at least check that we get
-    # the same set of interfaces in both these functions
-    assert set(netifaces.interfaces_by_index().values()) == set(netifaces.interfaces())
+    # the same interfaces, in the same order, from both these functions
+    by_index = netifaces.interfaces_by_index()
+
+    assert list(by_index.values()) == netifaces.interfaces()
+    assert list(by_index.keys()) == sorted(by_index.keys())
 
 
 def test_can_lookup_by_either_name() -> None:
@@ -0,0 +1,161 @@
+from typing import Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfcache"
+
+
+@pytest.fixture  # type: ignore[misc]
+def cache() -> Iterator[None]:
+    netifaces.enable_cache()
+    yield
+    netifaces.disable_cache()
+
+
+@linux_only  # type: ignore[misc]
+def test_cache_disabled_by_default() -> None:
+    assert netifaces.cache_generation() is None
+    assert not hasattr(netifaces.interfaces(), "generation")
+
+
+@linux_only  # type: ignore[misc]
+def test_cached_results_match_uncached(cache: None) -> None:
+    generation = netifaces.cache_generation()
+    assert generation is not None
+
+    cached_interfaces = netifaces.interfaces()
+    cached_by_index = netifaces.interfaces_by_index()
+    cached_gateways = netifaces.gateways()
+    assert cached_interfaces.generation == generation  # type: ignore[attr-defined]
+    assert cached_gateways.generation == generation  # type: ignore[attr-defined]
+
+    cached_addresses = {interface: netifaces.ifaddresses(interface) for interface in cached_interfaces}
+
+    netifaces.disable_cache()
+
+    assert set(cached_by_index.values()) == set(cached_interfaces)
+    assert cached_by_index == netifaces.interfaces_by_index()
+    assert cached_gateways == netifaces.gateways()
+
+    for interface, addresses in cached_addresses.items():
+        assert addresses.get(netifaces.InterfaceType.AF_INET6) == netifaces.ifaddresses(interface).get(
+            netifaces.InterfaceType.AF_INET6
+        )
+
+
+@linux_only  # type: ignore[misc]
+def test_cached_invalid_if_name(cache: None) -> None:
+    with pytest.raises(Exception):
+        netifaces.ifaddresses("arglebargle")
+
+
+@linux_only  # type: ignore[misc]
+def test_enable_cache_twice(cache: None) -> None:
+    generation = netifaces.cache_generation()
+    netifaces.enable_cache()
+
+    assert netifaces.cache_generation() == generation
+
+
+@pytest.fixture
+def bridged() -> Iterator[None]:
+    commands = [
+        "link add nfb0 type bridge",
+        "link add nfp0 type veth peer name nfp0p",
+        "link set nfp0 master nfb0",
+        "addr add 10.66.0.1/24 dev nfp0",
+        "link set nfb0 up",
+        "link set nfp0 up",
+        "link set nfp0p up",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield
+
+
+# Bridges report changes to their ports with AF_BRIDGE notifications, which only carry
+# some of the attributes of the port. The address added last tells that the cache went
+# through them.
+CHANGE_BRIDGE_PORT = """
+import subprocess, time
+netifaces.enable_cache()
+subprocess.run(["bridge", "link", "set", "dev", "nfp0", "cost", "10"], check=True)
+subprocess.run(["ip", "link", "set", "nfp0", "nomaster"], check=True)
+subprocess.run(["ip", "addr", "add", "10.66.1.1/24", "dev", "nfp0"], check=True)
+deadline = time.monotonic() + 5
+while "10.66.1.1" not in str(netifaces.ifaddresses("nfp0")) and time.monotonic() < deadline:
+    time.sleep(0.01)
+cached = [
+    sorted(netifaces.interfaces()),
+    netifaces.interface_is_up("nfp0"),
+    netifaces.ifaddresses("nfp0"),
+]
+netifaces.disable_cache()
+"""
+
+
+@root_only  # type: ignore[misc]
+def test_cache_ignores_bridge_port_notifications(bridged: None) -> None:
+    cached, uncached = in_namespace(
+        NAMESPACE,
+        """[cached, [
+            sorted(netifaces.interfaces()),
+            netifaces.interface_is_up("nfp0"),
+            netifaces.ifaddresses("nfp0"),
+        ]]""",
+        setup=CHANGE_BRIDGE_PORT,
+    )
+
+    assert cached == uncached
+
+
+@pytest.fixture
+def routed() -> Iterator[None]:
+    commands = [
+        "link add nfr0 type veth peer name nfr0p",
+        "link set nfr0 up",
+        "link set nfr0p up",
+        "route add 10.67.0.0/24 dev nfr0",
+        "route add 10.68.0.0/24 via 10.67.0.2 dev nfr0",
+    ]
+
+    # Without IPv6 nor addresses, no route of the link is removed with a notification of its own
+    with ip_batch(commands, namespace=NAMESPACE, sysctls=["net.ipv6.conf.default.disable_ipv6=1"]):
+        yield
+
+
+# The kernel removes the IPv4 routes through a link going down or away without notifying
+# each of them. The link added last tells that the cache went through the change.
+DROP_LINK = """
+import subprocess, time
+def wait_for(condition):
+    deadline = time.monotonic() + 5
+    while not condition() and time.monotonic() < deadline:
+        time.sleep(0.01)
+gateway_interfaces = lambda: [entry[1] for entries in netifaces.gateways().values() for entry in entries]
+netifaces.enable_cache()
+before = gateway_interfaces()
+subprocess.run(["ip", "link", "set", "nfr0", "down"], check=True)
+subprocess.run(["ip", "link", "add", "nfr1", "type", "veth", "peer", "name", "nfr1p"], check=True)
+wait_for(lambda: "nfr1" in netifaces.interfaces())
+after_down = gateway_interfaces()
+subprocess.run(["ip", "link", "set", "nfr0", "up"], check=True)
+subprocess.run(["ip", "route", "add", "10.67.0.0/24", "dev", "nfr0"], check=True)
+subprocess.run(["ip", "route", "add", "10.68.0.0/24", "via", "10.67.0.2", "dev", "nfr0"], check=True)
+wait_for(lambda: "nfr0" in gateway_interfaces())
+subprocess.run(["ip", "link", "del", "nfr0"], check=True)
+wait_for(lambda: "nfr0" not in netifaces.interfaces())
+after_delete = gateway_interfaces()
+netifaces.disable_cache()
+"""
+
+
+@root_only  # type: ignore[misc]
+def test_cached_routes_drop_dead_links(routed: None) -> None:
+    before, after_down, after_delete = in_namespace(NAMESPACE, "[before, after_down, after_delete]", setup=DROP_LINK)
+
+    assert "nfr0" in before
+    assert "nfr0" not in after_down
+    assert "nfr0" not in after_delete
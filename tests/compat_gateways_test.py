@@ -0,0 +1,59 @@
+import subprocess
+from typing import Iterator
+
+import netifaces
+import netifaces.compat
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfcompat"
+
+
+@linux_only  # type: ignore[misc]
+def test_legacy_structure() -> None:
+    table = netifaces.compat.gateways()
+
+    assert list(table)[0] == "default"
+    assert table["default"] == {
+        int(family): gateway for family, gateway in netifaces.default_gateway(old_api=True).items()
+    }
+
+    for family, entries in table.items():
+        if family == "default":
+            continue
+
+        assert type(family) is int
+        assert isinstance(entries, list)
+        for gateway_ip, iface, is_default in entries:
+            assert isinstance(gateway_ip, str) and isinstance(iface, str) and isinstance(is_default, bool)
+
+    for family, default in table["default"].items():
+        assert (*default, True) in table[family]
+
+
+@pytest.fixture
+def routed() -> Iterator[None]:
+    commands = [
+        "link set lo up",
+        "link add nfc0 type veth peer name nfc0p",
+        "addr add 10.95.0.1/24 dev nfc0",
+        "link set nfc0 up",
+        "link set nfc0p up",
+        "route add default via 10.95.0.2",
+        "route add 10.94.0.0/16 via 10.95.0.3",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield
+
+
+@root_only  # type: ignore[misc]
+def test_same_table_as_netifaces_1(routed: None) -> None:
+    # JSON turns the integer keys into strings, and the tuples into lists
+    assert in_namespace(NAMESPACE, "netifaces.compat.gateways()") == {
+        "default": {"2": ["10.95.0.2", "nfc0"]},
+        "2": [["10.95.0.2", "nfc0", True], ["10.95.0.3", "nfc0", False]],
+    }
+
+    subprocess.run(["ip", "-n", NAMESPACE, "route", "flush", "table", "main"], check=True)
+    assert in_namespace(NAMESPACE, "netifaces.compat.gateways()") == {"default": {}}
@@ -0,0 +1,85 @@
+import re
+from typing import Iterator
+
+import netifaces
+import netifaces.compat
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfcompatif"
+
+
+def test_address_families() -> None:
+    assert netifaces.compat.version == "0.11.0"
+    assert (netifaces.compat.AF_INET, netifaces.compat.AF_INET6, netifaces.compat.AF_LINK) == (2, 10, 17)
+
+    families = netifaces.compat.address_families
+    assert (families[1], families[16], families[17]) == ("AF_FILE", "AF_NETLINK", "AF_PACKET")
+    assert list(families)[:5] == [0, 1, 2, 10, 22]
+    assert len(families) == 26
+
+
+@linux_only  # type: ignore[misc]
+def test_same_interfaces() -> None:
+    interfaces = netifaces.compat.interfaces()
+
+    assert len(interfaces) == len(set(interfaces))
+    # Only the IPv4 label aliases are not interfaces of their own
+    assert set(netifaces.interfaces()) == {name for name in interfaces if ":" not in name}
+
+
+@linux_only  # type: ignore[misc]
+def test_formatting() -> None:
+    for ifname in netifaces.compat.interfaces():
+        addresses = netifaces.compat.ifaddresses(ifname)
+
+        for entry in addresses.get(netifaces.compat.AF_PACKET, []):
+            assert re.fullmatch(r"([0-9a-f]{2}:)*[0-9a-f]{2}", entry["addr"])
+        for entry in addresses.get(netifaces.compat.AF_INET6, []):
+            assert re.fullmatch(r"[0-9a-f:]+/\d+", entry["netmask"])
+            if entry["addr"].startswith("fe80:"):
+                assert entry["addr"].endswith(f"%{ifname}")
+
+    lo = netifaces.compat.ifaddresses("lo")
+    assert lo[netifaces.compat.AF_INET][0] == {"addr": "127.0.0.1", "netmask": "255.0.0.0", "peer": "127.0.0.1"}
+    assert lo[netifaces.compat.AF_LINK] == [{"addr": "00:00:00:00:00:00", "peer": "00:00:00:00:00:00"}]
+    assert {"addr": "::1", "netmask": "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/128"} in lo[netifaces.compat.AF_INET6]
+
+
+@linux_only  # type: ignore[misc]
+def test_unknown_interface() -> None:
+    with pytest.raises(ValueError, match="You must specify a valid interface name."):
+        netifaces.compat.ifaddresses("nfcompat-none")
+
+
+@pytest.fixture
+def aliased() -> Iterator[None]:
+    commands = [
+        "link add nfc0 address 02:AB:CD:00:00:01 type veth peer name nfc0p",
+        "addr add 10.77.0.1/24 brd + dev nfc0",
+        "addr add 10.77.1.1/24 brd + dev nfc0 label nfc0:1",
+        "addr add 169.254.7.1/16 brd + dev nfc0",
+        "addr add fd77::1/48 dev nfc0 nodad",
+        "link set nfc0 up",
+        "link set nfc0p up",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield
+
+
+@root_only  # type: ignore[misc]
+def test_aliases(aliased: None) -> None:
+    assert in_namespace(NAMESPACE, "netifaces.compat.interfaces()") == ["lo", "nfc0p", "nfc0", "nfc0:1"]
+
+    addresses = in_namespace(NAMESPACE, "netifaces.compat.ifaddresses('nfc0')")
+    assert addresses["17"] == [{"addr": "02:ab:cd:00:00:01", "broadcast": "ff:ff:ff:ff:ff:ff"}]
+    assert addresses["2"] == [
+        {"addr": "10.77.0.1", "netmask": "255.255.255.0", "broadcast": "10.77.0.255"},
+        # netifaces 1 dropped the broadcast address of link-local networks
+        {"addr": "169.254.7.1", "netmask": "255.255.0.0"},
+    ]
+    assert addresses["10"][0] == {"addr": "fd77::1", "netmask": "ffff:ffff:ffff::/48"}
+
+    alias = in_namespace(NAMESPACE, "netifaces.compat.ifaddresses('nfc0:1')")
+    assert alias == {"2": [{"addr": "10.77.1.1", "netmask": "255.255.255.0", "broadcast": "10.77.1.255"}]}
@@ -0,0 +1,94 @@
+import subprocess
+from typing import Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfdefaults"
+
+
+@linux_only  # type: ignore[misc]
+def test_default_routes_are_sorted() -> None:
+    routes = netifaces.default_routes()
+
+    assert all(route["destination"] is None and route["prefixlen"] == 0 for route in routes)
+    assert [(route["family"], route["metric"] or 0) for route in routes] == sorted(
+        (route["family"], route["metric"] or 0) for route in routes
+    )
+    assert netifaces.default_routes(netifaces.InterfaceType.AF_INET) == [
+        route for route in routes if route["family"] == netifaces.AF_INET
+    ]
+
+
+@linux_only  # type: ignore[misc]
+def test_primary_interface_is_a_default_route() -> None:
+    for family in [netifaces.InterfaceType.AF_INET, netifaces.InterfaceType.AF_INET6]:
+        primary = netifaces.primary_interface(family)
+        if primary is None:
+            continue
+
+        assert primary["interface"] is not None
+        assert netifaces.interface_is_up(primary["interface"])
+        assert primary["source"] is not None
+        assert dict(primary, source=None) in [
+            dict(route, source=None) for route in netifaces.default_routes(family)
+        ]
+
+
+@linux_only  # type: ignore[misc]
+def test_primary_interface_invalid_family() -> None:
+    with pytest.raises(RuntimeError):
+        netifaces.primary_interface(netifaces.InterfaceType.AF_PACKET)
+
+
+@pytest.fixture
+def two_uplinks() -> Iterator[None]:
+    # A namespace with a preferred uplink, and a fallback with a higher metric
+    commands = [
+        "link set lo up",
+        "link add nfp0 type veth peer name nfp0p",
+        "link add nfp1 type veth peer name nfp1p",
+        "addr add 10.85.0.1/24 dev nfp0",
+        "addr add 10.86.0.1/24 dev nfp1",
+        "link set nfp0 up",
+        "link set nfp0p up",
+        "link set nfp1 up",
+        "link set nfp1p up",
+        "route add default via 10.86.0.2 metric 200",
+        "route add default via 10.85.0.2 metric 100",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield
+
+
+@root_only  # type: ignore[misc]
+def test_ranking(two_uplinks: None) -> None:
+    routes = in_namespace(NAMESPACE, "netifaces.default_routes()")
+    assert [(route["interface"], route["gateway"], route["metric"]) for route in routes] == [
+        ("nfp0", "10.85.0.2", 100),
+        ("nfp1", "10.86.0.2", 200),
+    ]
+
+    primary = in_namespace(NAMESPACE, "netifaces.primary_interface()")
+    assert (primary["interface"], primary["gateway"], primary["source"]) == ("nfp0", "10.85.0.2", "10.85.0.1")
+    assert in_namespace(NAMESPACE, "netifaces.default_gateway(old_api=True)") == {"2": ["10.85.0.2", "nfp0"]}
+
+    # Once the preferred uplink loses its carrier, the fallback carries the traffic
+    subprocess.run(["ip", "-n", NAMESPACE, "link", "set", "nfp0p", "down"], check=True)
+    primary = in_namespace(NAMESPACE, "netifaces.primary_interface()")
+    assert (primary["interface"], primary["gateway"], primary["source"]) == ("nfp1", "10.86.0.2", "10.86.0.1")
+    assert in_namespace(NAMESPACE, "netifaces.default_gateway(old_api=True)") == {"2": ["10.86.0.2", "nfp1"]}
+
+    assert in_namespace(NAMESPACE, "netifaces.primary_interface(netifaces.InterfaceType.AF_INET6)") is None
+
+
+@root_only  # type: ignore[misc]
+def test_default_gateway_without_routes(two_uplinks: None) -> None:
+    # The getifaddrs backend cannot list the routes, the gateways are found another way
+    backend = 'netifaces.set_backend("getifaddrs")'
+    default = in_namespace(NAMESPACE, "netifaces.default_gateway(old_api=True)", setup=backend)
+
+    assert list(default) == ["2"]
+    assert default["2"] in [["10.85.0.2", "nfp0"], ["10.86.0.2", "nfp1"]]
@@ -0,0 +1,124 @@
+from typing import Dict, Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfdisplay"
+
+
+@linux_only  # type: ignore[misc]
+def test_index_display() -> None:
+    by_index = netifaces.interfaces_by_index(netifaces.InterfaceDisplay.Index)
+
+    assert all(name == str(index) for index, name in by_index.items())
+    for index, name in by_index.items():
+        assert netifaces.ifaddresses(name) == netifaces.ifaddresses(index)
+
+
+@linux_only  # type: ignore[misc]
+def test_every_display_lists_the_same_interfaces() -> None:
+    indexes = list(netifaces.interfaces_by_index())
+
+    for display in netifaces.InterfaceDisplay:
+        assert list(netifaces.interfaces_by_index(display)) == indexes
+        assert [interface["index"] for interface in netifaces.snapshot(display)] == indexes
+
+
+@pytest.fixture
+def described_link() -> Iterator[str]:
+    commands = [
+        "link add nfd0 type veth peer name nfd0p",
+        "link set nfd0 alias uplink-to-the-lab",
+        "link property add dev nfd0 altname nfd-first",
+        "link property add dev nfd0 altname nfd-second",
+        "link set nfd0p alias 'peer of the uplink'",
+        "addr add 10.79.0.1/24 dev nfd0",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield "nfd0"
+
+
+def by_index(display: str) -> Dict[int, str]:
+    """The interfaces of the namespace by index, displayed as said"""
+
+    names = in_namespace(NAMESPACE, f"netifaces.interfaces_by_index(netifaces.InterfaceDisplay.{display})")
+    return {int(index): name for index, name in names.items()}
+
+
+@root_only  # type: ignore[misc]
+def test_display_names(described_link: str) -> None:
+    index, peer_index = in_namespace(
+        NAMESPACE, f"[netifaces.if_nametoindex({described_link!r}), netifaces.if_nametoindex('{described_link}p')]"
+    )
+
+    human = by_index("HumanReadable")
+    assert human[index] == "uplink-to-the-lab"
+    assert human[peer_index] == "peer of the uplink"
+    assert "lo" in human.values()
+
+    machine = by_index("MachineReadable")
+    assert machine[index] == described_link
+
+    altnames = by_index("AltName")
+    assert altnames[index] == "nfd-first"
+    assert altnames[peer_index] == f"{described_link}p"
+
+    assert in_namespace(NAMESPACE, f"netifaces.if_indextoname({index})") == "uplink-to-the-lab"
+    assert in_namespace(NAMESPACE, f"netifaces.if_indextoname({index}, netifaces.InterfaceDisplay.AltName)") == (
+        "nfd-first"
+    )
+
+    snapshot = in_namespace(NAMESPACE, "{interface['index']: interface['name'] for interface in netifaces.snapshot()}")
+    assert snapshot[str(index)] == "uplink-to-the-lab"
+
+
+# Look the link up by each of its names, without and with the cache
+LOOK_UP_BY_ANY_NAME = """
+index = netifaces.if_nametoindex("nfd0")
+addresses = netifaces.ifaddresses("nfd0")
+up = netifaces.interface_is_up("nfd0")
+names = ["uplink-to-the-lab", "nfd-first", "nfd-second", str(index)]
+uncached = [
+    [
+        netifaces.if_nametoindex(name) == index,
+        netifaces.ifaddresses(name) == addresses,
+        netifaces.interface_is_up(name) == up,
+    ]
+    for name in names
+]
+session = netifaces.NetlinkSession()
+netifaces.enable_cache()
+cached = [[session.ifaddresses(name) == addresses, netifaces.ifaddresses(name) == addresses] for name in names]
+altnames = netifaces.interfaces(netifaces.InterfaceDisplay.AltName, name="nfd0")
+netifaces.disable_cache()
+"""
+
+
+@root_only  # type: ignore[misc]
+def test_lookup_by_any_display_name(described_link: str) -> None:
+    uncached, cached, altnames = in_namespace(NAMESPACE, "[uncached, cached, altnames]", setup=LOOK_UP_BY_ANY_NAME)
+
+    assert uncached == [[True, True, True]] * 4
+    assert cached == [[True, True]] * 4
+    assert altnames == ["nfd-first"]
+
+
+# Give the peer the description of the link, and look the description up
+LOOK_UP_AMBIGUOUS = """
+import subprocess
+subprocess.run(["ip", "link", "set", "nfd0p", "alias", "uplink-to-the-lab"], check=True)
+try:
+    netifaces.ifaddresses("uplink-to-the-lab")
+    error = None
+except RuntimeError as e:
+    error = str(e)
+"""
+
+
+@root_only  # type: ignore[misc]
+def test_ambiguous_description(described_link: str) -> None:
+    error = in_namespace(NAMESPACE, "error", setup=LOOK_UP_AMBIGUOUS)
+
+    assert error is not None and "More than a single interface" in error
@@ -0,0 +1,77 @@
+from typing import Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfenumeration"
+
+
+def test_interfaces_are_sorted_by_index() -> None:
+    by_index = netifaces.interfaces_by_index()
+    names = netifaces.interfaces()
+
+    assert len(names) == len(set(names))
+    assert names == [by_index[index] for index in sorted(by_index)]
+
+
+@linux_only  # type: ignore[misc]
+def test_interfaces_agree_with_snapshot() -> None:
+    assert netifaces.interfaces() == [interface["name"] for interface in netifaces.snapshot()]
+
+
+@pytest.fixture
+def aliased_link() -> Iterator[str]:
+    # A veth pair: the peer has no address at all, and the link has an IPv4 alias
+    commands = [
+        "link add nfe0 type veth peer name nfe0p",
+        "addr add 10.77.0.1/24 dev nfe0",
+        "addr add 10.77.1.1/24 dev nfe0 label nfe0:1",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield "nfe0"
+
+
+@root_only  # type: ignore[misc]
+def test_aliases_are_not_interfaces(aliased_link: str) -> None:
+    interfaces, by_index = in_namespace(
+        NAMESPACE, "[netifaces.interfaces(), list(netifaces.interfaces_by_index().values())]"
+    )
+
+    assert interfaces == ["lo", f"{aliased_link}p", aliased_link]
+    assert by_index == interfaces
+
+
+# Look the addresses of the link up in every way, and an alias it does not have
+LOOK_ALIASES_UP = """
+addresses = netifaces.ifaddresses("nfe0")
+try:
+    netifaces.ifaddresses("nfe0:2")
+    unknown_alias_found = True
+except RuntimeError:
+    unknown_alias_found = False
+session = netifaces.NetlinkSession()
+snapshot = {interface["name"]: interface for interface in netifaces.snapshot()}
+"""
+
+
+@root_only  # type: ignore[misc]
+def test_alias_addresses_belong_to_their_link(aliased_link: str) -> None:
+    ipv4, alias, unknown_alias_found, agree = in_namespace(
+        NAMESPACE,
+        """[
+            [entry["addr"] for entry in addresses[netifaces.AF_INET]],
+            [entry["addr"] for entry in netifaces.ifaddresses("nfe0:1")[netifaces.AF_INET]],
+            unknown_alias_found,
+            [session.ifaddresses("nfe0") == addresses, snapshot["nfe0"]["addresses"] == addresses],
+        ]""",
+        setup=LOOK_ALIASES_UP,
+    )
+
+    assert sorted(ipv4) == ["10.77.0.1", "10.77.1.1"]
+    # Looking an alias up by name only gives the addresses bearing its label
+    assert alias == ["10.77.1.1"]
+    assert not unknown_alias_found
+    # Every way of getting the addresses agrees
+    assert agree == [True, True]
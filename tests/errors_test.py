@@ -0,0 +1,85 @@
+from typing import Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nferrors"
+
+BACKENDS = ["netlink", "getifaddrs", "iphlpapi"]
+
+
+def test_hierarchy() -> None:
+    for error in [
+        netifaces.InterfaceNotFoundError,
+        netifaces.AmbiguousInterfaceError,
+        netifaces.PermissionDeniedError,
+        netifaces.UnsupportedOperationError,
+        netifaces.BackendUnavailableError,
+    ]:
+        assert issubclass(error, netifaces.NetifacesError)
+
+    assert issubclass(netifaces.NetifacesError, RuntimeError)
+    assert issubclass(netifaces.PermissionDeniedError, PermissionError)
+    assert issubclass(netifaces.UnsupportedOperationError, NotImplementedError)
+
+
+def test_attributes() -> None:
+    error = netifaces.PermissionDeniedError("denied", errno=1, interface="eth0", backend="netlink")
+
+    assert (str(error), error.errno, error.interface, error.backend) == ("denied", 1, "eth0", "netlink")
+    assert netifaces.NetifacesError("failed").errno is None
+
+
+def test_unknown_interface_name() -> None:
+    with pytest.raises(netifaces.InterfaceNotFoundError, match="Failed to find an interface") as error:
+        netifaces.ifaddresses("nferrors-none")
+
+    assert error.value.interface == "nferrors-none"
+    assert error.value.backend in BACKENDS
+    assert error.value.errno is None
+
+
+@linux_only  # type: ignore[misc]
+def test_unknown_interface_index() -> None:
+    with pytest.raises(netifaces.InterfaceNotFoundError) as error:
+        netifaces.get_interface(999999)
+
+    assert error.value.interface == 999999
+
+
+def test_invalid_display() -> None:
+    with pytest.raises(TypeError, match="Invalid interface display 42"):
+        netifaces.netifaces._get_interface("lo", 42)
+
+
+@pytest.fixture
+def twin_links() -> Iterator[str]:
+    commands = [
+        "link add nfe0 type veth peer name nfe0p",
+        "link set nfe0 alias nferrors-twin",
+        "link set nfe0p alias nferrors-twin",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield "nferrors-twin"
+
+
+# Look up the description of both links
+LOOK_UP_TWINS = """
+try:
+    netifaces.ifaddresses("nferrors-twin")
+    error = None
+except netifaces.AmbiguousInterfaceError as e:
+    error = [str(e), e.interface]
+"""
+
+
+@root_only  # type: ignore[misc]
+def test_ambiguous_description(twin_links: str) -> None:
+    error = in_namespace(NAMESPACE, "error", setup=LOOK_UP_TWINS)
+
+    assert error is not None
+    message, interface = error
+    assert "More than a single interface" in message
+    assert interface == twin_links
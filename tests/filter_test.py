@@ -0,0 +1,96 @@
+import re
+from typing import Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nffilter"
+
+
+def test_no_filter_lists_everything() -> None:
+    assert netifaces.interfaces(exclude_loopback=False, up=False) == netifaces.interfaces()
+
+
+def test_filters_return_a_subset_in_order() -> None:
+    everything = netifaces.interfaces()
+    filtered = netifaces.interfaces(exclude_loopback=True, has_address=netifaces.InterfaceType.AF_INET)
+
+    assert filtered == [name for name in everything if name in filtered]
+    assert list(netifaces.interfaces_by_index(exclude_loopback=True).values()) == netifaces.interfaces(
+        exclude_loopback=True
+    )
+
+
+def test_up_filter() -> None:
+    for name in netifaces.interfaces(up=True):
+        assert netifaces.interface_is_up(name)
+
+
+def test_has_address_filter() -> None:
+    for name in netifaces.interfaces(has_address=netifaces.InterfaceType.AF_INET):
+        assert netifaces.AF_INET in netifaces.ifaddresses(name)
+
+    for name in netifaces.interfaces():
+        if netifaces.AF_INET6 in netifaces.ifaddresses(name):
+            assert name in netifaces.interfaces(has_address=netifaces.InterfaceType.AF_INET6)
+
+
+def test_name_filters() -> None:
+    everything = netifaces.interfaces()
+    first = everything[0]
+
+    assert netifaces.interfaces(name=first) == [first]
+    assert netifaces.interfaces(name="*") == everything
+    assert netifaces.interfaces(name=first[0] + "*") == [name for name in everything if name.startswith(first[0])]
+    assert netifaces.interfaces(name_regex="^" + re.escape(first) + "$") == [first]
+    assert netifaces.interfaces(name="[!" + first[0] + "]*") == [
+        name for name in everything if not name.startswith(first[0])
+    ]
+
+
+def test_invalid_name_regex() -> None:
+    with pytest.raises(RuntimeError):
+        netifaces.interfaces(name_regex="(")
+
+
+@linux_only  # type: ignore[misc]
+def test_loopback_filters() -> None:
+    assert "lo" in netifaces.interfaces(has_address=netifaces.InterfaceType.AF_INET)
+    assert "lo" not in netifaces.interfaces(exclude_loopback=True)
+    assert "lo" not in netifaces.interfaces(physical=True)
+    assert netifaces.interfaces(name="l?") == ["lo"]
+
+
+@linux_only  # type: ignore[misc]
+def test_filters_agree_with_cache() -> None:
+    uncached = netifaces.interfaces(exclude_loopback=True, has_address=netifaces.InterfaceType.AF_INET6)
+
+    netifaces.enable_cache()
+    try:
+        cached = netifaces.interfaces(exclude_loopback=True, has_address=netifaces.InterfaceType.AF_INET6)
+    finally:
+        netifaces.disable_cache()
+
+    assert cached == uncached
+
+
+@pytest.fixture
+def bridged_veth() -> Iterator[str]:
+    commands = [
+        "link add nff0 type veth peer name nff0p",
+        "link add nffbr0 type bridge",
+        "link set nff0 master nffbr0",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield "nffbr0"
+
+
+@root_only  # type: ignore[misc]
+def test_kind_and_master_filters(bridged_veth: str) -> None:
+    assert in_namespace(NAMESPACE, f"netifaces.interfaces(master={bridged_veth!r})") == ["nff0"]
+    assert in_namespace(NAMESPACE, "netifaces.interfaces(kind='bridge')") == [bridged_veth]
+    assert sorted(in_namespace(NAMESPACE, "netifaces.interfaces(kind='veth')")) == ["nff0", "nff0p"]
+    assert in_namespace(NAMESPACE, "netifaces.interfaces(physical=True)") == []
+    assert in_namespace(NAMESPACE, "netifaces.interfaces(master='does-not-exist0')") == []
@@ -0,0 +1,106 @@
+import os
+import platform
+import time
+from typing import Callable, Iterator, List
+
+import netifaces
+import pytest
+from helpers import ip_batch, linux_only
+
+# Number of veth pairs the benchmark creates. The benchmark changes the host's network
+# configuration, so it only runs when asked to (and as root).
+BENCHMARK_PAIRS = int(os.environ.get("NETIFACES_BENCHMARK_PAIRS", "0"))
+BENCHMARK_ROUNDS = 20
+
+
+def _route_key(route: netifaces.RouteEntry) -> str:
+    return repr(sorted(route.items()))
+
+
+def _goes_through(route: netifaces.RouteEntry, interface: str) -> bool:
+    # Multipath routes go out through the interface of any of their nexthops
+    return any(hop["interface"] == interface for hop in route["nexthops"])
+
+
+@linux_only  # type: ignore[misc]
+def test_interface_routes_match_full_dump() -> None:
+    session = netifaces.NetlinkSession()
+
+    for interface in session.interfaces():
+        expected = [route for route in session.routes() if _goes_through(route, interface)]
+        filtered = netifaces.interface_routes(interface)
+
+        assert sorted(map(_route_key, filtered)) == sorted(map(_route_key, expected))
+        assert sorted(map(_route_key, session.interface_routes(interface))) == sorted(map(_route_key, expected))
+
+
+@linux_only  # type: ignore[misc]
+def test_interface_routes_family() -> None:
+    routes = netifaces.interface_routes("lo", netifaces.InterfaceType.AF_INET)
+
+    assert all(route["family"] == netifaces.AF_INET for route in routes)
+    assert any(route["destination"] == "127.0.0.1" for route in routes)
+
+
+@linux_only  # type: ignore[misc]
+def test_filtered_queries_reject_unknown_interface() -> None:
+    with pytest.raises(RuntimeError):
+        netifaces.interface_routes("does-not-exist0")
+
+    with pytest.raises(RuntimeError):
+        netifaces.ifaddresses("does-not-exist0")
+
+
+@linux_only  # type: ignore[misc]
+def test_ifaddresses_matches_snapshot() -> None:
+    for interface in netifaces.snapshot():
+        addresses = netifaces.ifaddresses(interface["name"])
+
+        for family in [netifaces.InterfaceType.AF_PACKET, netifaces.InterfaceType.AF_INET6]:
+            assert addresses.get(family) == interface["addresses"].get(family)
+
+
+@pytest.fixture
+def many_interfaces() -> Iterator[List[str]]:
+    names = [f"nfb{i}" for i in range(BENCHMARK_PAIRS)]
+
+    setup = []
+    for i, name in enumerate(names):
+        # Without IPv6 link-local addresses, the system settles as soon as the links are up
+        setup.append(f"link add {name} type veth peer name {name}p")
+        setup.append(f"link set {name} addrgenmode none")
+        setup.append(f"link set {name}p addrgenmode none")
+        setup.append(f"addr add 10.{100 + i // 65536}.{i // 256 % 256}.{i % 256}/32 dev {name}")
+        setup.append(f"link set {name} up")
+        setup.append(f"link set {name}p up")
+
+    # Deleting one end of a veth pair deletes the other
+    with ip_batch(setup, cleanup=[f"link del {name}" for name in names]):
+        for name in names:
+            netifaces.wait_for_interface(name, netifaces.WaitCondition.Up, timeout=10)
+        yield names
+
+
+def _timed(query: Callable[[], object]) -> float:
+    start = time.perf_counter()
+    for _ in range(BENCHMARK_ROUNDS):
+        query()
+    return (time.perf_counter() - start) / BENCHMARK_ROUNDS
+
+
+@linux_only  # type: ignore[misc]
+@pytest.mark.skipif(BENCHMARK_PAIRS <= 0, reason="Set NETIFACES_BENCHMARK_PAIRS to run")  # type: ignore[misc]
+@pytest.mark.skipif(platform.system() == "Linux" and os.geteuid() != 0, reason="Needs root")  # type: ignore[misc]
+def test_benchmark_filtered_dumps(many_interfaces: List[str]) -> None:
+    target = many_interfaces[len(many_interfaces) // 2]
+
+    # Both sides only dump routes, the full dump being filtered afterwards
+    def full_routes() -> List[netifaces.RouteEntry]:
+        return [route for route in netifaces.routes() if _goes_through(route, target)]
+
+    assert sorted(map(_route_key, netifaces.interface_routes(target))) == sorted(map(_route_key, full_routes()))
+
+    filtered_routes = _timed(lambda: netifaces.interface_routes(target))
+    dumped_routes = _timed(full_routes)
+
+    assert filtered_routes < dumped_routes
@@ -0,0 +1,69 @@
+"""
+Helpers shared by the tests.
+"""
+
+import json
+import os
+import platform
+import subprocess
+import sys
+from contextlib import contextmanager
+from typing import Any, Iterator, Optional, Sequence
+
+import netifaces
+import pytest
+
+linux_only = pytest.mark.skipif(platform.system() != "Linux", reason="Linux only")
+root_only = pytest.mark.skipif(platform.system() != "Linux" or os.geteuid() != 0, reason="Needs root")
+
+
+def in_namespace(namespace: str, code: str, setup: str = "") -> Any:
+    """
+    Evaluate a Python expression in a network namespace, after running the statements of
+    `setup` there, and return its value through JSON
+    """
+
+    env = dict(os.environ, PYTHONPATH=os.path.dirname(os.path.dirname(netifaces.__file__)))
+    result = subprocess.run(
+        [
+            "ip",
+            "netns",
+            "exec",
+            namespace,
+            sys.executable,
+            "-c",
+            f"import json, netifaces.compat\n{setup}\nprint(json.dumps({code}))",
+        ],
+        env=env,
+        capture_output=True,
+        text=True,
+        check=True,
+    )
+    return json.loads(result.stdout)
+
+
+@contextmanager
+def ip_batch(
+    commands: Sequence[str], namespace: Optional[str] = None, cleanup: Sequence[str] = (), sysctls: Sequence[str] = ()
+) -> Iterator[None]:
+    """
+    Run `ip` commands in a batch, in a new network namespace when one is named, after
+    setting the `sysctls` there, and undo them on exit: the `cleanup` commands run, past
+    the ones which fail, and the namespace is deleted
+    """
+
+    namespaced = ["-n", namespace] if namespace is not None else []
+    try:
+        if namespace is not None:
+            subprocess.run(["ip", "netns", "add", namespace], check=True)
+            for sysctl in sysctls:
+                subprocess.run(["ip", "netns", "exec", namespace, "sysctl", "-qw", sysctl], check=True)
+        subprocess.run(["ip", *namespaced, "-batch", "-"], input="\n".join(commands), text=True, check=True)
+        yield
+    finally:
+        if cleanup:
+            subprocess.run(
+                ["ip", *namespaced, "-force", "-batch", "-"], input="\n".join(cleanup), text=True, check=False
+            )
+        if namespace is not None:
+            subprocess.run(["ip", "netns", "del", namespace], check=False)
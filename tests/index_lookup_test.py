@@ -0,0 +1,75 @@
+from typing import Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfindex"
+
+
+def test_name_index_round_trip() -> None:
+    for index, name in netifaces.interfaces_by_index().items():
+        assert netifaces.if_nametoindex(name) == index
+        assert netifaces.if_indextoname(index) == name
+
+
+def test_queries_accept_an_index() -> None:
+    for index, name in netifaces.interfaces_by_index().items():
+        assert netifaces.ifaddresses(index) == netifaces.ifaddresses(name)
+        assert netifaces.interface_is_up(index) == netifaces.interface_is_up(name)
+
+
+def test_unknown_interface() -> None:
+    unused = max(netifaces.interfaces_by_index()) + 1000
+
+    with pytest.raises(RuntimeError):
+        netifaces.if_indextoname(unused)
+    with pytest.raises(RuntimeError):
+        netifaces.if_nametoindex("does-not-exist0")
+    with pytest.raises(RuntimeError):
+        netifaces.ifaddresses(unused)
+
+
+@linux_only  # type: ignore[misc]
+def test_session_and_cache_accept_an_index() -> None:
+    index = netifaces.if_nametoindex("lo")
+    expected = netifaces.ifaddresses("lo")
+
+    session = netifaces.NetlinkSession()
+    assert session.ifaddresses(index) == expected
+    assert session.interface_routes(index) == netifaces.interface_routes("lo")
+
+    netifaces.enable_cache()
+    try:
+        assert netifaces.ifaddresses(index) == expected
+    finally:
+        netifaces.disable_cache()
+
+
+@pytest.fixture
+def altnamed_link() -> Iterator[str]:
+    commands = [
+        "link add nfi0 type veth peer name nfi0p",
+        "link property add dev nfi0 altname nfi-short",
+        "link property add dev nfi0 altname nfi-an-altname-longer-than-ifnamsiz",
+        "addr add 10.78.0.1/24 dev nfi0",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield "nfi0"
+
+
+def lookups(name: str) -> str:
+    """The expression looking a link up by the given name, in every way"""
+
+    return f"[netifaces.if_nametoindex({name!r}), netifaces.ifaddresses({name!r}), netifaces.interface_is_up({name!r})]"
+
+
+@root_only  # type: ignore[misc]
+def test_lookups_through_altnames(altnamed_link: str) -> None:
+    found = in_namespace(NAMESPACE, lookups(altnamed_link))
+
+    for altname in ["nfi-short", "nfi-an-altname-longer-than-ifnamsiz"]:
+        assert in_namespace(NAMESPACE, lookups(altname)) == found
+
+    assert in_namespace(NAMESPACE, f"netifaces.if_indextoname({found[0]})") == altnamed_link
@@ -0,0 +1,101 @@
+import subprocess
+from typing import Any, Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfmultipath"
+
+RTNH_F_LINKDOWN = 16
+
+
+@linux_only  # type: ignore[misc]
+def test_every_route_has_its_nexthops() -> None:
+    for route in netifaces.routes():
+        if len(route["nexthops"]) == 1:
+            hop = route["nexthops"][0]
+            assert (hop["gateway"], hop["index"], hop["weight"]) == (route["gateway"], route["index"], 1)
+        for hop in route["nexthops"]:
+            assert hop["weight"] >= 1
+
+
+@pytest.fixture
+def multipath() -> Iterator[None]:
+    # A default route balanced over two uplinks, and a route through a nexthop group
+    commands = [
+        "link set lo up",
+        "link add nfm0 type veth peer name nfm0p",
+        "link add nfm1 type veth peer name nfm1p",
+        "addr add 10.87.0.1/24 dev nfm0",
+        "addr add 10.88.0.1/24 dev nfm1",
+        "link set nfm0 up",
+        "link set nfm0p up",
+        "link set nfm1 up",
+        "link set nfm1p up",
+        "route add default nexthop via 10.87.0.2 dev nfm0 weight 1 nexthop via 10.88.0.2 dev nfm1 weight 3",
+        "nexthop add id 11 via 10.87.0.3 dev nfm0",
+        "nexthop add id 12 via 10.88.0.3 dev nfm1",
+        "nexthop add id 20 group 11,2/12,5",
+        "route add 10.99.0.0/16 nhid 20",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield
+
+
+def paths(route: Any) -> list:
+    return [(hop["gateway"], hop["interface"], hop["weight"]) for hop in route["nexthops"]]
+
+
+@root_only  # type: ignore[misc]
+def test_multipath_routes(multipath: None) -> None:
+    routes = {route["destination"]: route for route in in_namespace(NAMESPACE, "netifaces.routes(netifaces.AF_INET)")}
+
+    default = routes[None]
+    assert (default["gateway"], default["interface"], default["nhid"]) == (None, None, None)
+    assert paths(default) == [("10.87.0.2", "nfm0", 1), ("10.88.0.2", "nfm1", 3)]
+
+    grouped = routes["10.99.0.0"]
+    assert grouped["nhid"] == 20
+    assert paths(grouped) == [("10.87.0.3", "nfm0", 2), ("10.88.0.3", "nfm1", 5)]
+
+    through_nfm1 = in_namespace(NAMESPACE, "netifaces.interface_routes('nfm1', netifaces.AF_INET)")
+    assert [paths(route) for route in through_nfm1 if route["destination"] in [None, "10.99.0.0"]] == [
+        paths(default),
+        paths(grouped),
+    ]
+
+
+@root_only  # type: ignore[misc]
+def test_multipath_gateways(multipath: None) -> None:
+    expected = [
+        ["10.87.0.2", "nfm0", True],
+        ["10.88.0.2", "nfm1", True],
+        ["10.87.0.3", "nfm0"],
+        ["10.88.0.3", "nfm1"],
+    ]
+
+    gateways = in_namespace(NAMESPACE, "netifaces.gateways(old_api=True)")
+    assert sorted(gateways["2"]) == sorted(expected)
+
+    from_ip_tool = in_namespace(
+        NAMESPACE, "__import__('netifaces.routing').routing.routes_parse_ip_tool('ip', old_api=True)"
+    )
+    assert sorted(from_ip_tool["2"]) == sorted(expected)
+
+    assert in_namespace(NAMESPACE, "netifaces.default_gateway(old_api=True)") == {"2": ["10.87.0.2", "nfm0"]}
+
+
+@root_only  # type: ignore[misc]
+def test_multipath_primary_interface(multipath: None) -> None:
+    primary = in_namespace(NAMESPACE, "netifaces.primary_interface()")
+    assert (primary["interface"], primary["gateway"], primary["source"]) == ("nfm0", "10.87.0.2", "10.87.0.1")
+
+    # A path whose interface lost its carrier is skipped
+    subprocess.run(["ip", "-n", NAMESPACE, "link", "set", "nfm0p", "down"], check=True)
+    default = in_namespace(NAMESPACE, "netifaces.default_routes(netifaces.InterfaceType.AF_INET)")[0]
+    assert [hop["flags"] & RTNH_F_LINKDOWN for hop in default["nexthops"]] == [RTNH_F_LINKDOWN, 0]
+
+    primary = in_namespace(NAMESPACE, "netifaces.primary_interface()")
+    assert (primary["interface"], primary["gateway"], primary["source"]) == ("nfm1", "10.88.0.2", "10.88.0.1")
@@ -0,0 +1,106 @@
+import ipaddress
+from typing import Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfobjects"
+
+IFA_F_PERMANENT = 0x80
+
+
+def test_interfaces_agree_with_snapshot() -> None:
+    interfaces = netifaces.all_interfaces()
+
+    assert [interface.as_dict() for interface in interfaces] == netifaces.snapshot()
+    for interface in interfaces:
+        assert (interface.name, interface.index) == (interface.as_dict()["name"], interface.as_dict()["index"])
+
+        entries = [address.as_dict() for address in interface.addresses]
+        for family_entries in interface.as_dict()["addresses"].values():
+            for entry in family_entries:
+                assert entry in entries
+
+
+def test_addresses_are_ipaddress_objects() -> None:
+    for interface in netifaces.all_interfaces():
+        for address in interface.addresses:
+            if address.family not in [netifaces.AF_INET, netifaces.AF_INET6]:
+                assert address.ip == address.as_dict()["addr"]
+                assert address.ip_interface is None
+                continue
+
+            assert address.ip == ipaddress.ip_address(address.as_dict()["addr"])
+            if address.prefixlen is not None:
+                assert address.ip_interface.ip == address.ip
+                assert address.ip_interface.network.prefixlen == address.prefixlen
+                assert address.netmask == address.ip_interface.netmask
+
+
+def test_get_interface() -> None:
+    for interface in netifaces.all_interfaces():
+        by_index = netifaces.get_interface(interface.index)
+        assert by_index.as_dict() == interface.as_dict()
+        assert netifaces.get_interface(interface.name).index == interface.index
+
+
+def test_get_unknown_interface() -> None:
+    with pytest.raises(RuntimeError, match="Failed to find an interface"):
+        netifaces.get_interface("nfobjects-none")
+
+
+@linux_only  # type: ignore[misc]
+def test_routes_agree_with_dicts() -> None:
+    routes = netifaces.all_routes()
+
+    assert [route.as_dict() for route in routes] == netifaces.routes()
+    for route in routes:
+        if route.family not in [netifaces.AF_INET, netifaces.AF_INET6]:
+            continue
+
+        assert route.destination.prefixlen == route.prefixlen
+        if route.as_dict()["destination"] is not None:
+            assert route.destination.network_address == ipaddress.ip_address(route.as_dict()["destination"])
+        assert [hop.as_dict() for hop in route.nexthops] == route.as_dict()["nexthops"]
+
+
+@pytest.fixture
+def addressed() -> Iterator[None]:
+    commands = [
+        "link add nfo0 address 02:00:00:00:0b:01 mtu 1280 type veth peer name nfo0p",
+        "addr add 10.66.0.1/23 brd + dev nfo0",
+        "addr add fd66::1/56 dev nfo0 nodad",
+        "link set nfo0 up",
+        "link set nfo0p up",
+        "route add 10.67.0.0/16 via 10.66.0.2",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield
+
+
+@root_only  # type: ignore[misc]
+def test_interface_object(addressed: None) -> None:
+    interface = in_namespace(
+        NAMESPACE,
+        "(lambda i: [i.name, i.mtu, i.mac, i.up,"
+        " [[a.family, str(a.ip), a.prefixlen, str(a.broadcast), a.flags, str(a.ip_interface)] for a in i.addresses]])"
+        "(netifaces.get_interface('nfo0'))"
+    )
+    name, mtu, mac, up, addresses = interface
+    assert (name, mtu, mac, up) == ("nfo0", 1280, "02:00:00:00:0b:01", True)
+
+    assert addresses[0] == [netifaces.AF_PACKET, "02:00:00:00:0b:01", None, "ff:ff:ff:ff:ff:ff", 0, "None"]
+    assert addresses[1] == [netifaces.AF_INET, "10.66.0.1", 23, "10.66.1.255", IFA_F_PERMANENT, "10.66.0.1/23"]
+    assert addresses[2][:3] + addresses[2][5:] == [netifaces.AF_INET6, "fd66::1", 56, "fd66::1/56"]
+
+
+@root_only  # type: ignore[misc]
+def test_route_object(addressed: None) -> None:
+    route = in_namespace(
+        NAMESPACE,
+        "[[str(r.destination), str(r.gateway), r.interface, [str(h.gateway) for h in r.nexthops]]"
+        " for r in netifaces.all_routes(netifaces.InterfaceType.AF_INET) if r.gateway is not None]"
+    )
+    assert route == [["10.67.0.0/16", "10.66.0.2", "nfo0", ["10.66.0.2"]]]
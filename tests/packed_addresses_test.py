@@ -0,0 +1,53 @@
+import ipaddress
+import socket
+
+import netifaces
+import pytest
+
+IP_FAMILIES = [netifaces.InterfaceType.AF_INET, netifaces.InterfaceType.AF_INET6]
+
+
+def packed(family: int, text: str) -> bytes:
+    if family == netifaces.InterfaceType.AF_PACKET:
+        return bytes.fromhex(text.replace(":", ""))
+    return socket.inet_pton(family, text.split("%")[0])
+
+
+def test_same_addresses_as_ifaddresses() -> None:
+    for if_name in netifaces.interfaces():
+        addresses = netifaces.ifaddresses(if_name)
+        packed_addresses = netifaces.packed_addresses(if_name)
+
+        for family in IP_FAMILIES + [netifaces.InterfaceType.AF_PACKET]:
+            entries = addresses.get(family, [])
+            packed_entries = packed_addresses.get(family, [])
+            assert len(packed_entries) == len(entries)
+
+            for entry, packed_entry in zip(entries, packed_entries):
+                assert packed_entry["addr"] == packed(family, entry["addr"])
+                for key in ["broadcast", "peer"]:
+                    assert packed_entry.get(key) == (packed(family, entry[key]) if key in entry else None)
+
+                if family in IP_FAMILIES and "mask" in entry:
+                    netmask = ipaddress.ip_address(entry["mask"])
+                    assert packed_entry["prefixlen"] == bin(int(netmask)).count("1")
+
+
+def test_address_sizes() -> None:
+    sizes = {netifaces.InterfaceType.AF_INET: 4, netifaces.InterfaceType.AF_INET6: 16}
+
+    for if_name in netifaces.interfaces():
+        for family, entries in netifaces.packed_addresses(if_name).items():
+            for entry in entries:
+                assert isinstance(entry["addr"], bytes)
+                assert len(entry["addr"]) == sizes.get(family, len(entry["addr"]))
+
+
+def test_lookup_by_index() -> None:
+    for index, if_name in netifaces.interfaces_by_index(netifaces.InterfaceDisplay.MachineReadable).items():
+        assert netifaces.packed_addresses(index) == netifaces.packed_addresses(if_name)
+
+
+def test_unknown_interface() -> None:
+    with pytest.raises(RuntimeError):
+        netifaces.packed_addresses("nfpacked-none")
@@ -0,0 +1,91 @@
+from typing import Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+from netifaces.routing import routes_from_entries, routes_from_proc
+
+NAMESPACE = "nfproc"
+
+RTF_UP = 0x1
+RTF_GATEWAY = 0x2
+RTN_UNICAST = 1
+RT_TABLE_MAIN = 254
+
+
+@linux_only  # type: ignore[misc]
+def test_same_ipv4_routes_as_netlink() -> None:
+    from_proc = netifaces.proc_routes(netifaces.InterfaceType.AF_INET)
+    from_netlink = netifaces.routes(netifaces.InterfaceType.AF_INET)
+
+    assert sorted(
+        (route["destination"] or "", route["prefixlen"], route["gateway"] or "", route["interface"])
+        for route in from_proc
+    ) == sorted(
+        (route["destination"] or "", route["prefixlen"], route["gateway"] or "", route["interface"] or "")
+        for route in from_netlink
+        if route["table"] == RT_TABLE_MAIN and route["type"] == RTN_UNICAST
+    )
+
+    gateways = routes_from_proc(from_proc, old_api=True)
+    assert {family: sorted(entries) for family, entries in gateways.items()} == {
+        family: sorted(entries) for family, entries in routes_from_entries(from_netlink, old_api=True).items()
+    }
+
+
+@linux_only  # type: ignore[misc]
+def test_columns() -> None:
+    routes = netifaces.proc_routes()
+
+    assert routes == netifaces.proc_routes(netifaces.InterfaceType.AF_INET) + netifaces.proc_routes(
+        netifaces.InterfaceType.AF_INET6
+    )
+    for route in routes:
+        assert (route["gateway"] is not None) == bool(route["flags"] & RTF_GATEWAY)
+        assert (route["destination"] is None) == (route["prefixlen"] == 0)
+        # Only /proc/net/route has the MTU, window and IRTT columns
+        assert (route["mtu"] is None) == (route["family"] == netifaces.AF_INET6)
+
+
+@pytest.fixture
+def tuned_routes() -> Iterator[None]:
+    commands = [
+        "link set lo up",
+        "link add nfr0 type veth peer name nfr0p",
+        "addr add 10.96.0.1/24 dev nfr0",
+        "addr add fd96::1/64 dev nfr0 nodad",
+        "link set nfr0 up",
+        "link set nfr0p up",
+        "route add default via 10.96.0.2 metric 7",
+        "route add 10.97.0.0/16 via 10.96.0.3 advmss 1360 window 8192 rtt 80ms",
+        "route add default via fd96::2 metric 9",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield
+
+
+@root_only  # type: ignore[misc]
+def test_decoded_columns(tuned_routes: None) -> None:
+    routes = in_namespace(NAMESPACE, "netifaces.proc_routes()")
+    # IPv6 has a catch-all "unreachable" route through lo, which is not up
+    by_destination = {(route["family"], route["destination"]): route for route in routes if route["flags"] & RTF_UP}
+
+    default = by_destination[(netifaces.AF_INET, None)]
+    assert (default["gateway"], default["interface"], default["metric"]) == ("10.96.0.2", "nfr0", 7)
+    assert default["flags"] == RTF_UP | RTF_GATEWAY
+
+    tuned = by_destination[(netifaces.AF_INET, "10.97.0.0")]
+    assert (tuned["prefixlen"], tuned["gateway"]) == (16, "10.96.0.3")
+    # The MTU column is the advertised MSS plus the size of the IP and TCP headers
+    assert (tuned["mtu"], tuned["window"], tuned["irtt"]) == (1400, 8192, 80)
+
+    default6 = by_destination[(netifaces.AF_INET6, None)]
+    assert (default6["gateway"], default6["interface"], default6["metric"]) == ("fd96::2", "nfr0", 9)
+
+    # Without netlink nor the ip tool, gateways() falls back to the route files
+    gateways = in_namespace(
+        NAMESPACE, "__import__('netifaces.routing').routing.routes_from_proc(netifaces.proc_routes(), old_api=True)"
+    )
+    assert sorted(gateways["2"]) == [["10.96.0.2", "nfr0", True], ["10.96.0.3", "nfr0"]]
+    assert gateways["10"] == [["fd96::2", "nfr0", True]]
@@ -0,0 +1,140 @@
+import json
+import platform
+import subprocess
+import sys
+from typing import Iterator, List, Set, Tuple
+
+import netifaces
+import pytest
+from helpers import linux_only
+
+# The audit architecture and the number of socket(2), for the seccomp filter
+SECCOMP_SYSCALLS = {"x86_64": (0xC000003E, 41), "aarch64": (0xC00000B7, 198)}
+
+# Forbid netlink sockets with EPERM, like some sandboxes do, then query the interfaces
+FORBID_NETLINK = """
+import ctypes, json, struct, sys
+import netifaces
+
+arch, socket_nr = int(sys.argv[1]), int(sys.argv[2])
+AF_NETLINK = 16
+
+
+def statement(code, k, jt=0, jf=0):
+    return struct.pack("HBBI", code, jt, jf, k)
+
+
+program = b"".join(
+    [
+        statement(0x20, 4),  # load the architecture
+        statement(0x15, arch, 0, 5),
+        statement(0x20, 0),  # load the syscall number
+        statement(0x15, socket_nr, 0, 3),
+        statement(0x20, 16),  # load the address family
+        statement(0x15, AF_NETLINK, 0, 1),
+        statement(0x06, 0x00050000 | 1),  # fail with EPERM
+        statement(0x06, 0x7FFF0000),  # allow
+    ]
+)
+
+
+class SockFprog(ctypes.Structure):
+    _fields_ = [("len", ctypes.c_ushort), ("filter", ctypes.c_void_p)]
+
+
+buffer = ctypes.create_string_buffer(program)
+fprog = SockFprog(len(program) // 8, ctypes.addressof(buffer))
+libc = ctypes.CDLL(None, use_errno=True)
+if libc.prctl(38, 1, 0, 0, 0) != 0 or libc.prctl(22, 2, ctypes.byref(fprog), 0, 0) != 0:
+    sys.exit(77)
+
+before = netifaces.get_backend()
+try:
+    netifaces.interface_is_up("nfprocfs-none")
+except netifaces.InterfaceNotFoundError as error:
+    failed = error.backend
+names = netifaces.interfaces()
+print(json.dumps([before, netifaces.get_backend(), failed, names, netifaces.interface_is_up("lo")]))
+"""
+
+
+@pytest.fixture(autouse=True)
+def default_backend() -> Iterator[None]:
+    yield
+    netifaces.set_backend(None)
+
+
+def ipv6_addresses(if_name: str) -> Set[Tuple[str, str]]:
+    return {(entry["addr"], entry["mask"]) for entry in netifaces.ifaddresses(if_name).get(netifaces.AF_INET6, [])}
+
+
+def ipv4_addresses(if_name: str) -> List[Tuple[str, str]]:
+    return [(entry["addr"], entry["mask"]) for entry in netifaces.ifaddresses(if_name).get(netifaces.AF_INET, [])]
+
+
+@linux_only  # type: ignore[misc]
+def test_procfs_agrees_with_netlink() -> None:
+    netifaces.set_backend("netlink")
+    netlink_interfaces = netifaces.interfaces_by_index()
+    netlink_snapshot = [(i.name, i.index, i.flags, i.up, i.mtu) for i in netifaces.all_interfaces()]
+    netlink_addresses = {
+        name: (
+            netifaces.ifaddresses(name).get(netifaces.AF_PACKET),
+            ipv6_addresses(name),
+            ipv4_addresses(name),
+        )
+        for name in netlink_interfaces.values()
+    }
+
+    netifaces.set_backend("procfs")
+    assert netifaces.interfaces_by_index() == netlink_interfaces
+    assert [(i.name, i.index, i.flags, i.up, i.mtu) for i in netifaces.all_interfaces()] == netlink_snapshot
+
+    for name, (hardware, ipv6, ipv4) in netlink_addresses.items():
+        assert netifaces.ifaddresses(name).get(netifaces.AF_PACKET) == hardware
+        assert ipv6_addresses(name) == ipv6
+        # The addresses of point-to-point links cannot be found
+        assert set(ipv4_addresses(name)) <= set(ipv4)
+
+
+@linux_only  # type: ignore[misc]
+def test_procfs_lookups() -> None:
+    netifaces.set_backend("procfs")
+
+    assert netifaces.interface_is_up("lo")
+    assert netifaces.ifaddresses("lo")[netifaces.AF_INET][0]["addr"] == "127.0.0.1"
+    assert netifaces.if_indextoname(netifaces.if_nametoindex("lo")) == "lo"
+    assert netifaces.interfaces(has_address=netifaces.InterfaceType.AF_INET)[0] == "lo"
+
+    with pytest.raises(netifaces.InterfaceNotFoundError) as error:
+        netifaces.interface_is_up("nfprocfs-none")
+    assert error.value.backend == "procfs"
+
+
+@linux_only  # type: ignore[misc]
+def test_procfs_neighbours() -> None:
+    netifaces.set_backend("procfs")
+
+    assert all(entry["family"] == netifaces.AF_INET for entry in netifaces.neighbours())
+    with pytest.raises(netifaces.UnsupportedOperationError):
+        netifaces.neighbours(netifaces.InterfaceType.AF_INET6)
+
+
+@pytest.mark.skipif(  # type: ignore[misc]
+    platform.system() != "Linux" or platform.machine() not in SECCOMP_SYSCALLS, reason="Linux x86_64 and aarch64 only"
+)
+def test_fallback_when_netlink_is_forbidden() -> None:
+    arch, socket_nr = SECCOMP_SYSCALLS[platform.machine()]
+    result = subprocess.run(
+        [sys.executable, "-c", FORBID_NETLINK, str(arch), str(socket_nr)], capture_output=True, text=True
+    )
+    if result.returncode == 77:
+        pytest.skip("seccomp filters cannot be installed")
+    assert result.returncode == 0, result.stderr
+
+    before, after, failed, names, lo_up = json.loads(result.stdout)
+    assert (before, after) == ("netlink", "procfs")
+    # The first query fell back to procfs, which raised the error
+    assert failed == "procfs"
+    assert names == netifaces.interfaces()
+    assert lo_up
@@ -0,0 +1,98 @@
+from typing import Iterator
+
+import netifaces
+import pytest
+from helpers import in_namespace, ip_batch, linux_only, root_only
+
+NAMESPACE = "nfroute"
+
+RTN_UNICAST = 1
+RTN_LOCAL = 2
+
+
+@linux_only  # type: ignore[misc]
+def test_local_addresses() -> None:
+    # The local routes of an interface are removed while it is down
+    for name in netifaces.interfaces(up=True):
+        for entry in netifaces.ifaddresses(name).get(netifaces.AF_INET, []):
+            route = netifaces.route_get(entry["addr"])
+
+            assert route is not None
+            assert route["type"] == RTN_LOCAL
+            assert route["interface"] == "lo"
+            assert route["source"] == entry["addr"]
+
+
+@linux_only  # type: ignore[misc]
+def test_forced_output_interface() -> None:
+    route = netifaces.route_get("127.0.0.1", oif="lo")
+    assert route is not None
+    assert route["interface"] == "lo"
+    assert netifaces.route_get("127.0.0.1", oif=route["index"]) == route
+
+
+@linux_only  # type: ignore[misc]
+def test_invalid_arguments() -> None:
+    for kwargs in [
+        {"dest": "not an address"},
+        {"dest": "10.0.0.1", "source": "::1"},
+        {"dest": "10.0.0.1", "oif": "does-not-exist0"},
+        {"dest": "fe80::1%does-not-exist0"},
+    ]:
+        with pytest.raises(RuntimeError):
+            netifaces.route_get(**kwargs)  # type: ignore[arg-type]
+
+
+@pytest.fixture
+def routed_link() -> Iterator[str]:
+    commands = [
+        "link add nfr0 type veth peer name nfr0p",
+        "addr add 10.81.0.1/24 dev nfr0",
+        "link set nfr0 up",
+        "link set nfr0p up",
+        "route add 10.82.0.0/16 via 10.81.0.2 metric 7",
+        "route add unreachable 10.83.0.0/16",
+        "route add 10.84.0.0/16 dev nfr0 table 81",
+        "rule add fwmark 0x51 table 81",
+    ]
+
+    with ip_batch(commands, namespace=NAMESPACE):
+        yield "nfr0"
+
+
+@root_only  # type: ignore[misc]
+def test_route_through_gateway(routed_link: str) -> None:
+    route = in_namespace(NAMESPACE, "netifaces.route_get('10.82.3.4')")
+
+    assert route is not None
+    assert route["destination"] == "10.82.0.0"
+    assert route["prefixlen"] == 16
+    assert route["gateway"] == "10.81.0.2"
+    assert route["interface"] == routed_link
+    assert route["source"] == "10.81.0.1"
+    assert route["metric"] == 7
+    assert route["table"] == 254
+    assert route["type"] == RTN_UNICAST
+
+
+@root_only  # type: ignore[misc]
+def test_unreachable(routed_link: str) -> None:
+    assert in_namespace(NAMESPACE, "netifaces.route_get('10.83.0.1')") is None
+
+
+@root_only  # type: ignore[misc]
+def test_policy_routing(routed_link: str) -> None:
+    marked = in_namespace(NAMESPACE, "netifaces.route_get('10.84.0.1', mark=0x51)")
+    assert marked is not None
+    assert marked["table"] == 81
+    assert marked["interface"] == routed_link
+
+    unmarked = in_namespace(NAMESPACE, "netifaces.route_get('10.84.0.1')")
+    assert unmarked is None or unmarked["table"] != 81
+
+
+@root_only  # type: ignore[misc]
+def test_given_source(routed_link: str) -> None:
+    route = in_namespace(NAMESPACE, "netifaces.route_get('10.82.3.4', source='10.81.0.1')")
+    assert route is not None
+    assert route["source"] == "10.81.0.1"
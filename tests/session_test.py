@@ -0,0 +1,63 @@
+import netifaces
+import pytest
+from helpers import linux_only
+
+
+@linux_only  # type: ignore[misc]
+def test_session_agrees_with_module_functions() -> None:
+    session = netifaces.NetlinkSession()
+
+    assert set(session.interfaces()) == set(netifaces.interfaces_by_index().values())
+
+    for interface in session.interfaces():
+        assert session.interface_is_up(interface) == netifaces.interface_is_up(interface)
+
+        addresses = session.ifaddresses(interface)
+        for family in [netifaces.InterfaceType.AF_PACKET, netifaces.InterfaceType.AF_INET6]:
+            assert addresses.get(family) == netifaces.ifaddresses(interface).get(family)
+
+
+@linux_only  # type: ignore[misc]
+def test_session_can_be_reused() -> None:
+    session = netifaces.NetlinkSession()
+
+    first = session.interfaces()
+    for _ in range(100):
+        assert session.interfaces() == first
+
+
+@linux_only  # type: ignore[misc]
+def test_session_routes_and_neighbours() -> None:
+    session = netifaces.NetlinkSession()
+    interfaces = set(session.interfaces())
+
+    routes = session.routes(netifaces.InterfaceType.AF_INET)
+    assert all(route["family"] == netifaces.AF_INET for route in routes)
+
+    # The loopback address is always routed through the local table
+    assert any(route["destination"] == "127.0.0.1" for route in routes)
+
+    for neighbour in session.neighbours():
+        assert neighbour["interface"] in interfaces
+
+
+@linux_only  # type: ignore[misc]
+def test_session_stats() -> None:
+    session = netifaces.NetlinkSession()
+    stats = session.stats()
+
+    assert set(stats) == set(session.interfaces())
+    for counters in stats.values():
+        assert counters["rx_bytes"] >= 0
+        assert counters["tx_packets"] >= 0
+
+
+@linux_only  # type: ignore[misc]
+def test_session_invalid_if_name() -> None:
+    session = netifaces.NetlinkSession()
+
+    with pytest.raises(Exception):
+        session.ifaddresses("arglebargle")
+
+    # The session remains usable after an error
+    assert len(session.interfaces())
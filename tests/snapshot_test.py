@@ -0,0 +1,37 @@
+import netifaces
+
+
+def test_snapshot_lists_every_interface() -> None:
+    snapshot = netifaces.snapshot()
+
+    assert {iface["name"] for iface in snapshot} == set(netifaces.interfaces_by_index().values())
+
+
+def test_snapshot_is_sorted_by_index() -> None:
+    indexes = [iface["index"] for iface in netifaces.snapshot()]
+
+    assert indexes == sorted(indexes)
+    assert len(set(indexes)) == len(indexes)
+
+
+def test_snapshot_agrees_with_individual_queries() -> None:
+    """
+    The snapshot of an interface holds the same information as the per-interface functions
+    """
+
+    by_index = netifaces.interfaces_by_index()
+
+    for iface in netifaces.snapshot():
+        assert by_index[iface["index"]] == iface["name"]
+        assert iface["up"] == netifaces.interface_is_up(iface["name"])
+
+        if not iface["addresses"]:
+            continue
+
+        addresses = netifaces.ifaddresses(iface["name"])
+        for family in [netifaces.InterfaceType.AF_PACKET, netifaces.InterfaceType.AF_INET6]:
+            assert iface["addresses"].get(family) == addresses.get(family)
+
+        # IPv4 addresses with a label (e.g. eth0:1) are listed under their link in the snapshot
+        for ipv4_settings in addresses.get(netifaces.InterfaceType.AF_INET, []):
+            assert ipv4_settings in iface["addresses"][netifaces.InterfaceType.AF_INET]
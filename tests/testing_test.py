@@ -0,0 +1,223 @@
+import ipaddress
+import json
+from pathlib import Path
+from typing import Any, Dict, Iterator, List
+
+import netifaces
+import pytest
+from helpers import linux_only
+from netifaces import testing
+
+HOST: Dict[str, Any] = {
+    "interfaces": [
+        {"name": "lo", "mac": "00:00:00:00:00:00", "addresses": ["127.0.0.1/8", "::1/128"]},
+        {
+            "name": "eth0",
+            "mac": "52:54:00:12:34:56",
+            "physical": True,
+            "addresses": [
+                "192.0.2.10/24",
+                {"address": "192.0.2.11/24", "label": "eth0:1"},
+                "2001:db8::10/64",
+            ],
+        },
+        {"name": "wg0", "mtu": 1420, "addresses": [{"address": "10.9.1.1/32", "peer": "10.9.1.2"}]},
+        {"name": "eth1", "up": False, "mac": "52:54:00:ab:cd:ef", "master": "eth0", "description": "Spare"},
+    ],
+    "routes": [
+        {"destination": "0.0.0.0/0", "gateway": "192.0.2.1", "interface": "eth0", "metric": 100},
+        {"destination": "0.0.0.0/0", "gateway": "10.9.1.2", "interface": "wg0", "metric": 50},
+        {"destination": "192.0.2.0/24", "interface": "eth0"},
+        {"destination": "::/0", "gateway": "fe80::1", "interface": "eth0", "metric": 1024},
+    ],
+}
+
+
+@pytest.fixture(autouse=True)
+def real_host() -> Iterator[None]:
+    yield
+    testing.unload_host()
+    netifaces.set_backend(None)
+
+
+def test_mock_interfaces() -> None:
+    testing.load_host(HOST)
+
+    assert netifaces.get_backend() == "mock"
+    assert netifaces.interfaces() == ["lo", "eth0", "wg0", "Spare"]
+    assert netifaces.interfaces(netifaces.InterfaceDisplay.MachineReadable, up=True) == ["lo", "eth0", "wg0"]
+    assert netifaces.interfaces(physical=True) == ["eth0"]
+    assert netifaces.interfaces(master="eth0") == ["Spare"]
+    assert netifaces.interfaces_by_index(has_address=netifaces.InterfaceType.AF_INET6) == {1: "lo", 2: "eth0"}
+    assert netifaces.if_nametoindex("wg0") == 3
+    assert netifaces.if_indextoname(4, netifaces.InterfaceDisplay.MachineReadable) == "eth1"
+
+    assert [netifaces.interface_is_up(name) for name in ["lo", "eth0", "wg0", "eth1"]] == [True, True, True, False]
+    assert netifaces.get_interface("wg0").mtu == 1420
+
+    with pytest.raises(netifaces.InterfaceNotFoundError) as error:
+        netifaces.interface_is_up("eth2")
+    assert error.value.backend == "mock"
+
+
+def test_mock_addresses() -> None:
+    testing.load_host(HOST)
+
+    assert netifaces.ifaddresses("eth0") == {
+        netifaces.AF_PACKET: [{"addr": "52:54:00:12:34:56", "broadcast": "ff:ff:ff:ff:ff:ff"}],
+        netifaces.AF_INET: [
+            {"addr": "192.0.2.10", "mask": "255.255.255.0", "broadcast": "192.0.2.255"},
+            {"addr": "192.0.2.11", "mask": "255.255.255.0", "broadcast": "192.0.2.255"},
+        ],
+        netifaces.AF_INET6: [{"addr": "2001:db8::10", "mask": "ffff:ffff:ffff:ffff::"}],
+    }
+    assert netifaces.ifaddresses("eth0:1") == {
+        netifaces.AF_INET: [{"addr": "192.0.2.11", "mask": "255.255.255.0", "broadcast": "192.0.2.255"}]
+    }
+    assert netifaces.ifaddresses("wg0") == {
+        netifaces.AF_INET: [{"addr": "10.9.1.1", "mask": "255.255.255.255", "peer": "10.9.1.2"}]
+    }
+    assert netifaces.packed_addresses("wg0") == {
+        netifaces.AF_INET: [{"addr": b"\n\t\x01\x01", "prefixlen": 32, "peer": b"\n\t\x01\x02"}]
+    }
+
+
+def test_mock_routes() -> None:
+    testing.load_host(HOST)
+
+    assert [route["interface"] for route in netifaces.default_routes()] == ["wg0", "eth0", "eth0"]
+    assert netifaces.gateways() == {
+        netifaces.InterfaceType.AF_INET: [("192.0.2.1", "eth0", True), ("10.9.1.2", "wg0", True)],
+        netifaces.InterfaceType.AF_INET6: [("fe80::1", "eth0", True)],
+    }
+    assert netifaces.default_gateway() == {
+        netifaces.InterfaceType.AF_INET: ("10.9.1.2", "wg0"),
+        netifaces.InterfaceType.AF_INET6: ("fe80::1", "eth0"),
+    }
+
+
+def test_mock_primary_interface_and_route_get() -> None:
+    testing.load_host(HOST)
+
+    primary = netifaces.primary_interface(netifaces.InterfaceType.AF_INET)
+    assert primary is not None
+    assert (primary["interface"], primary["gateway"], primary["source"]) == ("wg0", "10.9.1.2", "10.9.1.1")
+    primary = netifaces.primary_interface(netifaces.InterfaceType.AF_INET6)
+    assert primary is not None
+    assert (primary["interface"], primary["gateway"], primary["source"]) == ("eth0", "fe80::1", "2001:db8::10")
+
+    route = netifaces.route_get("192.0.2.50")
+    assert route is not None
+    assert (route["destination"], route["prefixlen"], route["interface"]) == ("192.0.2.0", 24, "eth0")
+    assert (route["gateway"], route["source"]) == (None, "192.0.2.10")
+    route = netifaces.route_get("198.51.100.1")
+    assert route is not None
+    assert (route["interface"], route["gateway"]) == ("wg0", "10.9.1.2")
+    route = netifaces.route_get("198.51.100.1", oif="eth0")
+    assert route is not None
+    assert (route["interface"], route["gateway"], route["source"]) == ("eth0", "192.0.2.1", "192.0.2.10")
+
+
+def test_mock_default_gateway_skips_down_interfaces() -> None:
+    testing.load_host(
+        {
+            "interfaces": [
+                {"name": "eth0", "addresses": ["192.0.2.10/24"]},
+                {"name": "wlan0", "up": False, "addresses": ["198.51.100.10/24"]},
+            ],
+            "routes": [
+                {"destination": "0.0.0.0/0", "gateway": "192.0.2.1", "interface": "eth0", "metric": 100},
+                {"destination": "0.0.0.0/0", "gateway": "198.51.100.1", "interface": "wlan0", "metric": 50},
+            ],
+        }
+    )
+
+    primary = netifaces.primary_interface(netifaces.InterfaceType.AF_INET)
+    assert primary is not None
+    assert primary["interface"] == "eth0"
+    assert netifaces.default_gateway() == {netifaces.InterfaceType.AF_INET: ("192.0.2.1", "eth0")}
+
+
+def test_mock_lookups() -> None:
+    testing.load_host(HOST)
+
+    assert netifaces.interface_for_address("192.0.2.11") == {
+        "interface": "eth0",
+        "index": 2,
+        "family": netifaces.AF_INET,
+        "prefixlen": 24,
+        "address": {"addr": "192.0.2.11", "mask": "255.255.255.0", "broadcast": "192.0.2.255"},
+    }
+    assert netifaces.interface_for_address("192.0.2.12") is None
+    assert [owner["interface"] for owner in netifaces.interfaces_in_network("192.0.0.0/16")] == ["eth0", "eth0"]
+    assert [route["destination"] for route in netifaces.interface_routes("eth0", netifaces.InterfaceType.AF_INET)] == [
+        None,
+        "192.0.2.0",
+    ]
+
+
+def test_mock_host_from_json(tmp_path: Path) -> None:
+    path = tmp_path / "host.json"
+    path.write_text(json.dumps(HOST))
+
+    with testing.mock_host(path):
+        assert netifaces.interfaces(netifaces.InterfaceDisplay.MachineReadable) == ["lo", "eth0", "wg0", "eth1"]
+    assert netifaces.get_backend() != "mock"
+
+
+@linux_only  # type: ignore[misc]
+def test_unload_restores_the_selection() -> None:
+    netifaces.set_backend("procfs")
+
+    with testing.mock_host(HOST):
+        assert netifaces.get_backend() == "mock"
+        netifaces.set_backend("netlink")
+        assert netifaces.get_backend() == "mock"
+
+    assert netifaces.get_backend() == "netlink"
+
+
+def test_invalid_host() -> None:
+    with pytest.raises(ValueError):
+        testing.load_host({"interfaces": [{"name": "eth0", "addresses": ["192.0.2.300/24"]}]})
+    with pytest.raises(ValueError):
+        testing.load_host({"interfaces": [{"name": "eth0", "mac": "52:54:zz"}]})
+    with pytest.raises(ValueError):
+        testing.load_host({"routes": [{"destination": "0.0.0.0/0", "interface": "eth0"}]})
+
+    assert netifaces.get_backend() != "mock"
+
+
+def describe(interface: Dict[str, Any]) -> Dict[str, Any]:
+    """The description of a real interface, from its snapshot."""
+
+    addresses: List[Dict[str, Any]] = []
+    for family in (netifaces.AF_INET, netifaces.AF_INET6):
+        for entry in interface["addresses"].get(family, []):
+            prefix_len = bin(int(ipaddress.ip_address(entry["mask"]))).count("1")
+            addresses.append(
+                {
+                    "address": f"{entry['addr']}/{prefix_len}",
+                    "broadcast": entry.get("broadcast"),
+                    "peer": entry.get("peer"),
+                }
+            )
+
+    hardware = interface["addresses"].get(netifaces.AF_PACKET, [{}])[0]
+    return {
+        "name": interface["name"],
+        "index": interface["index"],
+        "flags": interface["flags"],
+        "mtu": interface["mtu"],
+        "mac": hardware.get("addr"),
+        "addresses": addresses,
+    }
+
+
+@linux_only  # type: ignore[misc]
+def test_mock_looks_like_netlink() -> None:
+    netifaces.set_backend("netlink")
+    real = netifaces.snapshot(netifaces.InterfaceDisplay.MachineReadable)
+
+    with testing.mock_host({"interfaces": [describe(interface) for interface in real]}):
+        assert netifaces.snapshot(netifaces.InterfaceDisplay.MachineReadable) == real
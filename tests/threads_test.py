@@ -0,0 +1,79 @@
+import sys
+import sysconfig
+import threading
+from concurrent.futures import ThreadPoolExecutor
+from typing import Any, Callable, Dict, List
+
+import netifaces
+import pytest
+from helpers import linux_only
+
+free_threaded_only = pytest.mark.skipif(not sysconfig.get_config_var("Py_GIL_DISABLED"), reason="Needs no-GIL Python")
+
+THREADS = 16
+ROUNDS = 50
+
+
+def hammer(queries: Dict[str, Callable[[], Any]]) -> None:
+    """Run every query from many threads at once, and check they all get the single-threaded results"""
+
+    expected = {name: query() for name, query in queries.items()}
+    start = threading.Barrier(THREADS)
+
+    def worker() -> List[str]:
+        start.wait()
+        return [name for _ in range(ROUNDS) for name, query in queries.items() if query() != expected[name]]
+
+    with ThreadPoolExecutor(THREADS) as pool:
+        mismatches = [name for names in pool.map(lambda _: worker(), range(THREADS)) for name in names]
+
+    assert mismatches == []
+
+
+def test_concurrent_queries() -> None:
+    if_names = netifaces.interfaces()
+    queries: Dict[str, Callable[[], Any]] = {
+        "interfaces": netifaces.interfaces,
+        "interfaces_by_index": netifaces.interfaces_by_index,
+        "snapshot": netifaces.snapshot,
+        "get_interface": lambda: netifaces.get_interface(if_names[0]).as_dict(),
+    }
+    for if_name in if_names:
+        queries[f"ifaddresses {if_name}"] = lambda if_name=if_name: netifaces.ifaddresses(if_name)
+        queries[f"packed_addresses {if_name}"] = lambda if_name=if_name: netifaces.packed_addresses(if_name)
+        queries[f"interface_is_up {if_name}"] = lambda if_name=if_name: netifaces.interface_is_up(if_name)
+
+    hammer(queries)
+
+
+@linux_only  # type: ignore[misc]
+def test_concurrent_route_queries() -> None:
+    hammer(
+        {
+            "routes": netifaces.routes,
+            "gateways": netifaces.gateways,
+            "default_gateway": netifaces.default_gateway,
+            "proc_routes": netifaces.proc_routes,
+        }
+    )
+
+
+@linux_only  # type: ignore[misc]
+def test_shared_session() -> None:
+    session = netifaces.NetlinkSession()
+
+    hammer({"interfaces": session.interfaces, "routes": session.routes, "stats": lambda: sorted(session.stats())})
+
+
+def test_concurrent_errors() -> None:
+    def query() -> str:
+        with pytest.raises(netifaces.InterfaceNotFoundError) as error:
+            netifaces.ifaddresses("nfthreads-none")
+        return str(error.value.interface)
+
+    hammer({"unknown interface": query})
+
+
+@free_threaded_only  # type: ignore[misc]
+def test_gil_stays_disabled() -> None:
+    assert not sys._is_gil_enabled()  # type: ignore[attr-defined]
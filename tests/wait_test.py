@@ -0,0 +1,75 @@
+import time
+
+import netifaces
+import pytest
+from helpers import linux_only
+
+
+def _loopback_name() -> str:
+    for interface in netifaces.interfaces():
+        address_table = netifaces.ifaddresses(interface)
+        for ipv4_settings in address_table.get(netifaces.InterfaceType.AF_INET, []):
+            if ipv4_settings["addr"] == "127.0.0.1":
+                return interface
+
+    raise AssertionError("No loopback interface found")
+
+
+@linux_only  # type: ignore[misc]
+def test_wait_for_loopback_is_immediate() -> None:
+    """
+    The loopback interface is always up, so waiting on it returns straight away.
+    (Its addresses have a host scope, so it never meets WaitCondition.HasAddress.)
+    """
+
+    loopback = _loopback_name()
+
+    for condition in [netifaces.WaitCondition.Present, netifaces.WaitCondition.Up]:
+        start = time.monotonic()
+        assert netifaces.wait_for_interface(loopback, condition, timeout=5)
+        assert time.monotonic() - start < 1
+
+
+@linux_only  # type: ignore[misc]
+def test_wait_for_missing_interface_times_out() -> None:
+    start = time.monotonic()
+    assert not netifaces.wait_for_interface("arglebargle", netifaces.WaitCondition.Present, timeout=0.3)
+    assert time.monotonic() - start >= 0.3
+
+
+@linux_only  # type: ignore[misc]
+def test_wait_for_network_online_agrees_with_default_gateway() -> None:
+    """
+    A host with a default gateway is (most likely) online; a timeout of 0 only checks the
+    current state.
+    """
+
+    online = netifaces.wait_for_network_online(timeout=0)
+    assert isinstance(online, bool)
+
+    if not netifaces.default_gateway():
+        assert not online
+
+
+@linux_only  # type: ignore[misc]
+def test_wait_for_network_online_invalid_family() -> None:
+    with pytest.raises(netifaces.NetifacesError, match="family 17"):
+        netifaces.wait_for_network_online(netifaces.InterfaceType.AF_PACKET, timeout=0)
+
+
+@linux_only  # type: ignore[misc]
+def test_wait_with_an_infinite_timeout() -> None:
+    """
+    An infinite timeout, or one too long to be represented, waits forever.
+    """
+
+    loopback = _loopback_name()
+
+    assert netifaces.wait_for_interface(loopback, netifaces.WaitCondition.Up, timeout=float("inf"))
+    assert netifaces.wait_for_interface(loopback, netifaces.WaitCondition.Up, timeout=1e300)
+
+
+@linux_only  # type: ignore[misc]
+def test_wait_with_a_nan_timeout() -> None:
+    with pytest.raises(ValueError):
+        netifaces.wait_for_interface(_loopback_name(), netifaces.WaitCondition.Up, timeout=float("nan"))